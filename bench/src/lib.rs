@@ -0,0 +1,167 @@
+/*!
+Micro-benchmarks, run with `cargo +nightly bench`.
+*/
+
+#![feature(test)]
+
+extern crate test;
+
+use test::{black_box, Bencher};
+
+// Document with very long section headers
+fn long_sections(name_len: usize, trailing: &str) -> String {
+	let mut s = String::new();
+	for i in 0..100 {
+		s.push('[');
+		s.extend(core::iter::repeat_n('s', name_len));
+		s.push(']');
+		s.push_str(trailing);
+		s.push('\n');
+		s.push_str(&format!("key{}=value\n", i));
+	}
+	s
+}
+
+fn parse(b: &mut Bencher, document: &str) {
+	b.bytes = document.len() as u64;
+	b.iter(|| {
+		for item in ini_core::Parser::new(black_box(document)) {
+			black_box(item);
+		}
+	});
+}
+
+#[bench]
+fn long_section(b: &mut Bencher) {
+	parse(b, &long_sections(1000, ""));
+}
+
+#[bench]
+fn long_section_malformed(b: &mut Bencher) {
+	parse(b, &long_sections(1000, &"x".repeat(1000)));
+}
+
+#[bench]
+fn short_section(b: &mut Bencher) {
+	parse(b, &long_sections(8, ""));
+}
+
+// Document with properties of the given value length
+fn properties(value_len: usize) -> String {
+	let mut s = String::new();
+	for i in 0..1000 {
+		s.push_str(&format!("key{}=", i));
+		s.extend(core::iter::repeat_n('v', value_len));
+		s.push('\n');
+	}
+	s
+}
+
+#[bench]
+fn long_property(b: &mut Bencher) {
+	parse(b, &properties(1000));
+}
+
+#[bench]
+fn short_property(b: &mut Bencher) {
+	parse(b, &properties(12));
+}
+
+// Document with comments and properties
+fn commented(n: usize) -> String {
+	let mut s = String::new();
+	for i in 0..n {
+		s.push_str(&format!("; comment {}\nkey{}=value\n", i, i));
+	}
+	s
+}
+
+#[bench]
+fn commented_parser(b: &mut Bencher) {
+	let document = commented(1000);
+	b.bytes = document.len() as u64;
+	b.iter(|| {
+		for item in ini_core::Parser::new(black_box(&document)) {
+			black_box(item);
+		}
+	});
+}
+
+#[bench]
+fn commented_const_parser(b: &mut Bencher) {
+	let document = commented(1000);
+	b.bytes = document.len() as u64;
+	b.iter(|| {
+		for item in ini_core::ConstParser::<b';'>::new(black_box(&document)) {
+			black_box(item);
+		}
+	});
+}
+
+#[bench]
+fn commented_slurp_parser(b: &mut Bencher) {
+	let document = commented(1000);
+	b.bytes = document.len() as u64;
+	b.iter(|| {
+		let mut s = String::new();
+		std::io::Read::read_to_string(&mut black_box(document.as_bytes()), &mut s).unwrap();
+		for item in ini_core::Parser::new(&s) {
+			black_box(item);
+		}
+	});
+}
+
+#[bench]
+fn commented_io_parser(b: &mut Bencher) {
+	let document = commented(1000);
+	b.bytes = document.len() as u64;
+	b.iter(|| {
+		for item in ini_core::IoParser::new(black_box(document.as_bytes()), ini_core::Parser::new("")) {
+			black_box(item.unwrap());
+		}
+	});
+}
+
+#[bench]
+fn commented_slurp_owned(b: &mut Bencher) {
+	let document = commented(1000);
+	b.bytes = document.len() as u64;
+	b.iter(|| {
+		let mut s = String::new();
+		std::io::Read::read_to_string(&mut black_box(document.as_bytes()), &mut s).unwrap();
+		for item in ini_core::Parser::new(&s) {
+			black_box(item.to_owned());
+		}
+	});
+}
+
+#[bench]
+fn commented_count_items(b: &mut Bencher) {
+	let document = commented(1000);
+	b.bytes = document.len() as u64;
+	b.iter(|| ini_core::Parser::new(black_box(&document)).count_items());
+}
+
+// Document resembling a large configuration file
+fn big_document() -> String {
+	let mut s = String::new();
+	for i in 0..100 {
+		s.push_str(&format!("; Settings of section {}\n[section.{}]\n", i, i));
+		for j in 0..20 {
+			s.push_str(&format!("key_{} = value number {}\n", j, i * 20 + j));
+		}
+		s.push('\n');
+	}
+	s
+}
+
+#[bench]
+fn big_document_parser(b: &mut Bencher) {
+	let document = big_document();
+	b.bytes = document.len() as u64;
+	b.iter(|| {
+		for item in ini_core::Parser::new(black_box(&document)).auto_trim(true) {
+			black_box(item);
+		}
+	});
+}
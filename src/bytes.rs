@@ -0,0 +1,1448 @@
+/*!
+Byte string parser.
+
+Parses documents whose encoding isn't known to be UTF-8.
+The elements are returned as raw byte slices of the input.
+*/
+
+use core::ops::Range;
+use crate::{parse, EmptySectionPolicy, ErrorKind, Newline, ParseError, Separator, Trim};
+
+/// Ini element over raw bytes.
+///
+/// Mirrors [`Item`](crate::Item) but borrows byte slices instead of string slices.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ByteItem<'a> {
+	/// Syntax error.
+	///
+	/// See [`Item::Error`](crate::Item::Error).
+	Error(&'a [u8]),
+
+	/// Section header element.
+	///
+	/// ```
+	/// assert_eq!(
+	/// 	ini_core::BytesParser::new(b"[Section]").nth(1),
+	/// 	Some(ini_core::ByteItem::Section(b"Section")));
+	/// ```
+	Section(&'a [u8]),
+
+	/// End of section.
+	///
+	/// See [`Item::SectionEnd`](crate::Item::SectionEnd).
+	SectionEnd,
+
+	/// Property element.
+	///
+	/// ```
+	/// assert_eq!(
+	/// 	ini_core::BytesParser::new(b"Key=\xff").next(),
+	/// 	Some(ini_core::ByteItem::Property(b"Key", Some(b"\xff"))));
+	/// ```
+	Property(&'a [u8], Option<&'a [u8]>),
+
+	/// Comment.
+	Comment(&'a [u8]),
+
+	/// Blank line.
+	Blank,
+}
+
+// Trim mode of an item kind, see `Trim` for the bit layout
+const TRIM_START: u8 = 1;
+const TRIM_END: u8 = 2;
+const TRIM_KEY: u32 = 0;
+const TRIM_VALUE: u32 = 2;
+const TRIM_SECTION: u32 = 4;
+const TRIM_COMMENT: u32 = 6;
+
+#[inline(never)]
+const fn trim(s: &[u8], trim: u8) -> &[u8] {
+	match trim {
+		TRIM_START => s.trim_ascii_start(),
+		TRIM_END => s.trim_ascii_end(),
+		_ => s.trim_ascii(),
+	}
+}
+
+// Trims characters matching the predicate, stops at invalid utf8
+#[inline(never)]
+fn trim_with(mut s: &[u8], trim: u8, f: fn(char) -> bool) -> &[u8] {
+	if trim & TRIM_START != 0 {
+		if let Some(chunk) = s.utf8_chunks().next() {
+			let valid = chunk.valid();
+			s = &s[valid.len() - valid.trim_start_matches(f).len()..];
+		}
+	}
+	if trim & TRIM_END != 0 {
+		if let Some(chunk) = s.utf8_chunks().last() {
+			if chunk.invalid().is_empty() {
+				let valid = chunk.valid();
+				s = &s[..s.len() - (valid.len() - valid.trim_end_matches(f).len())];
+			}
+		}
+	}
+	s
+}
+
+// Returns the end of the key of a line separated by whitespace, leading whitespace is part of the key
+const fn find_whitespace(line: &[u8]) -> usize {
+	let mut i = 0;
+	while i < line.len() && matches!(line[i], b' ' | b'\t') {
+		i += 1;
+	}
+	while i < line.len() && !matches!(line[i], b' ' | b'\t') {
+		i += 1;
+	}
+	i
+}
+
+// Returns the start of the value following the separating whitespace
+#[inline]
+const fn skip_whitespace(s: &[u8], mut i: usize) -> usize {
+	while i < s.len() && matches!(s[i], b' ' | b'\t') {
+		i += 1;
+	}
+	i
+}
+
+// Contains ascii control characters other than tab and the newlines
+#[inline]
+fn has_control_chars(s: &[u8]) -> bool {
+	s.iter().any(|&chr| chr < 0x20 && !matches!(chr, b'\t' | b'\r' | b'\n') || chr == 0x7f)
+}
+
+// Line starts with whitespace followed by more than whitespace
+#[inline]
+fn is_folded(s: &[u8]) -> bool {
+	let i = skip_whitespace(s, 0);
+	i > 0 && i < s.len() && !matches!(s[i], b'\r' | b'\n')
+}
+
+// Line ends in an unescaped backslash
+#[inline]
+fn is_continued(s: &[u8]) -> bool {
+	let mut n = 0;
+	while n < s.len() && s[s.len() - 1 - n] == b'\\' {
+		n += 1;
+	}
+	n & 1 != 0
+}
+
+/// Saved parser position.
+///
+/// Snapshot of the parsing state without the configuration of the parser.
+/// See [`Parser::position`](crate::Parser::position) and [`Parser::seek`](crate::Parser::seek).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Position<'a> {
+	line: u32,
+	line_number: u32,
+	section_ended: bool,
+	global_section: bool,
+	error: Option<ParseError>,
+	section: Option<&'a [u8]>,
+	inline_comment: Option<&'a [u8]>,
+	comment_prefix: Option<u8>,
+	quoted: bool,
+	trailing: Option<&'a [u8]>,
+	line_start: usize,
+	state: &'a [u8],
+}
+
+/// Ini streaming parser over raw bytes.
+///
+/// Implements the exact same line-based logic as [`Parser`](crate::Parser) without requiring the input to be valid UTF-8.
+///
+/// ```
+/// use ini_core as ini;
+///
+/// let mut parser = ini::BytesParser::new(b"[S]\nk=v");
+/// assert_eq!(parser.next(), Some(ini::ByteItem::SectionEnd));
+/// assert_eq!(parser.next(), Some(ini::ByteItem::Section(b"S")));
+/// assert_eq!(parser.next(), Some(ini::ByteItem::Property(b"k", Some(b"v"))));
+/// assert_eq!(parser.next(), Some(ini::ByteItem::SectionEnd));
+/// assert_eq!(parser.next(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BytesParser<'a> {
+	line: u32,
+	line_number: u32,
+	comment_chars: u128,
+	separator_char: u8,
+	whitespace_separator: bool,
+	section_open: u8,
+	section_close: u8,
+	trim: u8,
+	trim_with: Option<fn(char) -> bool>,
+	inline_comments: bool,
+	line_continuation: bool,
+	folding: bool,
+	strict: bool,
+	blank_on_whitespace: bool,
+	split_last: bool,
+	section_trailing: bool,
+	case_insensitive: bool,
+	// Only used when collecting the document
+	#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+	global_name: Option<&'a [u8]>,
+	max_line_len: usize,
+	reject_control_chars: bool,
+	quoted_values: bool,
+	empty_section: EmptySectionPolicy,
+	section_ended: bool,
+	global_section: bool,
+	error: Option<ParseError>,
+	section: Option<&'a [u8]>,
+	inline_comment: Option<&'a [u8]>,
+	comment_prefix: Option<u8>,
+	quoted: bool,
+	trailing: Option<&'a [u8]>,
+	line_start: usize,
+	// Start of the document after a skipped byte order mark
+	start: usize,
+	input: &'a [u8],
+	state: &'a [u8],
+}
+
+impl<'a> BytesParser<'a> {
+	/// Constructs a new `BytesParser` instance.
+	#[inline]
+	pub const fn new(s: &'a [u8]) -> BytesParser<'a> {
+		BytesParser { line: 0, line_number: 0, comment_chars: 1 << b';', separator_char: b'=', whitespace_separator: false, section_open: b'[', section_close: b']', trim: 0, trim_with: None, inline_comments: false, line_continuation: false, folding: false, strict: false, blank_on_whitespace: false, split_last: false, section_trailing: false, case_insensitive: false, global_name: None, max_line_len: usize::MAX, reject_control_chars: false, quoted_values: false, empty_section: EmptySectionPolicy::AllowEmpty, section_ended: false, global_section: true, error: None, section: None, inline_comment: None, comment_prefix: None, quoted: false, trailing: None, line_start: 0, start: 0, input: s, state: s }
+	}
+
+	/// Sets the comment character, eg. `b'#'`.
+	///
+	/// The default is `b';'`.
+	#[must_use]
+	#[inline]
+	pub const fn comment_char(self, chr: u8) -> BytesParser<'a> {
+		self.comment_chars(&[chr])
+	}
+
+	/// Sets the comment characters, eg. `b";#"`.
+	///
+	/// A line starting with any of these characters is a comment.
+	/// An empty set disables comments entirely.
+	///
+	/// The default is `b";"`.
+	#[must_use]
+	#[inline]
+	pub const fn comment_chars(self, chrs: &[u8]) -> BytesParser<'a> {
+		let mut comment_chars = 0u128;
+		let mut i = 0;
+		while i < chrs.len() {
+			// Mask off high bit to ensure we don't corrupt utf8 strings
+			comment_chars |= 1 << (chrs[i] & 0x7f);
+			i += 1;
+		}
+		BytesParser { comment_chars, ..self }
+	}
+
+	/// Sets both `;` and `#` as comment characters.
+	///
+	/// See [`Parser::default_comments`](crate::Parser::default_comments).
+	#[must_use]
+	#[inline]
+	pub const fn default_comments(self) -> BytesParser<'a> {
+		self.comment_chars(b";#")
+	}
+
+	/// Disables comments.
+	///
+	/// See [`Parser::no_comments`](crate::Parser::no_comments).
+	#[must_use]
+	#[inline]
+	pub const fn no_comments(self) -> BytesParser<'a> {
+		self.comment_chars(b"")
+	}
+
+	/// Sets the key value separator character, eg. `b':'`.
+	///
+	/// The default is `b'='`.
+	#[must_use]
+	#[inline]
+	pub const fn separator_char(self, chr: u8) -> BytesParser<'a> {
+		// Mask off high bit to ensure we don't corrupt utf8 strings
+		let separator_char = chr & 0x7f;
+		BytesParser { separator_char, whitespace_separator: false, ..self }
+	}
+
+	/// Sets how keys and values are separated.
+	///
+	/// See [`Parser::separator`](crate::Parser::separator).
+	#[must_use]
+	#[inline]
+	pub const fn separator(self, separator: Separator) -> BytesParser<'a> {
+		match separator {
+			Separator::Char(chr) => self.separator_char(chr),
+			Separator::Whitespace => BytesParser { whitespace_separator: true, ..self },
+		}
+	}
+
+	/// Sets the characters opening and closing section headers, eg. `b'<'` and `b'>'`.
+	///
+	/// See [`Parser::section_delimiters`](crate::Parser::section_delimiters).
+	#[must_use]
+	#[inline]
+	pub const fn section_delimiters(self, open: u8, close: u8) -> BytesParser<'a> {
+		// Mask off high bit to ensure we don't corrupt utf8 strings
+		BytesParser { section_open: open & 0x7f, section_close: close & 0x7f, ..self }
+	}
+
+	/// Sets auto trimming of all returned byte slices.
+	///
+	/// The default is `false`.
+	#[must_use]
+	#[inline]
+	pub const fn auto_trim(self, auto_trim: bool) -> BytesParser<'a> {
+		let trim = if auto_trim { Trim::ALL.0 } else { Trim::NONE.0 };
+		BytesParser { trim, ..self }
+	}
+
+	/// Sets which returned byte slices are trimmed.
+	///
+	/// See [`Parser::trim`](crate::Parser::trim).
+	#[must_use]
+	#[inline]
+	pub const fn trim(self, trim: Trim) -> BytesParser<'a> {
+		BytesParser { trim: trim.0, ..self }
+	}
+
+	/// Sets the predicate matching the characters to trim.
+	///
+	/// See [`Parser::trim_with`](crate::Parser::trim_with).
+	#[must_use]
+	#[inline]
+	pub const fn trim_with(self, f: fn(char) -> bool) -> BytesParser<'a> {
+		BytesParser { trim_with: Some(f), ..self }
+	}
+
+	// Sets or clears the predicate matching the characters to trim, see Parser::auto_trim_unicode
+	#[inline]
+	pub(crate) const fn set_trim_with(self, trim_with: Option<fn(char) -> bool>) -> BytesParser<'a> {
+		BytesParser { trim_with, ..self }
+	}
+
+	/// Sets auto trimming the start of all returned byte slices.
+	///
+	/// See [`Parser::auto_trim_start`](crate::Parser::auto_trim_start).
+	#[must_use]
+	#[inline]
+	pub const fn auto_trim_start(self, auto_trim_start: bool) -> BytesParser<'a> {
+		let trim = if auto_trim_start { self.trim | Trim::START.0 } else { self.trim & !Trim::START.0 };
+		BytesParser { trim, ..self }
+	}
+
+	/// Sets auto trimming the end of all returned byte slices.
+	///
+	/// See [`Parser::auto_trim_end`](crate::Parser::auto_trim_end).
+	#[must_use]
+	#[inline]
+	pub const fn auto_trim_end(self, auto_trim_end: bool) -> BytesParser<'a> {
+		let trim = if auto_trim_end { self.trim | Trim::END.0 } else { self.trim & !Trim::END.0 };
+		BytesParser { trim, ..self }
+	}
+
+	/// Sets skipping a leading UTF-8 byte order mark.
+	///
+	/// See [`Parser::skip_bom`](crate::Parser::skip_bom).
+	#[must_use]
+	#[inline]
+	pub const fn skip_bom(self, skip_bom: bool) -> BytesParser<'a> {
+		if skip_bom && self.offset() == 0 {
+			if let [0xEF, 0xBB, 0xBF, state @ ..] = self.state {
+				return BytesParser { start: 3, state, ..self };
+			}
+		}
+		self
+	}
+
+	/// Sets splitting trailing comments off property values.
+	///
+	/// See [`Parser::inline_comments`](crate::Parser::inline_comments).
+	#[must_use]
+	#[inline]
+	pub const fn inline_comments(self, inline_comments: bool) -> BytesParser<'a> {
+		BytesParser { inline_comments, ..self }
+	}
+
+	/// Returns the trailing comment split off the most recently returned property.
+	#[inline]
+	pub const fn inline_comment(&self) -> Option<&'a [u8]> {
+		self.inline_comment
+	}
+
+	/// Returns the comment character of the most recently returned comment.
+	///
+	/// See [`Parser::comment_prefix`](crate::Parser::comment_prefix).
+	#[inline]
+	pub const fn comment_prefix(&self) -> Option<u8> {
+		self.comment_prefix
+	}
+
+	/// Sets accepting section headers followed by trailing text.
+	///
+	/// See [`Parser::section_trailing`](crate::Parser::section_trailing).
+	#[must_use]
+	#[inline]
+	pub const fn section_trailing(self, section_trailing: bool) -> BytesParser<'a> {
+		BytesParser { section_trailing, ..self }
+	}
+
+	/// Returns the text after the closing `]` of the most recently returned section header.
+	#[inline]
+	pub const fn trailing(&self) -> Option<&'a [u8]> {
+		self.trailing
+	}
+
+	/// Sets joining property values ending in a backslash with the next line.
+	///
+	/// See [`Parser::line_continuation`](crate::Parser::line_continuation).
+	#[must_use]
+	#[inline]
+	pub const fn line_continuation(self, line_continuation: bool) -> BytesParser<'a> {
+		BytesParser { line_continuation, ..self }
+	}
+
+	/// Sets joining property values with the following lines starting with whitespace.
+	///
+	/// See [`Parser::folding`](crate::Parser::folding).
+	#[must_use]
+	#[inline]
+	pub const fn folding(self, folding: bool) -> BytesParser<'a> {
+		BytesParser { folding, ..self }
+	}
+
+	/// Sets how section headers with an empty name are parsed.
+	///
+	/// See [`Parser::empty_section`](crate::Parser::empty_section).
+	#[must_use]
+	#[inline]
+	pub const fn empty_section(self, empty_section: EmptySectionPolicy) -> BytesParser<'a> {
+		BytesParser { empty_section, ..self }
+	}
+
+	/// Sets splitting properties on the last separator character of the line.
+	///
+	/// See [`Parser::split_last`](crate::Parser::split_last).
+	#[must_use]
+	#[inline]
+	pub const fn split_last(self, split_last: bool) -> BytesParser<'a> {
+		BytesParser { split_last, ..self }
+	}
+
+	/// Sets the maximum length of a line in bytes.
+	///
+	/// See [`Parser::max_line_len`](crate::Parser::max_line_len).
+	#[must_use]
+	#[inline]
+	pub const fn max_line_len(self, max_line_len: usize) -> BytesParser<'a> {
+		BytesParser { max_line_len, ..self }
+	}
+
+	/// Sets returning lines containing control characters as errors.
+	///
+	/// See [`Parser::reject_control_chars`](crate::Parser::reject_control_chars).
+	#[must_use]
+	#[inline]
+	pub const fn reject_control_chars(self, reject_control_chars: bool) -> BytesParser<'a> {
+		BytesParser { reject_control_chars, ..self }
+	}
+
+	/// Sets reading values enclosed in double quotes.
+	///
+	/// See [`Parser::quoted_values`](crate::Parser::quoted_values).
+	#[must_use]
+	#[inline]
+	pub const fn quoted_values(self, quoted_values: bool) -> BytesParser<'a> {
+		BytesParser { quoted_values, ..self }
+	}
+
+	/// Returns if the value of the most recently returned property was enclosed in double quotes.
+	///
+	/// See [`Parser::quoted`](crate::Parser::quoted).
+	#[inline]
+	pub const fn quoted(&self) -> bool {
+		self.quoted
+	}
+
+	/// Sets comparing section names and keys ignoring ascii case in lookups.
+	///
+	/// See [`Parser::case_insensitive`](crate::Parser::case_insensitive).
+	#[must_use]
+	#[inline]
+	pub const fn case_insensitive(self, case_insensitive: bool) -> BytesParser<'a> {
+		BytesParser { case_insensitive, ..self }
+	}
+
+	/// Returns if section names and keys are compared ignoring ascii case.
+	#[inline]
+	pub const fn is_case_insensitive(&self) -> bool {
+		self.case_insensitive
+	}
+
+	/// Sets the name of the global section when collecting the document.
+	///
+	/// See [`Parser::global_section_name`](crate::Parser::global_section_name).
+	#[cfg(feature = "alloc")]
+	#[must_use]
+	#[inline]
+	pub const fn global_section_name(self, name: &'a [u8]) -> BytesParser<'a> {
+		BytesParser { global_name: Some(name), ..self }
+	}
+
+	// The name of the global section when collecting the document, see Parser::into_map
+	#[cfg(feature = "alloc")]
+	#[inline]
+	pub(crate) const fn global_name(&self) -> Option<&'a [u8]> {
+		self.global_name
+	}
+
+	// Recovers the kind of a malformed line according to the section delimiters
+	#[inline]
+	pub(crate) fn error_kind(&self, line: &[u8]) -> ErrorKind {
+		if self.reject_control_chars && has_control_chars(line) {
+			return ErrorKind::ControlChar;
+		}
+		// Property lines with a separator are only errors because of their value
+		if self.quoted_values && line.first() != Some(&self.section_open) {
+			let i = if self.whitespace_separator { find_whitespace(line) } else { parse::find_chr_nl(line, self.separator_char).0 };
+			if i < line.len() {
+				return ErrorKind::UnterminatedQuote;
+			}
+		}
+		ErrorKind::of(line, self.section_open, self.section_close)
+	}
+
+	// Compares section names or keys according to the case sensitivity option
+	#[inline]
+	pub(crate) fn name_eq(&self, a: &[u8], b: &[u8]) -> bool {
+		if self.case_insensitive { a.eq_ignore_ascii_case(b) } else { a == b }
+	}
+
+	// Section the most recently returned property belongs to for lookups
+	// The global section is `Some(None)`, the section of a malformed section header is `None` and matches no section
+	#[inline]
+	pub(crate) const fn lookup_section(&self) -> Option<Option<&'a [u8]>> {
+		if self.global_section {
+			Some(None)
+		}
+		else {
+			match self.section {
+				Some(name) => Some(Some(name)),
+				None => None,
+			}
+		}
+	}
+
+	// Compares optional section names according to the case sensitivity option
+	#[inline]
+	pub(crate) fn section_eq(&self, a: Option<&[u8]>, b: Option<&[u8]>) -> bool {
+		match (a, b) {
+			(Some(a), Some(b)) => self.name_eq(a, b),
+			(a, b) => a.is_none() && b.is_none(),
+		}
+	}
+
+	/// Sets returning lines with only whitespace as blank lines.
+	///
+	/// See [`Parser::blank_on_whitespace`](crate::Parser::blank_on_whitespace).
+	#[must_use]
+	#[inline]
+	pub const fn blank_on_whitespace(self, blank_on_whitespace: bool) -> BytesParser<'a> {
+		BytesParser { blank_on_whitespace, ..self }
+	}
+
+	/// Sets stopping at the first malformed line.
+	///
+	/// See [`Parser::strict`](crate::Parser::strict).
+	#[must_use]
+	#[inline]
+	pub const fn strict(self, strict: bool) -> BytesParser<'a> {
+		BytesParser { strict, ..self }
+	}
+
+	/// Returns the error which stopped parsing in strict mode.
+	#[inline]
+	pub const fn error(&self) -> Option<ParseError> {
+		self.error
+	}
+
+	/// Returns the number of lines consumed so far.
+	#[inline]
+	pub const fn line(&self) -> u32 {
+		self.line
+	}
+
+	/// Returns the line number (starting at 1) of the most recently returned item.
+	///
+	/// See [`Parser::line_number`](crate::Parser::line_number).
+	#[inline]
+	pub const fn line_number(&self) -> u32 {
+		self.line_number
+	}
+
+	/// Returns if the parser is in the global section before the first section header.
+	///
+	/// See [`Parser::in_global_section`](crate::Parser::in_global_section).
+	#[inline]
+	pub const fn in_global_section(&self) -> bool {
+		self.global_section
+	}
+
+	/// Returns if the parser has returned its last item.
+	///
+	/// See [`Parser::is_exhausted`](crate::Parser::is_exhausted).
+	#[inline]
+	pub const fn is_exhausted(&self) -> bool {
+		self.state.is_empty() && self.section_ended
+	}
+
+	/// Returns the byte column (starting at 1) where a slice of the input starts.
+	///
+	/// See [`Parser::column`](crate::Parser::column).
+	pub fn column(&self, s: &[u8]) -> Option<u32> {
+		let input = self.input.as_ptr_range();
+		let range = s.as_ptr_range();
+		if range.start < input.start || range.end > input.end {
+			return None;
+		}
+		let offset = range.start as usize - input.start as usize;
+		// The skipped byte order mark isn't part of the first line
+		let line_start = match self.input[..offset].iter().rposition(|&chr| chr == b'\n' || chr == b'\r') {
+			Some(i) => i + 1,
+			None => usize::min(self.start, offset),
+		};
+		Some((offset - line_start + 1) as u32)
+	}
+
+	/// Returns the name of the current section.
+	///
+	/// See [`Parser::current_section`](crate::Parser::current_section).
+	#[inline]
+	pub const fn current_section(&self) -> Option<&'a [u8]> {
+		self.section
+	}
+
+	/// Returns the number of items remaining without parsing them.
+	///
+	/// See [`Parser::count_items`](crate::Parser::count_items).
+	pub fn count_items(&self) -> usize {
+		if self.scans_forward() {
+			return self.clone().count();
+		}
+		let sections = !self.is_comment_char(self.section_open);
+		let mut section_ended = self.section_ended;
+		let mut count = 0;
+		let mut s = self.state;
+		while s.len() > 0 {
+			// Section headers are preceded by a section end
+			if sections && s[0] == self.section_open {
+				if !section_ended {
+					count += 1;
+				}
+				section_ended = false;
+			}
+			count += 1;
+			let i = parse::find_nl(s);
+			if i == s.len() {
+				break;
+			}
+			s = &s[i + crate::nl_len(&s[i..])..];
+		}
+		if !section_ended {
+			count += 1;
+		}
+		count
+	}
+
+	/// Skips ahead to the next section header or the end of the document.
+	///
+	/// See [`Parser::skip_section`](crate::Parser::skip_section).
+	pub fn skip_section(&mut self) {
+		if self.scans_forward() {
+			loop {
+				let saved = self.clone();
+				let header = match self.next() {
+					Some(ByteItem::SectionEnd | ByteItem::Section(_)) | None => true,
+					Some(ByteItem::Error([chr, ..])) => *chr == self.section_open,
+					_ => false,
+				};
+				if header {
+					*self = saved;
+					return;
+				}
+			}
+		}
+		let sections = !self.is_comment_char(self.section_open);
+		let mut s = self.state;
+		while s.len() > 0 && !(sections && s[0] == self.section_open) {
+			let i = parse::find_nl(s);
+			self.skip_ln(&s[i..]);
+			s = self.state;
+		}
+	}
+
+	/// Skips ahead past the header of the next section with the given name.
+	///
+	/// See [`Parser::skip_to_section`](crate::Parser::skip_to_section).
+	pub fn skip_to_section(&mut self, name: &[u8]) -> bool {
+		let fast = !self.scans_forward();
+		loop {
+			let s = self.state;
+			// Lines which can't be section headers are skipped without parsing them
+			if fast && s.len() > 0 && s[0] != self.section_open {
+				let i = parse::find_nl(s);
+				self.skip_ln(&s[i..]);
+				continue;
+			}
+			match self.next() {
+				Some(ByteItem::Section(section)) if self.name_eq(section, name) => return true,
+				Some(_) => (),
+				None => return false,
+			}
+		}
+	}
+
+	/// Returns the remainder of the input.
+	#[inline]
+	pub const fn remainder(&self) -> &'a [u8] {
+		self.state
+	}
+
+	/// Returns the whole input.
+	#[inline]
+	pub const fn input(&self) -> &'a [u8] {
+		self.input
+	}
+
+	/// Returns the number of bytes of the input consumed so far.
+	#[inline]
+	pub const fn offset(&self) -> usize {
+		self.input.len() - self.state.len()
+	}
+
+	/// Returns the current position of the parser.
+	///
+	/// See [`Parser::position`](crate::Parser::position).
+	#[inline]
+	pub const fn position(&self) -> Position<'a> {
+		Position { line: self.line, line_number: self.line_number, section_ended: self.section_ended, global_section: self.global_section, error: self.error, section: self.section, inline_comment: self.inline_comment, comment_prefix: self.comment_prefix, quoted: self.quoted, trailing: self.trailing, line_start: self.line_start, state: self.state }
+	}
+
+	/// Restores a position previously returned by [`position`](BytesParser::position).
+	///
+	/// See [`Parser::seek`](crate::Parser::seek).
+	#[inline]
+	pub fn seek(&mut self, pos: Position<'a>) {
+		// The remainder is always a suffix of the input
+		debug_assert_eq!(pos.state.as_ptr_range().end, self.input.as_ptr_range().end);
+		self.line = pos.line;
+		self.line_number = pos.line_number;
+		self.section_ended = pos.section_ended;
+		self.global_section = pos.global_section;
+		self.error = pos.error;
+		self.section = pos.section;
+		self.inline_comment = pos.inline_comment;
+		self.comment_prefix = pos.comment_prefix;
+		self.quoted = pos.quoted;
+		self.trailing = pos.trailing;
+		self.line_start = pos.line_start;
+		self.state = pos.state;
+	}
+
+	/// Returns the next item and its byte range in the input.
+	///
+	/// See [`Parser::next_spanned`](crate::Parser::next_spanned).
+	#[inline]
+	pub fn next_spanned(&mut self) -> Option<(ByteItem<'a>, Range<usize>)> {
+		let (item, span, _) = self.next_line()?;
+		Some((item, span))
+	}
+
+	// Returns the next item, its span and the end of its line including the newline
+	fn next_line(&mut self) -> Option<(ByteItem<'a>, Range<usize>, usize)> {
+		let start = self.offset();
+		let item = self.next()?;
+		let mut end = self.offset();
+		// Parsing stopped at the malformed line, the line ends at its newline instead of the end of the input
+		if let (Some(_), ByteItem::Error(error)) = (self.error, item) {
+			let i = error.as_ptr() as usize - self.input.as_ptr() as usize + error.len();
+			end = i + parse::find_nl(&self.input[i..]);
+			if end < self.input.len() {
+				end += crate::nl_len(&self.input[end..]);
+			}
+		}
+		let line_end = end;
+		// Exclude the newline from the span
+		if end > start && self.input[end - 1] == b'\n' {
+			end -= 1;
+		}
+		if end > start && self.input[end - 1] == b'\r' {
+			end -= 1;
+		}
+		Some((item, start..end, line_end))
+	}
+
+	/// Returns the line which produced the most recently returned item.
+	///
+	/// See [`Parser::current_line`](crate::Parser::current_line).
+	pub fn current_line(&self) -> &'a [u8] {
+		let mut end = self.offset();
+		// Parsing stopped at the malformed line in strict mode
+		if self.error.is_some() {
+			end = self.line_start + parse::find_nl(&self.input[self.line_start..]);
+		}
+		let mut line = &self.input[self.line_start..end];
+		if let [rest @ .., b'\n'] = line {
+			line = rest;
+		}
+		if let [rest @ .., b'\r'] = line {
+			line = rest;
+		}
+		line
+	}
+
+	/// Parses the line with the given line number on its own.
+	///
+	/// See [`Parser::nth_line`](crate::Parser::nth_line).
+	pub fn nth_line(&self, n: u32) -> Option<ByteItem<'a>> {
+		if n == 0 {
+			return None;
+		}
+		let mut s = &self.input[self.start..];
+		for _ in 1..n {
+			let i = parse::find_nl(s);
+			if i == s.len() {
+				return None;
+			}
+			s = &s[i + crate::nl_len(&s[i..])..];
+		}
+		// Section headers are returned as is
+		let mut parser = self.clone();
+		parser.line = n - 1;
+		parser.section_ended = true;
+		parser.error = None;
+		parser.line_start = self.input.len() - s.len();
+		parser.state = s;
+		parser.next()
+	}
+
+	/// Returns the next item and the newline which ended its line.
+	///
+	/// See [`Parser::next_with_newline`](crate::Parser::next_with_newline).
+	#[inline]
+	pub fn next_with_newline(&mut self) -> Option<(ByteItem<'a>, Option<Newline>)> {
+		let (item, span, line_end) = self.next_line()?;
+		let newline = Newline::detect_bytes(&self.input[span.end..line_end]);
+		Some((item, newline))
+	}
+
+	/// Returns an iterator decoding the items lossily.
+	///
+	/// Invalid UTF-8 sequences are replaced with `U+FFFD`, the strings borrow the input unless a replacement was made.
+	/// The parser slices only at ascii characters, decoding every slice separately is the same as decoding the whole document.
+	///
+	/// ```
+	/// use std::borrow::Cow;
+	/// use ini_core::{BytesParser, LossyItem};
+	///
+	/// let mut items = BytesParser::new(b"Name=caf\xc3\xa9\nPath=C:\\caf\xe9").lossy();
+	/// assert!(matches!(items.next(), Some(LossyItem::Property(Cow::Borrowed("Name"), Some(Cow::Borrowed("café"))))));
+	/// assert!(matches!(items.next(), Some(LossyItem::Property(Cow::Borrowed("Path"), Some(Cow::Owned(value)))) if value == "C:\\caf\u{fffd}"));
+	/// ```
+	#[cfg(feature = "alloc")]
+	#[inline]
+	pub const fn lossy(self) -> crate::Lossy<'a> {
+		crate::Lossy::new(self)
+	}
+
+	/// Returns the next item, usable in const contexts.
+	///
+	/// See [`Parser::next_const`](crate::Parser::next_const).
+	#[inline]
+	pub const fn next_const(mut self) -> (Option<ByteItem<'a>>, BytesParser<'a>) {
+		let item = self.parse_next_const();
+		(item, self)
+	}
+}
+
+impl<'a> Iterator for BytesParser<'a> {
+	type Item = ByteItem<'a>;
+
+	#[inline(never)]
+	fn next(&mut self) -> Option<ByteItem<'a>> {
+		self.parse_next(self.comment_chars)
+	}
+}
+
+impl<'a> core::iter::FusedIterator for BytesParser<'a> {}
+
+impl<'a> BytesParser<'a> {
+	// The comment characters are passed in separately so they can be constant folded, see ConstParser
+	// #[cfg_attr(test, mutagen::mutate)]
+	#[inline(always)]
+	pub(crate) fn parse_next(&mut self, comment_chars: u128) -> Option<ByteItem<'a>> {
+		let mut s = self.state;
+		self.line_number = self.line + 1;
+		let line_start = self.line_start;
+		self.line_start = self.input.len() - s.len();
+		if s.len() > self.max_line_len {
+			if let Some(item) = self.line_too_long(s) {
+				return Some(item);
+			}
+		}
+		if self.reject_control_chars {
+			if let Some(item) = self.control_chars(s) {
+				return Some(item);
+			}
+		}
+
+		match s.first().cloned() {
+			// Terminal case
+			None => {
+				if self.section_ended {
+					// Keep the line of the last item, eg. the malformed line in strict mode
+					self.line_start = line_start;
+					None
+				}
+				else {
+					self.section_ended = true;
+					Some(ByteItem::SectionEnd)
+				}
+			},
+			// Blank
+			Some(b'\r' | b'\n') => {
+				self.skip_ln(s);
+				Some(ByteItem::Blank)
+			},
+			// Comment
+			Some(chr) if chr < 0x80 && comment_chars & (1 << chr) != 0 => {
+				s = &s[1..];
+				let i = parse::find_nl(s);
+				let comment = &s[..i];
+				let comment = self.trim_as(comment, TRIM_COMMENT);
+				self.comment_prefix = Some(chr);
+				self.skip_ln(&s[i..]);
+				Some(ByteItem::Comment(comment))
+			},
+			// Section
+			Some(chr) if chr == self.section_open => {
+				if self.section_ended {
+					self.section_ended = false;
+					self.global_section = false;
+					let i = parse::find_nl(s);
+					let mut end = i;
+					self.trailing = None;
+					if i < 2 || s[i - 1] != self.section_close {
+						match self.split_trailing(s, i) {
+							Some(j) => end = j,
+							None => return self.malformed(s, i),
+						}
+					}
+					let section = &s[1..end - 1];
+					let section = self.trim_as(section, TRIM_SECTION);
+					if section.is_empty() && self.empty_section == EmptySectionPolicy::Error {
+						return self.malformed(s, i);
+					}
+					self.section = Some(section);
+					self.skip_ln(&s[i..]);
+					Some(ByteItem::Section(section))
+				}
+				else {
+					if self.empty_section == EmptySectionPolicy::Global {
+						if let Some(i) = self.empty_header(s) {
+							// Stay in the reopened global section
+							self.global_section = true;
+							self.section = None;
+							self.skip_ln(&s[i..]);
+							return Some(ByteItem::SectionEnd);
+						}
+					}
+					self.section_ended = true;
+					Some(ByteItem::SectionEnd)
+				}
+			},
+			// Property
+			_ => {
+				let (i, start, nl) = if self.whitespace_separator || self.split_last {
+					self.split_property(s)
+				}
+				else {
+					let (i, nl) = parse::find_chr_nl(s, self.separator_char);
+					(i, i + 1, nl)
+				};
+				let key = self.trim_as(&s[..i], TRIM_KEY);
+				if i == nl {
+					if key.is_empty() || self.blank_on_whitespace && trim(key, TRIM_START | TRIM_END).is_empty() {
+						self.skip_ln(&s[i..]);
+						return Some(ByteItem::Blank);
+					}
+					// Lines with only whitespace are not errors
+					if self.strict && !trim(key, TRIM_START | TRIM_END).is_empty() {
+						return self.malformed(s, i);
+					}
+					self.skip_ln(&s[i..]);
+					return Some(ByteItem::Property(key, None));
+				}
+				self.inline_comment = None;
+				self.quoted = false;
+				if self.line_continuation || self.folding || self.inline_comments || self.quoted_values {
+					return self.parse_value(key, s, start, nl);
+				}
+				let value = self.trim_as(&s[start..nl], TRIM_VALUE);
+				self.skip_ln(&s[nl..]);
+				Some(ByteItem::Property(key, Some(value)))
+			},
+		}
+	}
+
+	// Const evaluable subset of parse_next using the generic scalar routines
+	// The options which need runtime support are rejected, see Parser::next_const
+	const fn parse_next_const(&mut self) -> Option<ByteItem<'a>> {
+		assert!(
+			self.trim_with.is_none() && !self.inline_comments && !self.line_continuation && !self.folding && !self.strict && !self.section_trailing && self.max_line_len == usize::MAX && !self.reject_control_chars && !self.quoted_values,
+			"option not supported in const contexts");
+		let s = self.state;
+		self.line_number = self.line + 1;
+		let line_start = self.line_start;
+		self.line_start = self.input.len() - s.len();
+		let (line, rest) = s.split_at(parse::generic::find_nl(s));
+
+		let chr = match s.first() {
+			Some(&chr) => chr,
+			// Terminal case
+			None => {
+				if self.section_ended {
+					self.line_start = line_start;
+					return None;
+				}
+				self.section_ended = true;
+				return Some(ByteItem::SectionEnd);
+			},
+		};
+		// Blank
+		if chr == b'\r' || chr == b'\n' {
+			self.skip_ln(rest);
+			return Some(ByteItem::Blank);
+		}
+		// Comment
+		if self.is_comment_char(chr) {
+			let comment = self.trim_ascii_as(line.split_at(1).1, TRIM_COMMENT);
+			self.comment_prefix = Some(chr);
+			self.skip_ln(rest);
+			return Some(ByteItem::Comment(comment));
+		}
+		// Section
+		if chr == self.section_open {
+			let header = match line {
+				[_, section @ .., close] if *close == self.section_close => Some(self.trim_ascii_as(section, TRIM_SECTION)),
+				_ => None,
+			};
+			if !self.section_ended {
+				if matches!(self.empty_section, EmptySectionPolicy::Global) && matches!(header, Some(&[])) {
+					self.global_section = true;
+					self.section = None;
+					self.skip_ln(rest);
+					return Some(ByteItem::SectionEnd);
+				}
+				self.section_ended = true;
+				return Some(ByteItem::SectionEnd);
+			}
+			self.section_ended = false;
+			self.global_section = false;
+			self.trailing = None;
+			self.skip_ln(rest);
+			return match header {
+				Some(section) if !(section.is_empty() && matches!(self.empty_section, EmptySectionPolicy::Error)) => {
+					self.section = Some(section);
+					Some(ByteItem::Section(section))
+				},
+				_ => {
+					self.section = None;
+					Some(ByteItem::Error(line))
+				},
+			};
+		}
+		// Property
+		self.skip_ln(rest);
+		let mut i = if self.whitespace_separator { find_whitespace(line) } else { parse::generic::find_nl_chr(line, self.separator_char) };
+		if i == line.len() {
+			let key = self.trim_ascii_as(line, TRIM_KEY);
+			if key.is_empty() || self.blank_on_whitespace && trim(key, TRIM_START | TRIM_END).is_empty() {
+				return Some(ByteItem::Blank);
+			}
+			return Some(ByteItem::Property(key, None));
+		}
+		if self.split_last && !self.whitespace_separator {
+			let mut j = line.len() - 1;
+			while j > i && line[j] != self.separator_char {
+				j -= 1;
+			}
+			i = j;
+		}
+		let start = if self.whitespace_separator { skip_whitespace(line, i) } else { i + 1 };
+		let key = self.trim_ascii_as(line.split_at(i).0, TRIM_KEY);
+		let value = self.trim_ascii_as(line.split_at(start).1, TRIM_VALUE);
+		self.inline_comment = None;
+		Some(ByteItem::Property(key, Some(value)))
+	}
+
+	// Trims the byte slice according to the trim mode of the item kind, ignoring trim_with
+	#[inline]
+	const fn trim_ascii_as(&self, s: &'a [u8], kind: u32) -> &'a [u8] {
+		let mode = (self.trim >> kind) & (TRIM_START | TRIM_END);
+		if mode == 0 {
+			return s;
+		}
+		trim(s, mode)
+	}
+
+	#[inline]
+	const fn is_comment_char(&self, chr: u8) -> bool {
+		chr < 0x80 && self.comment_chars & (1 << chr) != 0
+	}
+
+	// Parser with the same options for another input
+	#[cfg(feature = "std")]
+	pub(crate) fn with_input<'b>(&self, s: &'b [u8]) -> BytesParser<'b> where 'a: 'b {
+		let options: BytesParser<'b> = self.clone();
+		BytesParser { line: 0, line_number: 0, section_ended: false, global_section: true, error: None, section: None, inline_comment: None, comment_prefix: None, quoted: false, trailing: None, line_start: 0, start: 0, input: s, state: s, ..options }
+	}
+
+	// Parses the next item from the buffered complete lines of a StreamParser
+	// Returns the item and the number of bytes consumed
+	#[cfg(feature = "alloc")]
+	pub(crate) fn parse_stream<'b>(&mut self, buffer: &'b [u8], finished: bool) -> Option<(ByteItem<'b>, usize)> where 'a: 'b {
+		if self.error.is_some() || buffer.is_empty() && !finished {
+			return None;
+		}
+		let options: BytesParser<'b> = self.clone();
+		let mut parser = BytesParser { section: None, inline_comment: None, trailing: None, line_start: 0, start: 0, input: buffer, state: buffer, ..options };
+		let item = parser.parse_next(self.comment_chars)?;
+		self.line = parser.line;
+		self.line_number = parser.line_number;
+		self.section_ended = parser.section_ended;
+		self.global_section = parser.global_section;
+		self.error = parser.error;
+		Some((item, parser.offset()))
+	}
+
+	// Length of the complete lines which don't depend on more input
+	#[cfg(feature = "alloc")]
+	pub(crate) fn complete_len(&self, s: &[u8]) -> usize {
+		let mut len = parse::rfind_nl(s);
+		// A trailing `\r` may be followed by `\n` in the next chunk
+		if len == s.len() && len > 0 && s[len - 1] == b'\r' {
+			len = parse::rfind_nl(&s[..len - 1]);
+		}
+		// Parse an overlong last line right away, it is an error regardless of what follows
+		if s.len() - len > self.max_line_len {
+			return s.len();
+		}
+		// Wait for the next line if the last line is continued
+		if self.line_continuation {
+			while len > 0 {
+				let mut end = len - 1;
+				if s[end] == b'\n' && end > 0 && s[end - 1] == b'\r' {
+					end -= 1;
+				}
+				let start = parse::rfind_nl(&s[..end]);
+				if !is_continued(&s[start..end]) {
+					break;
+				}
+				len = start;
+			}
+		}
+		// Wait for the line after the last line and the lines folded into it
+		if self.folding {
+			while len > 0 {
+				let mut end = len - 1;
+				if s[end] == b'\n' && end > 0 && s[end - 1] == b'\r' {
+					end -= 1;
+				}
+				len = parse::rfind_nl(&s[..end]);
+				if !matches!(s[len], b' ' | b'\t') {
+					break;
+				}
+			}
+		}
+		len
+	}
+
+	// Continued lines, errors which stop parsing and returning to the global section depend on the preceding lines
+	// The shortcuts which look at lines in isolation need the full parser instead
+	#[inline]
+	const fn scans_forward(&self) -> bool {
+		self.line_continuation || self.folding || self.strict || self.max_line_len != usize::MAX || matches!(self.empty_section, EmptySectionPolicy::Global)
+	}
+
+	// Finds the value of the last matching property, see Parser::last_property
+	pub(crate) fn last_property(mut self, section: Option<&[u8]>, key: &[u8]) -> Option<&'a [u8]> {
+		if self.scans_forward() {
+			let mut last = None;
+			while let Some(item) = self.next() {
+				match item {
+					ByteItem::Section(_) if section.is_none() => break,
+					ByteItem::Property(k, Some(value)) => {
+						if matches!(self.lookup_section(), Some(current) if self.section_eq(current, section) && self.name_eq(k, key)) {
+							last = Some(value);
+						}
+					},
+					_ => (),
+				}
+			}
+			return last;
+		}
+		let mut s = self.state;
+		let mut found = None;
+		loop {
+			let i = parse::rfind_nl(s);
+			let line = &s[i..];
+			if line.len() > 0 {
+				// Parse the line on its own, section headers are returned as is
+				self.section_ended = true;
+				self.state = line;
+				match self.parse_next(self.comment_chars) {
+					Some(ByteItem::Section(name)) => {
+						if found.is_some() && self.section_eq(section, Some(name)) {
+							return found;
+						}
+						found = None;
+					},
+					// Malformed section headers match no section
+					Some(ByteItem::Error(error)) if error[0] == self.section_open => found = None,
+					Some(ByteItem::Property(k, Some(value))) if found.is_none() && self.name_eq(k, key) => found = Some(value),
+					_ => (),
+				}
+			}
+			if i == 0 {
+				return if section.is_none() { found } else { None };
+			}
+			// Strip the newline, taking care of `\r\n`
+			let nl = s[i - 1];
+			s = &s[..i - 1];
+			if nl == b'\n' && s.last() == Some(&b'\r') {
+				s = &s[..s.len() - 1];
+			}
+		}
+	}
+
+	// Returns the end of the section header followed by trailing text
+	#[cold]
+	fn split_trailing(&mut self, s: &'a [u8], i: usize) -> Option<usize> {
+		if !self.section_trailing {
+			return None;
+		}
+		let end = s[1..i].iter().rposition(|&chr| chr == self.section_close)? + 2;
+		self.trailing = Some(&s[end..i]);
+		Some(end)
+	}
+
+	// Returns the newline index of a section header with an empty name
+	#[cold]
+	fn empty_header(&self, s: &[u8]) -> Option<usize> {
+		let i = parse::find_nl(s);
+		if i < 2 || s[i - 1] != self.section_close || !self.trim_as(&s[1..i - 1], TRIM_SECTION).is_empty() {
+			return None;
+		}
+		Some(i)
+	}
+
+	// Trims the byte slice according to the trim mode of the item kind
+	#[inline]
+	fn trim_as(&self, s: &'a [u8], kind: u32) -> &'a [u8] {
+		let mode = (self.trim >> kind) & (TRIM_START | TRIM_END);
+		if mode == 0 {
+			return s;
+		}
+		match self.trim_with {
+			None => trim(s, mode),
+			Some(f) => trim_with(s, mode, f),
+		}
+	}
+
+	// Comment char preceded by whitespace
+	#[inline]
+	fn find_inline_comment(&self, s: &[u8]) -> Option<usize> {
+		let mut i = 1;
+		while i < s.len() {
+			if self.is_comment_char(s[i]) && (s[i - 1] == b' ' || s[i - 1] == b'\t') {
+				return Some(i);
+			}
+			i += 1;
+		}
+		return None;
+	}
+
+	// Returns the start of a line longer than the maximum as an error and stops parsing
+	#[cold]
+	fn line_too_long(&mut self, s: &'a [u8]) -> Option<ByteItem<'a>> {
+		let limit = self.max_line_len;
+		// Only look for the newline within the limit
+		if parse::find_nl(&s[..limit + 1]) <= limit {
+			return None;
+		}
+		// Don't split a utf8 character
+		let mut end = limit;
+		while end > 0 && s[end] & 0xc0 == 0x80 {
+			end -= 1;
+		}
+		self.error = Some(ParseError { line: self.line + 1, kind: ErrorKind::LineTooLong });
+		self.section_ended = true;
+		self.state = &s[s.len()..];
+		Some(ByteItem::Error(&s[..end]))
+	}
+
+	// Finds the end of the key, the start of the value and the newline with the options changing where the key ends
+	#[cold]
+	fn split_property(&self, s: &'a [u8]) -> (usize, usize, usize) {
+		if self.whitespace_separator {
+			let nl = parse::find_nl(s);
+			let i = find_whitespace(&s[..nl]);
+			// The separating whitespace is part of neither the key nor the value
+			return (i, skip_whitespace(s, i), nl);
+		}
+		let (mut i, nl) = parse::find_chr_nl(s, self.separator_char);
+		if i < nl {
+			let mut end = nl;
+			// The separators within quotes are part of the value
+			if self.quoted_values {
+				if let Some(j) = s[i + 1..nl].iter().position(|&chr| chr == b'"') {
+					end = i + 1 + j;
+				}
+			}
+			if let Some(j) = s[i + 1..end].iter().rposition(|&chr| chr == self.separator_char) {
+				i += 1 + j;
+			}
+		}
+		(i, i + 1, nl)
+	}
+
+	// Parses the value with the options which look beyond the end of the value
+	#[cold]
+	fn parse_value(&mut self, key: &'a [u8], s: &'a [u8], start: usize, nl: usize) -> Option<ByteItem<'a>> {
+		let mut i = nl;
+		if self.line_continuation || self.folding {
+			while i < s.len() {
+				let j = i + crate::nl_len(&s[i..]);
+				if !(self.line_continuation && is_continued(&s[start..i]) || self.folding && is_folded(&s[j..])) {
+					break;
+				}
+				self.line += 1;
+				if s.len() - j > self.max_line_len {
+					if let Some(item) = self.line_too_long(&s[j..]) {
+						return Some(item);
+					}
+				}
+				i = j + parse::find_nl(&s[j..]);
+				if self.reject_control_chars && has_control_chars(&s[j..i]) {
+					return self.malformed(s, i);
+				}
+			}
+		}
+		let mut value = &s[start..i];
+		if self.quoted_values {
+			match self.split_quoted(value) {
+				Ok(Some(quoted)) => {
+					self.quoted = true;
+					self.skip_ln(&s[i..]);
+					return Some(ByteItem::Property(key, Some(quoted)));
+				},
+				Ok(None) => (),
+				Err(()) => return self.malformed(s, i),
+			}
+		}
+		if self.inline_comments {
+			if let Some(j) = self.find_inline_comment(value) {
+				let comment = &value[j + 1..];
+				self.inline_comment = Some(self.trim_as(comment, TRIM_COMMENT));
+				value = &value[..j];
+			}
+		}
+		let value = self.trim_as(value, TRIM_VALUE);
+		self.skip_ln(&s[i..]);
+		Some(ByteItem::Property(key, Some(value)))
+	}
+
+	// Returns the contents of a value enclosed in quotes, splitting off the inline comment after it
+	// Values with other text after the closing quote are not quoted, an unterminated quote is an error
+	#[cold]
+	fn split_quoted(&mut self, value: &'a [u8]) -> Result<Option<&'a [u8]>, ()> {
+		let start = skip_whitespace(value, 0);
+		if value.get(start) != Some(&b'"') {
+			return Ok(None);
+		}
+		let mut end = start + 1;
+		loop {
+			match value.get(end) {
+				None => return Err(()),
+				Some(b'"') => break,
+				Some(b'\\') => end += 2,
+				Some(_) => end += 1,
+			}
+		}
+		let rest = &value[end + 1..];
+		let i = skip_whitespace(rest, 0);
+		if i < rest.len() {
+			match self.find_inline_comment(rest) {
+				Some(j) if self.inline_comments && j == i => self.inline_comment = Some(self.trim_as(&rest[j + 1..], TRIM_COMMENT)),
+				_ => return Ok(None),
+			}
+		}
+		Ok(Some(&value[start + 1..end]))
+	}
+
+	// Returns a line containing control characters as an error
+	#[cold]
+	fn control_chars(&mut self, s: &'a [u8]) -> Option<ByteItem<'a>> {
+		let i = parse::find_nl(s);
+		if !has_control_chars(&s[..i]) {
+			return None;
+		}
+		// Malformed section headers start a section like any other header
+		if s[0] == self.section_open && !self.is_comment_char(s[0]) {
+			if !self.section_ended {
+				self.section_ended = true;
+				return Some(ByteItem::SectionEnd);
+			}
+			self.section_ended = false;
+			self.global_section = false;
+			self.trailing = None;
+		}
+		self.malformed(s, i)
+	}
+
+	// Returns the malformed line as an error, in strict mode parsing stops
+	#[cold]
+	fn malformed(&mut self, s: &'a [u8], i: usize) -> Option<ByteItem<'a>> {
+		if s[0] == self.section_open {
+			self.section = None;
+		}
+		if self.strict {
+			let kind = self.error_kind(&s[..i]);
+			self.error = Some(ParseError { line: self.line_number, kind });
+			self.section_ended = true;
+			self.state = &s[s.len()..];
+		}
+		else {
+			self.skip_ln(&s[i..]);
+		}
+		Some(ByteItem::Error(&s[..i]))
+	}
+
+	#[inline]
+	const fn skip_ln(&mut self, mut s: &'a [u8]) {
+		if s.len() > 0 {
+			if let [b'\r', rest @ ..] = s {
+				s = rest;
+			}
+			if let [b'\n', rest @ ..] = s {
+				s = rest;
+			}
+			self.line += 1;
+		}
+		self.state = s;
+	}
+}
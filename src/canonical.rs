@@ -0,0 +1,97 @@
+use core::{cmp, fmt, hash};
+use crate::{trim, Item};
+
+/// Item compared and hashed ignoring the whitespace around its strings.
+///
+/// The derived impls of [`Item`] compare the strings exactly, this view trims ascii whitespace first, see [`trim`].
+/// Useful for detecting semantic duplicates in a set or map.
+///
+/// See [`Item::canonical`].
+///
+/// ```
+/// use std::collections::HashSet;
+/// use ini_core::Item;
+///
+/// let set: HashSet<_> = ini_core::Parser::new("k=v\n k = v \n;c\n; c").map(Item::canonical).collect();
+/// // The properties, the comments and the end of the global section
+/// assert_eq!(set.len(), 3);
+/// assert!(set.contains(&Item::Property("k", Some("v")).canonical()));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct CanonicalItem<'a>(pub Item<'a>);
+
+impl<'a> CanonicalItem<'a> {
+	/// Returns the item with its strings trimmed.
+	///
+	/// ```
+	/// let item = ini_core::Item::Property(" k ", Some(" v")).canonical();
+	/// assert_eq!(item.trimmed(), ini_core::Item::Property("k", Some("v")));
+	/// ```
+	pub fn trimmed(&self) -> Item<'a> {
+		match self.0 {
+			Item::Error(error) => Item::Error(trim(error)),
+			Item::Section(section) => Item::Section(trim(section)),
+			Item::SectionEnd => Item::SectionEnd,
+			Item::Property(key, value) => Item::Property(trim(key), value.map(trim)),
+			Item::Comment(comment) => Item::Comment(trim(comment)),
+			Item::Blank => Item::Blank,
+		}
+	}
+
+	// Orders the variants in declaration order
+	fn key(&self) -> (u8, &'a str, Option<&'a str>) {
+		match self.trimmed() {
+			Item::Error(error) => (0, error, None),
+			Item::Section(section) => (1, section, None),
+			Item::SectionEnd => (2, "", None),
+			Item::Property(key, value) => (3, key, value),
+			Item::Comment(comment) => (4, comment, None),
+			Item::Blank => (5, "", None),
+		}
+	}
+}
+
+impl<'a> Item<'a> {
+	/// Returns a view of the item which compares and hashes ignoring the whitespace around its strings.
+	#[inline]
+	pub const fn canonical(self) -> CanonicalItem<'a> {
+		CanonicalItem(self)
+	}
+}
+
+impl<'a> PartialEq for CanonicalItem<'a> {
+	#[inline]
+	fn eq(&self, other: &CanonicalItem<'a>) -> bool {
+		self.key() == other.key()
+	}
+}
+
+impl<'a> Eq for CanonicalItem<'a> {}
+
+impl<'a> PartialOrd for CanonicalItem<'a> {
+	#[inline]
+	fn partial_cmp(&self, other: &CanonicalItem<'a>) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'a> Ord for CanonicalItem<'a> {
+	#[inline]
+	fn cmp(&self, other: &CanonicalItem<'a>) -> cmp::Ordering {
+		self.key().cmp(&other.key())
+	}
+}
+
+impl<'a> hash::Hash for CanonicalItem<'a> {
+	#[inline]
+	fn hash<H: hash::Hasher>(&self, state: &mut H) {
+		self.key().hash(state);
+	}
+}
+
+impl<'a> fmt::Display for CanonicalItem<'a> {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.trimmed(), f)
+	}
+}
@@ -0,0 +1,116 @@
+use crate::{from_utf8, BytesParser, Item, Trim};
+
+/// Ini streaming parser with the comment character known at compile time.
+///
+/// Behaves exactly like [`Parser`](crate::Parser) configured with [`comment_char`](crate::Parser::comment_char).
+/// The comment character check is constant folded in the parsing loop.
+///
+/// ```
+/// use ini_core as ini;
+///
+/// let mut parser = ini::ConstParser::<b'#'>::new("#comment\nKey=Value");
+/// assert_eq!(parser.next(), Some(ini::Item::Comment("comment")));
+/// assert_eq!(parser.next(), Some(ini::Item::Property("Key", Some("Value"))));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConstParser<'a, const COMMENT: u8 = b';'> {
+	bytes: BytesParser<'a>,
+}
+
+impl<'a, const COMMENT: u8> ConstParser<'a, COMMENT> {
+	/// Constructs a new `ConstParser` instance.
+	#[inline]
+	pub const fn new(s: &'a str) -> ConstParser<'a, COMMENT> {
+		let bytes = BytesParser::new(s.as_bytes()).comment_char(COMMENT);
+		ConstParser { bytes }
+	}
+
+	/// Sets the key value separator character, eg. `b':'`.
+	///
+	/// See [`Parser::separator_char`](crate::Parser::separator_char).
+	#[must_use]
+	#[inline]
+	pub const fn separator_char(self, chr: u8) -> ConstParser<'a, COMMENT> {
+		ConstParser { bytes: self.bytes.separator_char(chr) }
+	}
+
+	/// Sets auto trimming of all returned strings.
+	///
+	/// See [`Parser::auto_trim`](crate::Parser::auto_trim).
+	#[must_use]
+	#[inline]
+	pub const fn auto_trim(self, auto_trim: bool) -> ConstParser<'a, COMMENT> {
+		ConstParser { bytes: self.bytes.auto_trim(auto_trim) }
+	}
+
+	/// Sets which returned strings are trimmed.
+	///
+	/// See [`Parser::trim`](crate::Parser::trim).
+	#[must_use]
+	#[inline]
+	pub const fn trim(self, trim: Trim) -> ConstParser<'a, COMMENT> {
+		ConstParser { bytes: self.bytes.trim(trim) }
+	}
+
+	/// Sets the predicate matching the characters to trim.
+	///
+	/// See [`Parser::trim_with`](crate::Parser::trim_with).
+	#[must_use]
+	#[inline]
+	pub const fn trim_with(self, f: fn(char) -> bool) -> ConstParser<'a, COMMENT> {
+		ConstParser { bytes: self.bytes.trim_with(f) }
+	}
+
+	/// Sets splitting trailing comments off property values.
+	///
+	/// See [`Parser::inline_comments`](crate::Parser::inline_comments).
+	#[must_use]
+	#[inline]
+	pub const fn inline_comments(self, inline_comments: bool) -> ConstParser<'a, COMMENT> {
+		ConstParser { bytes: self.bytes.inline_comments(inline_comments) }
+	}
+
+	/// Returns the trailing comment split off the most recently returned property.
+	#[inline]
+	pub fn inline_comment(&self) -> Option<&'a str> {
+		self.bytes.inline_comment().map(from_utf8)
+	}
+
+	/// Sets joining property values ending in a backslash with the next line.
+	///
+	/// See [`Parser::line_continuation`](crate::Parser::line_continuation).
+	#[must_use]
+	#[inline]
+	pub const fn line_continuation(self, line_continuation: bool) -> ConstParser<'a, COMMENT> {
+		ConstParser { bytes: self.bytes.line_continuation(line_continuation) }
+	}
+
+	/// Returns the line number the parser is currently at.
+	#[inline]
+	pub const fn line(&self) -> u32 {
+		self.bytes.line()
+	}
+
+	/// Returns the remainder of the input string.
+	#[inline]
+	pub fn remainder(&self) -> &'a str {
+		from_utf8(self.bytes.remainder())
+	}
+
+	/// Returns the number of bytes of the input consumed so far.
+	#[inline]
+	pub const fn offset(&self) -> usize {
+		self.bytes.offset()
+	}
+}
+
+impl<'a, const COMMENT: u8> Iterator for ConstParser<'a, COMMENT> {
+	type Item = Item<'a>;
+
+	#[inline(never)]
+	fn next(&mut self) -> Option<Item<'a>> {
+		self.bytes.parse_next(1 << (COMMENT & 0x7f)).map(Item::from_bytes)
+	}
+}
+
+impl<'a, const COMMENT: u8> core::iter::FusedIterator for ConstParser<'a, COMMENT> {}
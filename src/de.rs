@@ -0,0 +1,409 @@
+/*!
+Deserialize INI documents with serde.
+
+The document is a map of global properties followed by sections:
+
+* Global properties before the first section map to scalar fields of the top-level struct.
+* Sections map to struct (or map) fields of the top-level struct, their properties map to the fields of that struct.
+* Values are parsed from strings using [`FromStr`](core::str::FromStr), property lines without `=` are empty strings.
+* Properties missing from the document map to `None` if the field is an `Option<T>`.
+* Repeated keys within a section deserialize into sequences, for scalar fields the last value wins.
+* Sections with the same name are merged.
+
+```
+#[derive(serde::Deserialize)]
+struct Config {
+	name: String,
+	server: Server,
+}
+#[derive(serde::Deserialize)]
+struct Server {
+	host: String,
+	port: u16,
+	timeout: Option<u32>,
+}
+
+let config: Config = ini_core::de::from_str("\
+name = example
+[server]
+host = localhost
+port = 8080
+").unwrap();
+
+assert_eq!(config.name, "example");
+assert_eq!(config.server.host, "localhost");
+assert_eq!(config.server.port, 8080);
+assert_eq!(config.server.timeout, None);
+```
+*/
+
+use core::{fmt, slice};
+use alloc::{format, string::String, vec, vec::Vec};
+use serde::de::{self, IntoDeserializer};
+use crate::{Item, Parser};
+
+/// Deserialization error.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+	line: u32,
+	message: String,
+}
+
+impl Error {
+	#[inline]
+	fn new(line: u32, message: String) -> Error {
+		Error { line, message }
+	}
+
+	#[inline]
+	fn at(mut self, line: u32) -> Error {
+		if self.line == 0 {
+			self.line = line;
+		}
+		self
+	}
+
+	/// Returns the line number (starting at 1) where the error occurred, if known.
+	#[inline]
+	pub fn line(&self) -> Option<u32> {
+		if self.line != 0 { Some(self.line) } else { None }
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.line != 0 {
+			write!(f, "line {}: {}", self.line, self.message)
+		}
+		else {
+			f.write_str(&self.message)
+		}
+	}
+}
+
+impl core::error::Error for Error {}
+
+impl de::Error for Error {
+	#[cold]
+	fn custom<T: fmt::Display>(msg: T) -> Error {
+		Error::new(0, format!("{}", msg))
+	}
+}
+
+/// Deserializes an instance of `T` from the INI document.
+///
+/// Keys, values and section names are trimmed.
+#[inline]
+pub fn from_str<'de, T: de::Deserialize<'de>>(s: &'de str) -> Result<T, Error> {
+	from_parser(Parser::new(s).auto_trim(true))
+}
+
+/// Deserializes an instance of `T` from the configured parser.
+pub fn from_parser<'de, T: de::Deserialize<'de>>(parser: Parser<'de>) -> Result<T, Error> {
+	let document = Document::parse(parser)?;
+	T::deserialize(Deserializer { document: &document })
+}
+
+//----------------------------------------------------------------
+// Document model
+
+type Value<'de> = (Option<&'de str>, u32);
+
+struct Property<'de> {
+	key: &'de str,
+	values: Vec<Value<'de>>,
+}
+
+struct Section<'de> {
+	name: &'de str,
+	line: u32,
+	properties: Vec<Property<'de>>,
+}
+
+struct Document<'de> {
+	globals: Vec<Property<'de>>,
+	sections: Vec<Section<'de>>,
+}
+
+impl<'de> Document<'de> {
+	fn parse(mut parser: Parser<'de>) -> Result<Document<'de>, Error> {
+		let mut document = Document { globals: Vec::new(), sections: Vec::new() };
+		let mut current = None;
+		loop {
+			let line = parser.line() + 1;
+			let Some(item) = parser.next() else { break };
+			match item {
+				Item::Error(error) => return Err(Error::new(line, format!("malformed section header: {}", error))),
+				Item::Section(name) => {
+					let index = match document.sections.iter().position(|section| section.name == name) {
+						Some(index) => index,
+						None => {
+							document.sections.push(Section { name, line, properties: Vec::new() });
+							document.sections.len() - 1
+						},
+					};
+					current = Some(index);
+				},
+				Item::Property(key, value) => {
+					let properties = match current {
+						Some(index) => &mut document.sections[index].properties,
+						None => &mut document.globals,
+					};
+					match properties.iter_mut().find(|property| property.key == key) {
+						Some(property) => property.values.push((value, line)),
+						None => properties.push(Property { key, values: vec![(value, line)] }),
+					}
+				},
+				Item::SectionEnd | Item::Comment(_) | Item::Blank => (),
+			}
+		}
+		Ok(document)
+	}
+}
+
+//----------------------------------------------------------------
+// Document deserializer
+
+struct Deserializer<'a, 'de> {
+	document: &'a Document<'de>,
+}
+
+impl<'a, 'de> de::Deserializer<'de> for Deserializer<'a, 'de> {
+	type Error = Error;
+
+	fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_map(DocumentAccess {
+			globals: self.document.globals.iter(),
+			sections: self.document.sections.iter(),
+			value: None,
+		})
+	}
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf option unit unit_struct newtype_struct seq tuple
+		tuple_struct map struct enum identifier ignored_any
+	}
+}
+
+enum Entry<'a, 'de> {
+	Global(&'a Property<'de>),
+	Section(&'a Section<'de>),
+}
+
+struct DocumentAccess<'a, 'de> {
+	globals: slice::Iter<'a, Property<'de>>,
+	sections: slice::Iter<'a, Section<'de>>,
+	value: Option<Entry<'a, 'de>>,
+}
+
+impl<'a, 'de> de::MapAccess<'de> for DocumentAccess<'a, 'de> {
+	type Error = Error;
+
+	fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+		let (name, entry) = if let Some(property) = self.globals.next() {
+			(property.key, Entry::Global(property))
+		}
+		else if let Some(section) = self.sections.next() {
+			(section.name, Entry::Section(section))
+		}
+		else {
+			return Ok(None);
+		};
+		self.value = Some(entry);
+		seed.deserialize(name.into_deserializer()).map(Some)
+	}
+
+	fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+		match self.value.take() {
+			Some(Entry::Global(property)) => {
+				let line = property.values[property.values.len() - 1].1;
+				seed.deserialize(ValueDeserializer { values: &property.values }).map_err(|err| err.at(line))
+			},
+			Some(Entry::Section(section)) => {
+				seed.deserialize(SectionDeserializer { section }).map_err(|err| err.at(section.line))
+			},
+			None => Err(de::Error::custom("value is missing")),
+		}
+	}
+}
+
+//----------------------------------------------------------------
+// Section deserializer
+
+struct SectionDeserializer<'a, 'de> {
+	section: &'a Section<'de>,
+}
+
+impl<'a, 'de> de::Deserializer<'de> for SectionDeserializer<'a, 'de> {
+	type Error = Error;
+
+	fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_map(SectionAccess {
+			properties: self.section.properties.iter(),
+			value: None,
+		})
+	}
+
+	fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_some(self)
+	}
+
+	fn deserialize_newtype_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct seq tuple
+		tuple_struct map struct enum identifier ignored_any
+	}
+}
+
+struct SectionAccess<'a, 'de> {
+	properties: slice::Iter<'a, Property<'de>>,
+	value: Option<&'a Property<'de>>,
+}
+
+impl<'a, 'de> de::MapAccess<'de> for SectionAccess<'a, 'de> {
+	type Error = Error;
+
+	fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+		let Some(property) = self.properties.next() else { return Ok(None) };
+		self.value = Some(property);
+		seed.deserialize(property.key.into_deserializer()).map(Some)
+	}
+
+	fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+		let Some(property) = self.value.take() else { return Err(de::Error::custom("value is missing")) };
+		let line = property.values[property.values.len() - 1].1;
+		seed.deserialize(ValueDeserializer { values: &property.values }).map_err(|err| err.at(line))
+	}
+}
+
+//----------------------------------------------------------------
+// Value deserializer
+
+struct ValueDeserializer<'a, 'de> {
+	values: &'a [Value<'de>],
+}
+
+impl<'a, 'de> ValueDeserializer<'a, 'de> {
+	// The last value wins
+	#[inline]
+	fn last(&self) -> Value<'de> {
+		self.values[self.values.len() - 1]
+	}
+
+	#[inline]
+	fn value(&self) -> &'de str {
+		self.last().0.unwrap_or("")
+	}
+
+	fn parse<T: core::str::FromStr>(&self, expected: &str) -> Result<T, Error> {
+		let (value, line) = self.last();
+		let value = value.unwrap_or("");
+		value.parse().map_err(|_| Error::new(line, format!("invalid {} value: {:?}", expected, value)))
+	}
+}
+
+macro_rules! deserialize_parse {
+	($($method:ident $visit:ident $ty:ty,)*) => {
+		$(
+			fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+				visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+			}
+		)*
+	};
+}
+
+impl<'a, 'de> de::Deserializer<'de> for ValueDeserializer<'a, 'de> {
+	type Error = Error;
+
+	fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_borrowed_str(self.value())
+	}
+
+	deserialize_parse! {
+		deserialize_bool visit_bool bool,
+		deserialize_i8 visit_i8 i8,
+		deserialize_i16 visit_i16 i16,
+		deserialize_i32 visit_i32 i32,
+		deserialize_i64 visit_i64 i64,
+		deserialize_i128 visit_i128 i128,
+		deserialize_u8 visit_u8 u8,
+		deserialize_u16 visit_u16 u16,
+		deserialize_u32 visit_u32 u32,
+		deserialize_u64 visit_u64 u64,
+		deserialize_u128 visit_u128 u128,
+		deserialize_f32 visit_f32 f32,
+		deserialize_f64 visit_f64 f64,
+		deserialize_char visit_char char,
+	}
+
+	fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_borrowed_bytes(self.value().as_bytes())
+	}
+
+	fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		self.deserialize_bytes(visitor)
+	}
+
+	fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		match self.last().0 {
+			Some(_) => visitor.visit_some(self),
+			None => visitor.visit_none(),
+		}
+	}
+
+	fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_unit_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_newtype_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_seq(ValuesAccess { values: self.values.iter() })
+	}
+
+	fn deserialize_tuple<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_tuple_struct<V: de::Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_enum<V: de::Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_enum(de::value::BorrowedStrDeserializer::new(self.value()))
+	}
+
+	serde::forward_to_deserialize_any! {
+		str string map struct identifier ignored_any
+	}
+}
+
+struct ValuesAccess<'a, 'de> {
+	values: slice::Iter<'a, Value<'de>>,
+}
+
+impl<'a, 'de> de::SeqAccess<'de> for ValuesAccess<'a, 'de> {
+	type Error = Error;
+
+	fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+		let Some(value) = self.values.as_slice().get(..1) else { return Ok(None) };
+		self.values.next();
+		seed.deserialize(ValueDeserializer { values: value }).map(Some)
+	}
+
+	#[inline]
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.values.len())
+	}
+}
@@ -0,0 +1,231 @@
+use alloc::vec::Vec;
+use crate::{Item, Parser};
+
+/// A difference between two documents.
+///
+/// See [`diff`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Change<'a> {
+	/// Section only in the new document.
+	SectionAdded(&'a str),
+	/// Section only in the old document.
+	SectionRemoved(&'a str),
+	/// Property only in the new document.
+	PropertyAdded {
+		section: Option<&'a str>,
+		key: &'a str,
+		value: Option<&'a str>,
+	},
+	/// Property only in the old document.
+	PropertyRemoved {
+		section: Option<&'a str>,
+		key: &'a str,
+		value: Option<&'a str>,
+	},
+	/// Property with a different value.
+	PropertyModified {
+		section: Option<&'a str>,
+		key: &'a str,
+		old: Option<&'a str>,
+		new: Option<&'a str>,
+	},
+	/// Comment only in the new document.
+	CommentAdded {
+		section: Option<&'a str>,
+		comment: &'a str,
+	},
+	/// Comment only in the old document.
+	CommentRemoved {
+		section: Option<&'a str>,
+		comment: &'a str,
+	},
+}
+
+/// Compares two documents item by item.
+///
+/// The documents are parsed with [`auto_trim`](Parser::auto_trim) enabled.
+/// Duplicate sections are merged and the last value of a duplicate key wins.
+/// Section names and keys are compared case-sensitively.
+///
+/// Changes are listed in document order: the sections of the old document followed by the sections added in the new document.
+/// Within a section the properties of the old document are listed first, followed by the added properties.
+/// A removed or added section is followed by all of its properties.
+///
+/// Comments are ignored unless enabled with [`comments`](Diff::comments), blank lines and errors are always ignored.
+/// The properties and comments after a malformed section header are ignored as well.
+///
+/// ```
+/// use ini_core::Change;
+///
+/// let old = "[S]\na=1\nb=2\n[T]\nc=3";
+/// let new = "[S]\na=1\nb=20\nd=4";
+/// assert_eq!(ini_core::diff(old, new), [
+/// 	Change::PropertyModified { section: Some("S"), key: "b", old: Some("2"), new: Some("20") },
+/// 	Change::PropertyAdded { section: Some("S"), key: "d", value: Some("4") },
+/// 	Change::SectionRemoved("T"),
+/// 	Change::PropertyRemoved { section: Some("T"), key: "c", value: Some("3") },
+/// ]);
+/// ```
+#[inline]
+pub fn diff<'a>(old: &'a str, new: &'a str) -> Vec<Change<'a>> {
+	Diff::new().diff(old, new)
+}
+
+/// Options for comparing documents.
+///
+/// See [`diff`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Diff {
+	comments: bool,
+}
+
+impl Diff {
+	/// Constructs a new `Diff` instance.
+	#[inline]
+	pub const fn new() -> Diff {
+		Diff { comments: false }
+	}
+
+	/// Sets comparing comments.
+	///
+	/// Comments are compared per section regardless of their position.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// use ini_core::{Change, Diff};
+	///
+	/// let changes = Diff::new().comments(true).diff(";one\nk=v", ";two\nk=v");
+	/// assert_eq!(changes, [
+	/// 	Change::CommentRemoved { section: None, comment: "one" },
+	/// 	Change::CommentAdded { section: None, comment: "two" },
+	/// ]);
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn comments(self, comments: bool) -> Diff {
+		Diff { comments }
+	}
+
+	/// Compares two documents.
+	pub fn diff<'a>(&self, old: &'a str, new: &'a str) -> Vec<Change<'a>> {
+		let old = self.group(old);
+		let new = self.group(new);
+		let mut changes = Vec::new();
+		for a in &old {
+			match new.iter().find(|b| b.name == a.name) {
+				Some(b) => a.compare(b, &mut changes),
+				None => a.removed(&mut changes),
+			}
+		}
+		for b in &new {
+			if !old.iter().any(|a| a.name == b.name) {
+				b.added(&mut changes);
+			}
+		}
+		return changes;
+	}
+
+	// Groups the document by section, the global section is always first
+	fn group<'a>(&self, s: &'a str) -> Vec<Section<'a>> {
+		let mut sections = Vec::new();
+		sections.push(Section { name: None, props: Vec::new(), comments: Vec::new() });
+		let mut parser = Parser::new(s).auto_trim(true);
+		while let Some(item) = parser.next() {
+			match item {
+				// Empty sections are compared too
+				Item::Section(name) => _ = section_mut(&mut sections, Some(name)),
+				Item::Property(key, value) => {
+					let Some(name) = parser.lookup_section() else { continue };
+					let props = &mut section_mut(&mut sections, name).props;
+					match props.iter_mut().find(|prop| prop.0 == key) {
+						Some(prop) => prop.1 = value,
+						None => props.push((key, value)),
+					}
+				},
+				Item::Comment(comment) if self.comments => {
+					let Some(name) = parser.lookup_section() else { continue };
+					section_mut(&mut sections, name).comments.push(comment);
+				},
+				_ => (),
+			}
+		}
+		return sections;
+	}
+}
+
+// Finds the section by name, adding it if it is new
+fn section_mut<'s, 'a>(sections: &'s mut Vec<Section<'a>>, name: Option<&'a str>) -> &'s mut Section<'a> {
+	let index = match sections.iter().position(|section| section.name == name) {
+		Some(index) => index,
+		None => {
+			sections.push(Section { name, props: Vec::new(), comments: Vec::new() });
+			sections.len() - 1
+		},
+	};
+	&mut sections[index]
+}
+
+struct Section<'a> {
+	name: Option<&'a str>,
+	props: Vec<(&'a str, Option<&'a str>)>,
+	comments: Vec<&'a str>,
+}
+
+impl<'a> Section<'a> {
+	fn compare(&self, new: &Section<'a>, changes: &mut Vec<Change<'a>>) {
+		let section = self.name;
+		for &(key, old) in &self.props {
+			match new.props.iter().find(|prop| prop.0 == key) {
+				Some(&(_, new)) if new != old => changes.push(Change::PropertyModified { section, key, old, new }),
+				Some(_) => (),
+				None => changes.push(Change::PropertyRemoved { section, key, value: old }),
+			}
+		}
+		for &(key, value) in &new.props {
+			if !self.props.iter().any(|prop| prop.0 == key) {
+				changes.push(Change::PropertyAdded { section, key, value });
+			}
+		}
+
+		// Match up equal comments, the leftovers are removed and added
+		let mut matched = alloc::vec![false; new.comments.len()];
+		for &comment in &self.comments {
+			match (0..new.comments.len()).find(|&i| !matched[i] && new.comments[i] == comment) {
+				Some(i) => matched[i] = true,
+				None => changes.push(Change::CommentRemoved { section, comment }),
+			}
+		}
+		for (&comment, matched) in new.comments.iter().zip(matched) {
+			if !matched {
+				changes.push(Change::CommentAdded { section, comment });
+			}
+		}
+	}
+
+	fn removed(&self, changes: &mut Vec<Change<'a>>) {
+		let section = self.name;
+		if let Some(name) = section {
+			changes.push(Change::SectionRemoved(name));
+		}
+		for &(key, value) in &self.props {
+			changes.push(Change::PropertyRemoved { section, key, value });
+		}
+		for &comment in &self.comments {
+			changes.push(Change::CommentRemoved { section, comment });
+		}
+	}
+
+	fn added(&self, changes: &mut Vec<Change<'a>>) {
+		let section = self.name;
+		if let Some(name) = section {
+			changes.push(Change::SectionAdded(name));
+		}
+		for &(key, value) in &self.props {
+			changes.push(Change::PropertyAdded { section, key, value });
+		}
+		for &comment in &self.comments {
+			changes.push(Change::CommentAdded { section, comment });
+		}
+	}
+}
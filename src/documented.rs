@@ -0,0 +1,47 @@
+use alloc::vec::Vec;
+use crate::{Item, Parser};
+
+/// Iterator over the properties of a document with the comments documenting them.
+///
+/// See [`Parser::documented`].
+#[derive(Clone, Debug)]
+pub struct Documented<'a> {
+	parser: Parser<'a>,
+	comments: Vec<&'a str>,
+}
+
+impl<'a> Iterator for Documented<'a> {
+	type Item = (Vec<&'a str>, Item<'a>);
+
+	fn next(&mut self) -> Option<(Vec<&'a str>, Item<'a>)> {
+		loop {
+			match self.parser.next()? {
+				Item::Comment(comment) => self.comments.push(comment),
+				item @ Item::Property(..) => return Some((core::mem::take(&mut self.comments), item)),
+				_ => self.comments.clear(),
+			}
+		}
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Documented<'a> {}
+
+impl<'a> Parser<'a> {
+	/// Returns an iterator over the properties with the block of comments directly above them.
+	///
+	/// Blank lines, section headers and malformed lines end the comment block, the comments above them are dropped.
+	///
+	/// ```
+	/// let document = "; Stray\n\n; Window width\n; In pixels\nwidth=640\nheight=480\n; Documents the section\n[Audio]\n;Volume\nvolume=11";
+	/// let properties: Vec<_> = ini_core::Parser::new(document).documented().collect();
+	/// assert_eq!(properties, [
+	/// 	(vec![" Window width", " In pixels"], ini_core::Item::Property("width", Some("640"))),
+	/// 	(vec![], ini_core::Item::Property("height", Some("480"))),
+	/// 	(vec!["Volume"], ini_core::Item::Property("volume", Some("11"))),
+	/// ]);
+	/// ```
+	#[inline]
+	pub fn documented(self) -> Documented<'a> {
+		Documented { parser: self, comments: Vec::new() }
+	}
+}
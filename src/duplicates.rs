@@ -0,0 +1,91 @@
+use alloc::{borrow::Cow, collections::{BTreeMap, BTreeSet}};
+use crate::{Item, Parser};
+
+/// Iterator over duplicate keys.
+///
+/// See [`Parser::duplicate_keys`].
+#[derive(Clone, Debug)]
+pub struct Duplicates<'a> {
+	parser: Parser<'a>,
+	ignore_case: bool,
+	seen: BTreeMap<Option<Cow<'a, str>>, BTreeSet<Cow<'a, str>>>,
+}
+
+impl<'a> Duplicates<'a> {
+	/// Sets comparing section names and keys ignoring ASCII case.
+	///
+	/// The default is `false`.
+	#[must_use]
+	#[inline]
+	pub fn ignore_case(self, ignore_case: bool) -> Duplicates<'a> {
+		Duplicates { ignore_case, ..self }
+	}
+
+	// Records the item, returns if it is a property repeating a key of its section
+	pub(crate) fn is_duplicate(&mut self, item: Item<'a>) -> bool {
+		let Item::Property(key, _) = item else { return false };
+		// Properties after a malformed section header belong to no section
+		let Some(section) = self.parser.lookup_section() else { return false };
+		let section = section.map(|name| self.case_fold(name));
+		let folded = self.case_fold(key);
+		!self.seen.entry(section).or_default().insert(folded)
+	}
+
+	// The parser driving the iterator
+	#[inline]
+	pub(crate) fn parser(&mut self) -> &mut Parser<'a> {
+		&mut self.parser
+	}
+
+	fn case_fold(&self, s: &'a str) -> Cow<'a, str> {
+		if self.ignore_case && s.bytes().any(|chr| chr.is_ascii_uppercase()) {
+			Cow::Owned(s.to_ascii_lowercase())
+		}
+		else {
+			Cow::Borrowed(s)
+		}
+	}
+}
+
+impl<'a> Iterator for Duplicates<'a> {
+	type Item = (Option<&'a str>, &'a str);
+
+	fn next(&mut self) -> Option<(Option<&'a str>, &'a str)> {
+		loop {
+			let item = self.parser.next()?;
+			if let Item::Property(key, _) = item {
+				if self.is_duplicate(item) {
+					return Some((self.parser.current_section(), key));
+				}
+			}
+		}
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Duplicates<'a> {}
+
+impl<'a> Parser<'a> {
+	/// Finds keys which appear more than once in a section.
+	///
+	/// Returns the section name and key of every repeated property as it is found.
+	/// The global section before the first section header is its own scope.
+	/// Duplicate sections are treated as the same scope, like [`into_map`](Parser::into_map) merges them.
+	/// The properties after a malformed section header are not checked.
+	///
+	/// Names are compared exactly, enable [`auto_trim`](Parser::auto_trim) to ignore padding whitespace and [`ignore_case`](Duplicates::ignore_case) to ignore ASCII case.
+	///
+	/// ```
+	/// let document = "k=1\n[A]\nk=2\nK=3\nk=4\n[B]\nk=5\n[A]\nk=6";
+	/// let duplicates: Vec<_> = ini_core::Parser::new(document).duplicate_keys().collect();
+	/// assert_eq!(duplicates, [(Some("A"), "k"), (Some("A"), "k")]);
+	///
+	/// let duplicates = ini_core::Parser::new(document).duplicate_keys().ignore_case(true).count();
+	/// assert_eq!(duplicates, 3);
+	///
+	/// assert!(ini_core::Parser::new("a=1\nb=2").duplicate_keys().next().is_none());
+	/// ```
+	#[inline]
+	pub fn duplicate_keys(self) -> Duplicates<'a> {
+		Duplicates { parser: self, ignore_case: false, seen: BTreeMap::new() }
+	}
+}
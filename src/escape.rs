@@ -0,0 +1,169 @@
+use core::fmt;
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
+use crate::Item;
+
+#[inline]
+fn needs_escape(chr: char) -> bool {
+	chr.is_ascii_control() || matches!(chr, '\\' | ';' | '#' | '=')
+}
+
+pub(crate) fn write_escaped<W: fmt::Write + ?Sized>(w: &mut W, s: &str) -> fmt::Result {
+	let mut rest = s;
+	while let Some(i) = rest.find(needs_escape) {
+		w.write_str(&rest[..i])?;
+		match rest.as_bytes()[i] {
+			b'\n' => w.write_str("\\n")?,
+			b'\r' => w.write_str("\\r")?,
+			b'\t' => w.write_str("\\t")?,
+			chr @ (b'\\' | b';' | b'#' | b'=') => {
+				w.write_char('\\')?;
+				w.write_char(chr as char)?;
+			},
+			chr => write!(w, "\\x{:02x}", chr)?,
+		}
+		rest = &rest[i + 1..];
+	}
+	w.write_str(rest)
+}
+
+/// Escapes special characters in the string.
+///
+/// Escapes `\` `;` `#` `=` as `\\` `\;` `\#` `\=`, newlines and tabs as `\n` `\r` `\t` and other ascii control characters as `\xHH`.
+/// Returns the string unchanged if nothing needs escaping.
+///
+/// The escaped string can be decoded with [`unescape`].
+///
+/// ```
+/// assert_eq!(ini_core::escape("a=b;\n"), "a\\=b\\;\\n");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn escape(s: &str) -> Cow<'_, str> {
+	if !s.contains(needs_escape) {
+		return Cow::Borrowed(s);
+	}
+	let mut result = String::with_capacity(s.len() + 8);
+	let _ = write_escaped(&mut result, s);
+	Cow::Owned(result)
+}
+
+/// Display adapter escaping the strings of an item.
+///
+/// See [`Item::display_escaped`].
+#[derive(Copy, Clone, Debug)]
+pub struct DisplayEscaped<'a>(pub(crate) Item<'a>);
+
+impl<'a> fmt::Display for DisplayEscaped<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.0 {
+			Item::Error(error) => write_escaped(f, error)?,
+			Item::Section(section) => {
+				f.write_str("[")?;
+				write_escaped(f, section)?;
+				f.write_str("]")?;
+			},
+			Item::SectionEnd => return Ok(()),
+			Item::Property(key, value) => {
+				write_escaped(f, key)?;
+				if let Some(value) = value {
+					f.write_str("=")?;
+					write_escaped(f, value)?;
+				}
+			},
+			Item::Comment(comment) => {
+				f.write_str(";")?;
+				write_escaped(f, comment)?;
+			},
+			Item::Blank => {},
+		}
+		f.write_str("\n")
+	}
+}
+
+/// Decodes escape sequences in the string.
+///
+/// Decodes `\n` `\r` `\t` `\\` `\;` `\#` `\=`, `\xHH` with exactly two hex digits and `\u{H...}` with up to six hex digits.
+/// Unknown or malformed escape sequences are kept as is.
+/// Returns the string unchanged if it contains no backslashes.
+///
+/// ```
+/// assert_eq!(ini_core::unescape(r"a\=b\;\n\x41\u{1F600}"), "a=b;\nA😀");
+/// assert_eq!(ini_core::unescape(r"C:\path"), r"C:\path");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn unescape(s: &str) -> Cow<'_, str> {
+	if !s.contains('\\') {
+		return Cow::Borrowed(s);
+	}
+	let mut result = String::with_capacity(s.len());
+	let mut rest = s;
+	while let Some(i) = rest.find('\\') {
+		result.push_str(&rest[..i]);
+		rest = &rest[i + 1..];
+		match decode(rest) {
+			Some((chr, len)) => {
+				result.push(chr);
+				rest = &rest[len..];
+			},
+			None => result.push('\\'),
+		}
+	}
+	result.push_str(rest);
+	Cow::Owned(result)
+}
+
+/// Decodes the escapes of a quoted value.
+///
+/// Removes the backslash escaping the character following it, eg. `\"` and `\\`, see [`Parser::quoted_values`](crate::Parser::quoted_values).
+/// Returns the string unchanged if it contains no backslashes.
+///
+/// ```
+/// assert_eq!(ini_core::unescape_quotes(r#"say \"hi\" \\o/"#), r#"say "hi" \o/"#);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn unescape_quotes(s: &str) -> Cow<'_, str> {
+	if !s.contains('\\') {
+		return Cow::Borrowed(s);
+	}
+	let mut result = String::with_capacity(s.len());
+	let mut chars = s.chars();
+	while let Some(chr) = chars.next() {
+		match chr {
+			'\\' => result.extend(chars.next()),
+			chr => result.push(chr),
+		}
+	}
+	Cow::Owned(result)
+}
+
+// Decodes the escape sequence following a backslash, returns the char and the length of the sequence
+#[cfg(feature = "alloc")]
+fn decode(s: &str) -> Option<(char, usize)> {
+	match s.as_bytes().first()? {
+		b'n' => Some(('\n', 1)),
+		b'r' => Some(('\r', 1)),
+		b't' => Some(('\t', 1)),
+		&chr @ (b'\\' | b';' | b'#' | b'=') => Some((chr as char, 1)),
+		b'x' => {
+			let hex = s.get(1..3)?;
+			Some((hex_value(hex).and_then(char::from_u32)?, 3))
+		},
+		b'u' => {
+			let s = s.strip_prefix("u{")?;
+			let end = s.find('}')?;
+			if end > 6 {
+				return None;
+			}
+			Some((hex_value(&s[..end]).and_then(char::from_u32)?, end + 3))
+		},
+		_ => None,
+	}
+}
+
+#[cfg(feature = "alloc")]
+fn hex_value(s: &str) -> Option<u32> {
+	if s.is_empty() || !s.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+		return None;
+	}
+	u32::from_str_radix(s, 16).ok()
+}
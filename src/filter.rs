@@ -0,0 +1,108 @@
+use crate::{Item, Parser};
+
+/// Iterator over the items of a document without blank lines and comments.
+///
+/// See [`Parser::significant`].
+#[derive(Clone, Debug)]
+pub struct Significant<'a> {
+	parser: Parser<'a>,
+	section_ends: bool,
+}
+
+impl<'a> Significant<'a> {
+	#[inline]
+	pub(crate) const fn new(parser: Parser<'a>) -> Significant<'a> {
+		Significant { parser, section_ends: true }
+	}
+
+	/// Sets returning [`Item::SectionEnd`].
+	///
+	/// The default is `true`.
+	#[must_use]
+	#[inline]
+	pub const fn section_ends(self, section_ends: bool) -> Significant<'a> {
+		Significant { section_ends, ..self }
+	}
+}
+
+impl<'a> Iterator for Significant<'a> {
+	type Item = Item<'a>;
+
+	#[inline]
+	fn next(&mut self) -> Option<Item<'a>> {
+		loop {
+			match self.parser.next()? {
+				Item::Blank | Item::Comment(_) => (),
+				Item::SectionEnd if !self.section_ends => (),
+				item => return Some(item),
+			}
+		}
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Significant<'a> {}
+
+/// Iterator over the properties of a document.
+///
+/// See [`Parser::properties`].
+#[derive(Clone, Debug)]
+pub struct Properties<'a> {
+	parser: Parser<'a>,
+}
+
+impl<'a> Properties<'a> {
+	#[inline]
+	pub(crate) const fn new(parser: Parser<'a>) -> Properties<'a> {
+		Properties { parser }
+	}
+}
+
+impl<'a> Iterator for Properties<'a> {
+	type Item = (&'a str, Option<&'a str>);
+
+	#[inline]
+	fn next(&mut self) -> Option<(&'a str, Option<&'a str>)> {
+		loop {
+			if let Some(property) = self.parser.next()?.as_property() {
+				return Some(property);
+			}
+		}
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Properties<'a> {}
+
+/// Iterator over the properties of a section.
+///
+/// See [`Parser::properties_of`].
+#[derive(Debug)]
+pub struct PropertiesOf<'p, 'a> {
+	parser: &'p mut Parser<'a>,
+	done: bool,
+}
+
+impl<'p, 'a> PropertiesOf<'p, 'a> {
+	#[inline]
+	pub(crate) fn new(parser: &'p mut Parser<'a>, name: &str) -> PropertiesOf<'p, 'a> {
+		let done = !parser.skip_to_section(name);
+		PropertiesOf { parser, done }
+	}
+}
+
+impl<'p, 'a> Iterator for PropertiesOf<'p, 'a> {
+	type Item = (&'a str, Option<&'a str>);
+
+	#[inline]
+	fn next(&mut self) -> Option<(&'a str, Option<&'a str>)> {
+		while !self.done {
+			match self.parser.next() {
+				Some(Item::SectionEnd) | None => self.done = true,
+				Some(Item::Property(key, value)) => return Some((key, value)),
+				Some(_) => (),
+			}
+		}
+		None
+	}
+}
+
+impl<'p, 'a> core::iter::FusedIterator for PropertiesOf<'p, 'a> {}
@@ -0,0 +1,71 @@
+use core::fmt;
+use heapless::Vec;
+use crate::{Item, Parser};
+
+/// Properties of a section collected by [`Parser::collect_heapless`].
+pub type FixedSection<'a, const M: usize> = (Option<&'a str>, Vec<(&'a str, Option<&'a str>), M>);
+
+/// Document collected by [`Parser::collect_heapless`].
+pub type FixedDocument<'a, const N: usize, const M: usize> = Vec<FixedSection<'a, M>, N>;
+
+/// Error returned by [`Parser::collect_heapless`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CapacityError {
+	/// The document has more than `N` sections.
+	Sections,
+	/// A section has more than `M` properties.
+	Properties,
+}
+
+impl fmt::Display for CapacityError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			CapacityError::Sections => "too many sections",
+			CapacityError::Properties => "too many properties",
+		})
+	}
+}
+
+impl core::error::Error for CapacityError {}
+
+impl<'a> Parser<'a> {
+	/// Collects the properties into fixed capacity vectors without allocating.
+	///
+	/// Returns at most `N` sections with at most `M` properties each, the global section has name `None`.
+	/// Sections are in document order and duplicate sections are merged, sections without properties are skipped.
+	///
+	/// Comments, blank lines and errors are skipped.
+	///
+	/// ```
+	/// let document = "a=1\n[S]\nb=2\n;comment\nc";
+	/// let sections = ini_core::Parser::new(document).collect_heapless::<4, 8>().unwrap();
+	/// assert_eq!(sections.len(), 2);
+	/// assert_eq!(sections[0].0, None);
+	/// assert_eq!(sections[1].0, Some("S"));
+	/// assert_eq!(sections[1].1, [("b", Some("2")), ("c", None)]);
+	///
+	/// let error = ini_core::Parser::new(document).collect_heapless::<1, 8>().unwrap_err();
+	/// assert_eq!(error, ini_core::CapacityError::Sections);
+	/// ```
+	pub fn collect_heapless<const N: usize, const M: usize>(self) -> Result<FixedDocument<'a, N, M>, CapacityError> {
+		let mut sections = FixedDocument::<N, M>::new();
+		let mut section = None;
+		for item in self {
+			match item {
+				Item::Section(name) => section = Some(name),
+				Item::Property(key, value) => {
+					let index = match sections.iter().position(|&(name, _)| name == section) {
+						Some(index) => index,
+						None => {
+							sections.push((section, Vec::new())).map_err(|_| CapacityError::Sections)?;
+							sections.len() - 1
+						},
+					};
+					sections[index].1.push((key, value)).map_err(|_| CapacityError::Properties)?;
+				},
+				_ => (),
+			}
+		}
+		Ok(sections)
+	}
+}
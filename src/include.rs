@@ -0,0 +1,167 @@
+use std::{fmt, fs, io};
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+use crate::{trim, Item, OwnedItem, Parser};
+
+/// Include directive.
+///
+/// See [`Includes`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Directive<'a> {
+	/// Line starting with the prefix followed by whitespace and the path, eg. `@include path`.
+	Prefix(&'a str),
+	/// Property with the key and the path as its value, eg. `include=path`.
+	Key(&'a str),
+}
+
+impl<'a> Directive<'a> {
+	// Returns the path if the item is an include directive
+	fn path(&self, item: &Item<'a>) -> Option<&'a str> {
+		match (*self, *item) {
+			(Directive::Prefix(prefix), Item::Property(key, None)) => {
+				let path = key.strip_prefix(prefix)?;
+				if !path.starts_with(|chr: char| chr.is_ascii_whitespace()) {
+					return None;
+				}
+				Some(trim(path))
+			},
+			(Directive::Key(name), Item::Property(key, Some(path))) if trim(key) == name => Some(trim(path)),
+			_ => None,
+		}
+	}
+}
+
+/// Error returned by [`Includes::parse`].
+#[derive(Debug)]
+pub enum IncludeError {
+	/// Reading the file failed.
+	Io(PathBuf, io::Error),
+	/// The file includes itself, directly or indirectly.
+	Cycle(PathBuf),
+	/// The file is nested deeper than the maximum depth.
+	TooDeep(PathBuf),
+}
+
+impl IncludeError {
+	/// Returns the path of the file which caused the error.
+	#[inline]
+	pub fn path(&self) -> &Path {
+		match self {
+			IncludeError::Io(path, _) | IncludeError::Cycle(path) | IncludeError::TooDeep(path) => path,
+		}
+	}
+}
+
+impl fmt::Display for IncludeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			IncludeError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+			IncludeError::Cycle(path) => write!(f, "{}: include cycle", path.display()),
+			IncludeError::TooDeep(path) => write!(f, "{}: includes nested too deep", path.display()),
+		}
+	}
+}
+
+impl std::error::Error for IncludeError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			IncludeError::Io(_, err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+/// Parses files resolving include directives.
+///
+/// The items of the included file replace the directive as if its text was pasted in its place.
+/// Relative paths are resolved against the directory of the including file.
+///
+/// ```no_run
+/// use ini_core as ini;
+///
+/// let includes = ini::Includes::new(ini::Directive::Key("include")).max_depth(4);
+/// for item in includes.parse("config.ini").unwrap() {
+/// 	println!("{}", item);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Includes<'a> {
+	directive: Directive<'a>,
+	max_depth: u32,
+	options: Parser<'static>,
+}
+
+impl<'a> Includes<'a> {
+	/// Constructs a new `Includes` instance.
+	#[inline]
+	pub fn new(directive: Directive<'a>) -> Includes<'a> {
+		Includes { directive, max_depth: 16, options: Parser::new("") }
+	}
+
+	/// Sets the maximum nesting depth of included files.
+	///
+	/// The default is `16`.
+	#[must_use]
+	#[inline]
+	pub fn max_depth(self, max_depth: u32) -> Includes<'a> {
+		Includes { max_depth, ..self }
+	}
+
+	/// Sets the parser options, the input of the given parser is ignored.
+	#[must_use]
+	#[inline]
+	pub fn options(self, options: Parser<'static>) -> Includes<'a> {
+		Includes { options, ..self }
+	}
+
+	/// Parses the file and the files it includes.
+	pub fn parse<P: AsRef<Path>>(&self, path: P) -> Result<Vec<OwnedItem>, IncludeError> {
+		let mut items = Vec::new();
+		let mut stack = Vec::new();
+		self.include(path.as_ref(), &mut stack, &mut items)?;
+		Ok(items)
+	}
+
+	fn include(&self, path: &Path, stack: &mut Vec<PathBuf>, items: &mut Vec<OwnedItem>) -> Result<(), IncludeError> {
+		let path = fs::canonicalize(path).map_err(|err| IncludeError::Io(path.into(), err))?;
+		if stack.contains(&path) {
+			return Err(IncludeError::Cycle(path));
+		}
+		if stack.len() > self.max_depth as usize {
+			return Err(IncludeError::TooDeep(path));
+		}
+		let document = fs::read_to_string(&path).map_err(|err| IncludeError::Io(path.clone(), err))?;
+		let dir = path.parent().unwrap_or(Path::new(""));
+		stack.push(path.clone());
+		let parser = Parser { bytes: self.options.bytes.with_input(document.as_bytes()) };
+		for item in parser {
+			if let Some(include) = self.directive.path(&item) {
+				self.include(&dir.join(include), stack, items)?;
+				// Drop the final section end of the included file
+				if items.last() == Some(&OwnedItem::SectionEnd) {
+					items.pop();
+				}
+			}
+			else {
+				items.push(item.to_owned());
+			}
+		}
+		stack.pop();
+		Ok(())
+	}
+}
+
+impl<'a> Default for Includes<'a> {
+	#[inline]
+	fn default() -> Includes<'a> {
+		Includes::new(Directive::Prefix("@include"))
+	}
+}
+
+/// Parses the file resolving `@include path` directives.
+///
+/// Shorthand for [`Includes::parse`] with the default options.
+#[inline]
+pub fn parse_with_includes<P: AsRef<Path>>(path: P) -> Result<Vec<OwnedItem>, IncludeError> {
+	Includes::default().parse(path)
+}
@@ -0,0 +1,88 @@
+use std::io::{self, BufRead};
+use crate::{OwnedItem, ParseError, Parser, StreamParser};
+
+/// Ini parser reading from a [`BufRead`].
+///
+/// Parses the input as it is read into a reused buffer instead of reading the whole document into a string first.
+/// Items are returned as [`OwnedItem`] as they cannot borrow the buffer.
+/// The items are the same as [`Parser`] returns for the whole document.
+///
+/// Invalid UTF-8 is returned as an error of kind [`io::ErrorKind::InvalidData`], parsing continues with the next line.
+///
+/// ```
+/// use ini_core as ini;
+///
+/// let reader = std::io::Cursor::new("[Section]\nKey=Value");
+/// let items: Vec<_> = ini::IoParser::new(reader, ini::Parser::new("")).collect::<std::io::Result<_>>().unwrap();
+/// assert_eq!(items, [
+/// 	ini::Item::SectionEnd,
+/// 	ini::Item::Section("Section"),
+/// 	ini::Item::Property("Key", Some("Value")),
+/// 	ini::Item::SectionEnd,
+/// ]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IoParser<R> {
+	reader: R,
+	stream: StreamParser,
+}
+
+impl<R: BufRead> IoParser<R> {
+	/// Constructs a new `IoParser` instance.
+	///
+	/// Uses the options of the given parser, its input is ignored.
+	/// [`Parser::skip_bom`] only applies to the input of the parser, use [`skip_bom`](IoParser::skip_bom) instead.
+	#[inline]
+	pub fn new(reader: R, options: Parser<'static>) -> IoParser<R> {
+		IoParser { reader, stream: StreamParser::new(options) }
+	}
+
+	/// Sets skipping a leading UTF-8 byte order mark.
+	///
+	/// See [`StreamParser::skip_bom`].
+	#[must_use]
+	#[inline]
+	pub fn skip_bom(self, skip_bom: bool) -> IoParser<R> {
+		IoParser { stream: self.stream.skip_bom(skip_bom), ..self }
+	}
+
+	/// Returns the number of lines consumed so far.
+	#[inline]
+	pub const fn line(&self) -> u32 {
+		self.stream.line()
+	}
+
+	/// Returns the error which stopped parsing in strict mode.
+	///
+	/// See [`Parser::strict`].
+	#[inline]
+	pub const fn error(&self) -> Option<ParseError> {
+		self.stream.error()
+	}
+
+	/// Returns the underlying reader.
+	#[inline]
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+}
+
+impl<R: BufRead> Iterator for IoParser<R> {
+	type Item = io::Result<OwnedItem>;
+
+	fn next(&mut self) -> Option<io::Result<OwnedItem>> {
+		loop {
+			match self.stream.next() {
+				Some(Ok(item)) => return Some(Ok(item.to_owned())),
+				Some(Err(err)) => return Some(Err(io::Error::new(io::ErrorKind::InvalidData, err))),
+				None => (),
+			}
+			if self.stream.is_finished() {
+				return None;
+			}
+			if let Err(err) = self.stream.read_from(&mut self.reader) {
+				return Some(Err(err));
+			}
+		}
+	}
+}
@@ -0,0 +1,103 @@
+use alloc::{borrow::ToOwned, string::String, vec};
+use serde_json::{map::Entry, Map, Value};
+use crate::{from_utf8, Item, Parser};
+
+/// Converts a document to a JSON value.
+///
+/// Shorthand for [`Parser::into_json_value`] with [`auto_trim`](Parser::auto_trim) enabled.
+///
+/// ```
+/// use serde_json::json;
+///
+/// let document = "; Comment\nname = app\n\n[window]\nwidth = 640\nflag\n[paths]\ndir = /usr\ndir = /opt\n[empty]";
+/// assert_eq!(ini_core::to_json_value(document), json!({
+/// 	"name": "app",
+/// 	"window": { "width": "640", "flag": null },
+/// 	"paths": { "dir": ["/usr", "/opt"] },
+/// 	"empty": {},
+/// }));
+/// ```
+pub fn to_json_value(s: &str) -> Value {
+	Parser::new(s).auto_trim(true).into_json_value()
+}
+
+// Adds the value, repeated keys collect their values into an array
+fn insert(table: &mut Map<String, Value>, key: String, value: Option<&str>) {
+	let value = value.map_or(Value::Null, |value| Value::String(value.to_owned()));
+	match table.entry(key) {
+		Entry::Vacant(entry) => {
+			entry.insert(value);
+		},
+		Entry::Occupied(mut entry) => match entry.get_mut() {
+			Value::Array(values) => values.push(value),
+			prev => *prev = Value::Array(vec![prev.take(), value]),
+		},
+	}
+}
+
+// Returns the object of the section, it replaces a global property with the same name
+fn section_table<'m>(root: &'m mut Map<String, Value>, name: &str) -> &'m mut Map<String, Value> {
+	if !root.get(name).is_some_and(Value::is_object) {
+		root.insert(name.to_owned(), Value::Object(Map::new()));
+	}
+	match root.get_mut(name) {
+		Some(Value::Object(table)) => table,
+		_ => unreachable!(),
+	}
+}
+
+impl<'a> Parser<'a> {
+	/// Converts the document to a JSON value.
+	///
+	/// The result is an object with the global properties followed by an object for every section:
+	///
+	/// * Property values are strings, keys without a value are `null`.
+	/// * Repeated keys within a section become an array of their values in document order.
+	/// * Duplicate sections are merged, sections without properties are empty objects.
+	/// * A section replaces a global property with the same name, later global properties with that name are dropped.
+	/// * Comments, blank lines and errors are dropped, as are the properties after a malformed section header.
+	///
+	/// The keys of the objects are sorted.
+	/// With [`global_section_name`](Parser::global_section_name) set the global properties are put in a section object of that name instead.
+	/// With [`case_insensitive`](Parser::case_insensitive) enabled section names and keys are lowercased.
+	///
+	/// ```
+	/// use serde_json::json;
+	///
+	/// let value = ini_core::Parser::new("a=1\n[DEFAULT]\nb=2").global_section_name("DEFAULT").into_json_value();
+	/// assert_eq!(value, json!({ "DEFAULT": { "a": "1", "b": "2" } }));
+	/// ```
+	pub fn into_json_value(self) -> Value {
+		let case_insensitive = self.bytes.is_case_insensitive();
+		let fold = |s: &str| if case_insensitive { s.to_ascii_lowercase() } else { s.to_owned() };
+		let global = self.bytes.global_name().map(|name| fold(from_utf8(name)));
+		let mut root = Map::new();
+		let mut section = global.clone();
+		let mut parser = self;
+		while let Some(item) = parser.next() {
+			match item {
+				Item::Section(name) => {
+					let name = fold(name);
+					section_table(&mut root, &name);
+					section = Some(name);
+				},
+				// Empty section headers may return to the global section
+				Item::SectionEnd if parser.in_global_section() => section = global.clone(),
+				// Properties after a malformed section header belong to no section
+				Item::Property(..) if parser.lookup_section().is_none() => (),
+				Item::Property(key, value) => {
+					let key = fold(key);
+					match &section {
+						Some(name) => insert(section_table(&mut root, name), key, value),
+						// Sections take precedence over global properties
+						None => if !root.get(&key).is_some_and(Value::is_object) {
+							insert(&mut root, key, value);
+						},
+					}
+				},
+				_ => (),
+			}
+		}
+		Value::Object(root)
+	}
+}
@@ -62,8 +62,8 @@ INI is not a well specified format, this parser tries to make as little assumpti
 
 * Newline is either `"\r\n"`, `"\n"` or `"\r"`. It can be mixed in a single document but this is not recommended.
 * Section header is `"[" section "]" newline`. `section` can be anything except contain newlines.
-* Property is `key "=" value newline`. `key` and `value` can be anything except contain newlines.
-* Comment is `";" comment newline` and Blank is just `newline`. The comment character can be customized.
+* Property is `key "=" value newline`. `key` and `value` can be anything except contain newlines. The separator character can be customized.
+* Comment is `";" comment newline` and Blank is just `newline`. The comment characters can be customized.
 
 Note that padding whitespace is not trimmed by default:
 
@@ -72,38 +72,202 @@ Note that padding whitespace is not trimmed by default:
 * Comment `; comment`'s comment is `<space>comment`.
 
 No further processing of the input is done, eg. if escape sequences are necessary they must be processed by the caller.
+The `alloc` feature provides `escape` and `unescape` helpers for common escape sequences and `expand_vars` for `${NAME}` placeholders.
+It also provides `Parser::into_map` for collecting a document into a map and `OwnedItem` for keeping items past the lifetime of the input.
+`StreamParser` parses a document fed in chunks, eg. from a socket, buffering partial lines.
+The `std` feature provides `IoParser` for parsing directly from a `BufRead` reader and `Includes` for resolving include directives.
+The `serde` feature provides deserializing documents into structs in the `de` module and serializing them back in the `ser` module.
+The `heapless` feature provides `Parser::collect_heapless` for collecting a document into fixed capacity vectors without allocating.
+The `json` feature provides `to_json_value` for converting a document to a `serde_json::Value`.
+The `toml` feature provides `to_toml_string` for converting a document to TOML.
+The `runtime-dispatch` feature requires `std` and selects the fastest SIMD implementation for the CPU at runtime instead of compile time.
+The `checked-utf8` feature checks the conversion of every returned slice to `&str` in release builds, this is always done in debug builds.
+The `safe` feature forbids all `unsafe` code, the portable scalar routines are used instead of SIMD and UTF-8 conversions are checked.
+The `memchr` feature uses the `memchr` crate to find newlines instead of the built-in SIMD routines and `runtime-dispatch`, `safe` takes precedence.
+
+Documents which aren't known to be valid UTF-8 can be parsed with [`BytesParser`], it returns [`ByteItem`]s borrowing raw byte slices.
+The `alloc` feature provides `BytesParser::lossy` for decoding them with invalid UTF-8 sequences replaced.
 */
 
 #![cfg_attr(not(test), no_std)]
+#![cfg_attr(feature = "safe", forbid(unsafe_code))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(all(any(feature = "std", feature = "runtime-dispatch"), not(test)))]
+extern crate std;
 
 #[allow(unused_imports)]
-use core::{fmt, str};
+use core::{fmt, ops, ops::Range, str};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
 
 // All the routines here work only with and slice only at ascii characters
 // This means conversion between `&str` and `&[u8]` is a noop even when slicing
+// The conversion is checked in debug builds and with the `checked-utf8` feature
 #[inline]
-fn from_utf8(v: &[u8]) -> &str {
-	#[cfg(not(debug_assertions))]
+const fn from_utf8(v: &[u8]) -> &str {
+	#[cfg(not(any(debug_assertions, feature = "checked-utf8")))]
 	return unsafe { str::from_utf8_unchecked(v) };
-	#[cfg(debug_assertions)]
-	return str::from_utf8(v).unwrap();
+	#[cfg(any(debug_assertions, feature = "checked-utf8"))]
+	return match str::from_utf8(v) {
+		Ok(s) => s,
+		Err(_) => panic!("invalid utf8"),
+	};
+}
+
+// Length of the newline at the start of the byte string
+#[inline]
+fn nl_len(s: &[u8]) -> usize {
+	if s.len() >= 2 && s[0] == b'\r' && s[1] == b'\n' { 2 } else { 1 }
 }
 
 mod parse;
 
+mod bytes;
+pub use self::bytes::{ByteItem, BytesParser, Position};
+
+mod escape;
+pub use self::escape::DisplayEscaped;
+#[cfg(feature = "alloc")]
+pub use self::escape::{escape, unescape, unescape_quotes};
+
+mod writer;
+pub use self::writer::Writer;
+#[cfg(feature = "alloc")]
+pub use self::writer::write_items;
+
+mod const_parser;
+pub use self::const_parser::ConstParser;
+
+mod canonical;
+pub use self::canonical::CanonicalItem;
+
+mod sections;
+pub use self::sections::{SectionItems, Sections};
+
+mod filter;
+pub use self::filter::{Properties, PropertiesOf, Significant};
+
+mod results;
+pub use self::results::{LineError, Results};
+
+mod visitor;
+pub use self::visitor::{parse_with, Visitor};
+
+#[cfg(feature = "alloc")]
+mod owned;
+#[cfg(feature = "alloc")]
+pub use self::owned::OwnedItem;
+
+#[cfg(feature = "alloc")]
+mod lossy;
+#[cfg(feature = "alloc")]
+pub use self::lossy::{Lossy, LossyItem};
+
+#[cfg(feature = "alloc")]
+mod map;
+#[cfg(feature = "alloc")]
+pub use self::map::{DuplicateKeys, Map, MultiMap};
+
+#[cfg(feature = "alloc")]
+mod duplicates;
+#[cfg(feature = "alloc")]
+pub use self::duplicates::Duplicates;
+
+#[cfg(feature = "alloc")]
+mod documented;
+#[cfg(feature = "alloc")]
+pub use self::documented::Documented;
+
+#[cfg(feature = "alloc")]
+mod vars;
+#[cfg(feature = "alloc")]
+pub use self::vars::{expand_vars, MissingVar, UndefinedVar};
+
+#[cfg(feature = "alloc")]
+mod validate;
+#[cfg(feature = "alloc")]
+pub use self::validate::{validate, Validator};
+
+#[cfg(feature = "alloc")]
+mod diff;
+#[cfg(feature = "alloc")]
+pub use self::diff::{diff, Change, Diff};
+
+#[cfg(feature = "alloc")]
+mod value;
+#[cfg(feature = "alloc")]
+pub use self::value::ValuePipeline;
+
+#[cfg(feature = "alloc")]
+mod merge;
+#[cfg(feature = "alloc")]
+pub use self::merge::merge;
+
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+pub use self::json::to_json_value;
+
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "toml")]
+pub use self::toml::{to_toml_string, Toml};
+
+#[cfg(feature = "alloc")]
+mod stream;
+#[cfg(feature = "alloc")]
+pub use self::stream::StreamParser;
+
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+pub use self::io::IoParser;
+#[cfg(feature = "std")]
+mod include;
+#[cfg(feature = "std")]
+pub use self::include::{parse_with_includes, Directive, IncludeError, Includes};
+
+#[cfg(feature = "heapless")]
+mod fixed;
+#[cfg(feature = "heapless")]
+pub use self::fixed::{CapacityError, FixedDocument, FixedSection};
+
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "serde")]
+pub mod ser;
+
 /// Ini element.
 ///
 /// # Notes
 ///
 /// Strings are not checked or escaped when displaying the item.
 ///
-/// Ensure that they do not contain newlines or invalid characters.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+/// Ensure that they do not contain newlines or invalid characters, or use [`display_escaped`](Item::display_escaped).
+///
+/// # Ordering
+///
+/// Items are ordered by variant in declaration order, then by their strings.
+/// A property without a value orders before the same key with any value.
+///
+/// ```
+/// use ini_core::Item;
+///
+/// let mut items = vec![Item::Blank, Item::Property("b", None), Item::Section("S"), Item::Property("a", Some("1"))];
+/// items.sort();
+/// assert_eq!(items, [Item::Section("S"), Item::Property("a", Some("1")), Item::Property("b", None), Item::Blank]);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Item<'a> {
 	/// Syntax error.
 	///
 	/// Section header element was malformed.
 	/// Malformed section headers are defined by a line starting with `[` but not ending with `]`.
+	/// In [strict](Parser::strict) mode property lines without a separator are also errors.
+	///
+	/// The whole line is returned, see [`error_kind`](Item::error_kind) for the reason.
 	///
 	/// ```
 	/// assert_eq!(
@@ -114,10 +278,15 @@ pub enum Item<'a> {
 
 	/// Section header element.
 	///
+	/// The name is everything between the `[` at the start and the `]` at the end of the line, including any brackets in between.
+	///
 	/// ```
 	/// assert_eq!(
 	/// 	ini_core::Parser::new("[Section]").nth(1),
 	/// 	Some(ini_core::Item::Section("Section")));
+	/// assert_eq!(
+	/// 	ini_core::Parser::new("[section][subsection][3]").nth(1),
+	/// 	Some(ini_core::Item::Section("section][subsection][3")));
 	/// ```
 	Section(&'a str),
 
@@ -170,185 +339,1964 @@ pub enum Item<'a> {
 	Blank,
 }
 
-impl<'a> fmt::Display for Item<'a> {
-	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl<'a> Item<'a> {
+	/// Returns if the item starts a section, ie. is a [`Section`](Item::Section).
+	///
+	/// The parser upholds the following invariants:
+	///
+	/// * Every section start is immediately preceded by a section end.
+	/// * A section end is followed by a section start, a malformed section header [`Error`](Item::Error) or the end of the document.
+	/// * The last item of the document is a section end.
+	///
+	/// Every section start and the end of the document are thus paired with the section end before them.
+	/// The first section end closes the global section, which has no start.
+	///
+	/// ```
+	/// let items: Vec<_> = ini_core::Parser::new("a=1\n[A]\nb=2").collect();
+	/// let starts: Vec<_> = items.iter().map(|item| item.is_section_start()).collect();
+	/// let ends: Vec<_> = items.iter().map(|item| item.is_section_end()).collect();
+	/// assert_eq!(starts, [false, false, true, false, false]);
+	/// assert_eq!(ends, [false, true, false, false, true]);
+	/// ```
+	#[inline]
+	pub const fn is_section_start(&self) -> bool {
+		matches!(self, Item::Section(_))
+	}
+
+	/// Returns if the item ends a section, ie. is a [`SectionEnd`](Item::SectionEnd).
+	///
+	/// See [`is_section_start`](Item::is_section_start) for the invariants.
+	#[inline]
+	pub const fn is_section_end(&self) -> bool {
+		matches!(self, Item::SectionEnd)
+	}
+
+	/// Returns if the item starts or ends a section.
+	#[inline]
+	pub const fn is_section_boundary(&self) -> bool {
+		matches!(self, Item::Section(_) | Item::SectionEnd)
+	}
+
+	/// Returns if the item is an [`Error`](Item::Error).
+	#[inline]
+	pub const fn is_error(&self) -> bool {
+		matches!(self, Item::Error(_))
+	}
+
+	/// Returns if the item is a [`Section`](Item::Section).
+	#[inline]
+	pub const fn is_section(&self) -> bool {
+		matches!(self, Item::Section(_))
+	}
+
+	/// Returns if the item is a [`Property`](Item::Property).
+	#[inline]
+	pub const fn is_property(&self) -> bool {
+		matches!(self, Item::Property(..))
+	}
+
+	/// Returns if the item is a [`Comment`](Item::Comment).
+	#[inline]
+	pub const fn is_comment(&self) -> bool {
+		matches!(self, Item::Comment(_))
+	}
+
+	/// Returns if the item is a [`Blank`](Item::Blank) line.
+	#[inline]
+	pub const fn is_blank(&self) -> bool {
+		matches!(self, Item::Blank)
+	}
+
+	/// Returns the reason if the item is an [`Error`](Item::Error).
+	///
+	/// The kind is guessed from the text of the line alone, assuming the default [`section_delimiters`](Parser::section_delimiters).
+	/// The kinds which depend on the parser options can't be recovered and are never returned:
+	///
+	/// * [`ErrorKind::LineTooLong`], the truncated line is reported as [`MissingSeparator`](ErrorKind::MissingSeparator) or a section error.
+	/// * [`ErrorKind::ControlChar`] and [`ErrorKind::UnterminatedQuote`], reported as [`MissingSeparator`](ErrorKind::MissingSeparator) or a section error.
+	/// * [`ErrorKind::DuplicateKey`], duplicate keys are not errors of the parser.
+	///
+	/// Use [`Parser::results`] for the kind according to the parser options.
+	///
+	/// ```
+	/// use ini_core as ini;
+	///
+	/// let kinds: Vec<_> = ini::Parser::new("[foo] \n[foo\n[").filter_map(|item| item.error_kind()).collect();
+	/// assert_eq!(kinds, [
+	/// 	ini::ErrorKind::TrailingAfterSection,
+	/// 	ini::ErrorKind::UnterminatedSection,
+	/// 	ini::ErrorKind::UnterminatedSection,
+	/// ]);
+	/// ```
+	#[inline]
+	pub fn error_kind(&self) -> Option<ErrorKind> {
 		match self {
-			&Item::Error(error) => write!(f, "{}\n", error),
-			&Item::Section(section) => write!(f, "[{}]\n", section),
-			&Item::SectionEnd => Ok(()),
-			&Item::Property(key, Some(value)) => write!(f, "{}={}\n", key, value),
-			&Item::Property(key, None) => write!(f, "{}\n", key),
-			&Item::Comment(comment) => write!(f, ";{}\n", comment),
-			&Item::Blank => f.write_str("\n"),
+			&Item::Error(error) => Some(ErrorKind::of(error.as_bytes(), b'[', b']')),
+			_ => None,
 		}
 	}
-}
 
-/// Trims ascii whitespace from the start and end of the string slice.
-///
-/// See also [`Parser::auto_trim`] to automatically trim strings.
-#[inline(never)]
-pub fn trim(s: &str) -> &str {
-	s.trim_matches(|chr: char| chr.is_ascii_whitespace())
-}
+	/// Returns the malformed line if the item is an [`Error`](Item::Error).
+	#[inline]
+	pub const fn as_error(self) -> Option<&'a str> {
+		match self {
+			Item::Error(error) => Some(error),
+			_ => None,
+		}
+	}
 
-/// Ini streaming parser.
-///
-/// The whole document must be available before parsing starts.
-/// The parser then returns each element as it is being parsed.
-///
-/// See [`crate`] documentation for more information.
-#[derive(Clone, Debug)]
-pub struct Parser<'a> {
-	line: u32,
-	comment_char: u8,
-	auto_trim: bool,
-	section_ended: bool,
-	state: &'a [u8],
-}
+	/// Returns the section name if the item is a [`Section`](Item::Section).
+	#[inline]
+	pub const fn as_section(self) -> Option<&'a str> {
+		match self {
+			Item::Section(section) => Some(section),
+			_ => None,
+		}
+	}
 
-impl<'a> Parser<'a> {
-	/// Constructs a new `Parser` instance.
+	/// Returns the key and value if the item is a [`Property`](Item::Property).
+	///
+	/// ```
+	/// let document = "[Section]\nKey=Value\n;comment\nFlag";
+	/// let properties: Vec<_> = ini_core::Parser::new(document).filter_map(ini_core::Item::as_property).collect();
+	/// assert_eq!(properties, [("Key", Some("Value")), ("Flag", None)]);
+	/// ```
 	#[inline]
-	pub const fn new(s: &'a str) -> Parser<'a> {
-		let state = s.as_bytes();
-		Parser { line: 0, comment_char: b';', auto_trim: false, section_ended: false, state }
+	pub const fn as_property(self) -> Option<(&'a str, Option<&'a str>)> {
+		match self {
+			Item::Property(key, value) => Some((key, value)),
+			_ => None,
+		}
 	}
 
-	/// Sets the comment character, eg. `b'#'`.
+	/// Returns the comment if the item is a [`Comment`](Item::Comment).
+	#[inline]
+	pub const fn as_comment(self) -> Option<&'a str> {
+		match self {
+			Item::Comment(comment) => Some(comment),
+			_ => None,
+		}
+	}
+
+	// The byte parser only slices the input at ascii characters
+	#[inline]
+	const fn from_bytes(item: ByteItem<'a>) -> Item<'a> {
+		match item {
+			ByteItem::Error(error) => Item::Error(from_utf8(error)),
+			ByteItem::Section(section) => Item::Section(from_utf8(section)),
+			ByteItem::SectionEnd => Item::SectionEnd,
+			ByteItem::Property(key, Some(value)) => Item::Property(from_utf8(key), Some(from_utf8(value))),
+			ByteItem::Property(key, None) => Item::Property(from_utf8(key), None),
+			ByteItem::Comment(comment) => Item::Comment(from_utf8(comment)),
+			ByteItem::Blank => Item::Blank,
+		}
+	}
+
+	/// Returns a display adapter which escapes special characters.
 	///
-	/// The default is `b';'`.
-	#[must_use]
+	/// Newlines, control characters, `\\`, `;`, `#` and `=` are escaped the same way as `escape`.
+	///
+	/// The output always occupies a single line.
+	/// Sections, comments, values and keys parse back as the same kind of item whose strings decode with `unescape` to the original, except for these keys:
+	///
+	/// * A key containing `=` is split at the escaped `=`, the parser splits on the first `=` regardless of escapes.
+	/// * A key starting with `[` parses back as a section header, usually a malformed one.
+	/// * An empty key without a value parses back as a blank line.
+	///
+	/// Use [`Writer`] with [`escape`](Writer::escape) enabled to reject these keys.
+	///
+	/// ```
+	/// use ini_core::{Item, Parser};
+	///
+	/// let item = Item::Property("k", Some("a\nb"));
+	/// assert_eq!(item.display_escaped().to_string(), "k=a\\nb\n");
+	///
+	/// let s = Item::Property("a=b", Some("c")).display_escaped().to_string();
+	/// assert_eq!(Parser::new(&s).next(), Some(Item::Property("a\\", Some("b=c"))));
+	/// ```
 	#[inline]
-	pub const fn comment_char(self, chr: u8) -> Parser<'a> {
-		// Mask off high bit to ensure we don't corrupt utf8 strings
-		let comment_char = chr & 0x7f;
-		Parser { comment_char, ..self }
+	pub const fn display_escaped(self) -> DisplayEscaped<'a> {
+		DisplayEscaped(self)
 	}
 
-	/// Sets auto trimming of all returned strings.
+	/// Returns a display adapter which ends the line with the given newline.
 	///
-	/// The default is `false`.
-	#[must_use]
+	/// Nothing is written after the item if the newline is `None`, eg. for the last line of a document without a trailing newline.
+	/// [`Item::SectionEnd`] is always displayed as nothing.
+	///
+	/// With trimming disabled and the default options, displaying every item with the newline which ended its line reproduces the document exactly, see [`Parser::next_with_newline`]:
+	///
+	/// ```
+	/// use ini_core::Parser;
+	///
+	/// let document = "[Section]\r\nKey=Value\n;comment\r\n\rFlag";
+	/// let mut parser = Parser::new(document);
+	/// let mut output = String::new();
+	/// while let Some((item, newline)) = parser.next_with_newline() {
+	/// 	output += &item.display_line(newline).to_string();
+	/// }
+	/// assert_eq!(output, document);
+	/// ```
 	#[inline]
-	pub const fn auto_trim(self, auto_trim: bool) -> Parser<'a> {
-		Parser { auto_trim, ..self }
+	pub const fn display_line(self, newline: Option<Newline>) -> DisplayLine<'a> {
+		DisplayLine { item: self, newline, comment_char: b';' }
 	}
 
-	/// Returns the line number the parser is currently at.
+	/// Returns a display adapter which writes comments with the given comment character, eg. `b'#'`.
+	///
+	/// Displays the item like its `Display` impl which always writes `;` before comments.
+	/// Use the comment character the document was parsed with to write it back, see also [`Parser::comment_prefix`].
+	///
+	/// ```
+	/// use ini_core::{Item, Parser};
+	///
+	/// let document = "#comment\nKey=Value\n";
+	/// let output: String = Parser::new(document).comment_char(b'#').map(|item| item.display_with_comment_char(b'#').to_string()).collect();
+	/// assert_eq!(output, document);
+	/// assert_eq!(Item::Comment("comment").to_string(), ";comment\n");
+	/// ```
 	#[inline]
-	pub const fn line(&self) -> u32 {
-		self.line
+	pub const fn display_with_comment_char(self, chr: u8) -> DisplayLine<'a> {
+		self.display_line(Some(Newline::Lf)).comment_char(chr)
 	}
 
-	/// Returns the remainder of the input string.
+	/// Writes the item after checking it can be parsed back.
+	///
+	/// Fails if any string contains a newline, a section name contains `]` or a key contains `=`.
+	/// Nothing is written if the item is invalid.
+	///
+	/// ```
+	/// let mut s = String::new();
+	/// assert_eq!(ini_core::Item::Property("Key", Some("Value")).write_checked(&mut s), Ok(()));
+	/// assert_eq!(ini_core::Item::Comment("a\nb").write_checked(&mut s), Err(ini_core::WriteError::Newline));
+	/// assert_eq!(s, "Key=Value\n");
+	/// ```
+	pub fn write_checked<W: fmt::Write + ?Sized>(&self, w: &mut W) -> Result<(), WriteError> {
+		self.validate()?;
+		write!(w, "{}", self)?;
+		Ok(())
+	}
+
+	fn validate(&self) -> Result<(), WriteError> {
+		let has_nl = |s: &str| s.contains(['\r', '\n']);
+		match self {
+			&Item::Error(error) if has_nl(error) => Err(WriteError::Newline),
+			&Item::Section(section) if has_nl(section) => Err(WriteError::Newline),
+			&Item::Section(section) if section.contains(']') => Err(WriteError::SectionBracket),
+			&Item::Property(key, value) if has_nl(key) || value.is_some_and(has_nl) => Err(WriteError::Newline),
+			&Item::Property(key, _) if key.contains('=') => Err(WriteError::KeySeparator),
+			&Item::Comment(comment) if has_nl(comment) => Err(WriteError::Newline),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Checked conversion from raw bytes.
+///
+/// Fails if any of the byte slices isn't valid UTF-8.
+///
+/// ```
+/// use ini_core::{BytesParser, Item};
+///
+/// let mut parser = BytesParser::new(b"Key=\xc3\xbc\nKey=\xff");
+/// assert_eq!(Item::try_from(parser.next().unwrap()), Ok(Item::Property("Key", Some("ü"))));
+/// assert!(Item::try_from(parser.next().unwrap()).is_err());
+/// ```
+impl<'a> TryFrom<ByteItem<'a>> for Item<'a> {
+	type Error = str::Utf8Error;
+
+	fn try_from(item: ByteItem<'a>) -> Result<Item<'a>, str::Utf8Error> {
+		Ok(match item {
+			ByteItem::Error(error) => Item::Error(str::from_utf8(error)?),
+			ByteItem::Section(section) => Item::Section(str::from_utf8(section)?),
+			ByteItem::SectionEnd => Item::SectionEnd,
+			ByteItem::Property(key, value) => Item::Property(str::from_utf8(key)?, value.map(str::from_utf8).transpose()?),
+			ByteItem::Comment(comment) => Item::Comment(str::from_utf8(comment)?),
+			ByteItem::Blank => Item::Blank,
+		})
+	}
+}
+
+impl<'a> fmt::Display for Item<'a> {
 	#[inline]
-	pub fn remainder(&self) -> &'a str {
-		from_utf8(self.state)
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.display_line(Some(Newline::Lf)), f)
 	}
 }
 
-impl<'a> Iterator for Parser<'a> {
-	type Item = Item<'a>;
+/// Display adapter ending the line with a specific newline.
+///
+/// See [`Item::display_line`].
+#[derive(Copy, Clone, Debug)]
+pub struct DisplayLine<'a> {
+	item: Item<'a>,
+	newline: Option<Newline>,
+	comment_char: u8,
+}
 
-	// #[cfg_attr(test, mutagen::mutate)]
-	#[inline(never)]
-	fn next(&mut self) -> Option<Item<'a>> {
-		let mut s = self.state;
-
-		match s.first().cloned() {
-			// Terminal case
-			None => {
-				if self.section_ended {
-					None
-				}
-				else {
-					self.section_ended = true;
-					Some(Item::SectionEnd)
-				}
-			},
-			// Blank
-			Some(b'\r' | b'\n') => {
-				self.skip_ln(s);
-				Some(Item::Blank)
-			},
-			// Comment
-			Some(chr) if chr == self.comment_char => {
-				s = &s[1..];
-				let i = parse::find_nl(s);
-				let comment = from_utf8(&s[..i]);
-				let comment = if self.auto_trim { trim(comment) } else { comment };
-				self.skip_ln(&s[i..]);
-				Some(Item::Comment(comment))
-			},
-			// Section
-			Some(b'[') => {
-				if self.section_ended {
-					self.section_ended = false;
-					let i = parse::find_nl(s);
-					if s[i - 1] != b']' {
-						let error = from_utf8(&s[..i]);
-						self.skip_ln(&s[i..]);
-						return Some(Item::Error(error));
-					}
-					let section = from_utf8(&s[1..i - 1]);
-					let section = if self.auto_trim { trim(section) } else { section };
-					self.skip_ln(&s[i..]);
-					Some(Item::Section(section))
-				}
-				else {
-					self.section_ended = true;
-					Some(Item::SectionEnd)
-				}
-			},
-			// Property
-			_ => {
-				let key = {
-					let i = parse::find_nl_chr(s, b'=');
-					let key = from_utf8(&s[..i]);
-					let key = if self.auto_trim { trim(key) } else { key };
-					if s.get(i) != Some(&b'=') {
-						self.skip_ln(&s[i..]);
-						if key.is_empty() {
-							return Some(Item::Blank);
-						}
-						return Some(Item::Property(key, None));
-					}
-					s = &s[i + 1..];
-					key
-				};
-				let value = {
-					let i = parse::find_nl(s);
-					let value = from_utf8(&s[..i]);
-					let value = if self.auto_trim { trim(value) } else { value };
-					self.skip_ln(&s[i..]);
-					value
-				};
-				Some(Item::Property(key, Some(value)))
-			},
+impl<'a> DisplayLine<'a> {
+	/// Sets the character written before comments, eg. `b'#'`.
+	///
+	/// The default is `b';'`.
+	///
+	/// ```
+	/// let item = ini_core::Item::Comment("note");
+	/// assert_eq!(item.display_line(None).comment_char(b'#').to_string(), "#note");
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn comment_char(self, chr: u8) -> DisplayLine<'a> {
+		// Mask off high bit to ensure we don't corrupt utf8 strings
+		DisplayLine { comment_char: chr & 0x7f, ..self }
+	}
+}
+
+impl<'a> fmt::Display for DisplayLine<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.item {
+			Item::Error(error) => f.write_str(error)?,
+			Item::Section(section) => write!(f, "[{}]", section)?,
+			Item::SectionEnd => return Ok(()),
+			Item::Property(key, Some(value)) => write!(f, "{}={}", key, value)?,
+			Item::Property(key, None) => f.write_str(key)?,
+			Item::Comment(comment) => write!(f, "{}{}", self.comment_char as char, comment)?,
+			Item::Blank => (),
+		}
+		match self.newline {
+			Some(newline) => f.write_str(newline.as_str()),
+			None => Ok(()),
 		}
 	}
 }
 
-impl<'a> core::iter::FusedIterator for Parser<'a> {}
+/// Trims ascii whitespace from the start and end of the string slice.
+///
+/// See also [`Parser::auto_trim`] to automatically trim strings.
+#[inline(never)]
+pub fn trim(s: &str) -> &str {
+	s.trim_matches(|chr: char| chr.is_ascii_whitespace())
+}
 
-impl<'a> Parser<'a> {
+/// Trims ascii whitespace from the start of the string slice.
+///
+/// See also [`Parser::auto_trim_start`] to automatically trim strings.
+#[inline(never)]
+pub fn trim_start(s: &str) -> &str {
+	s.trim_start_matches(|chr: char| chr.is_ascii_whitespace())
+}
+
+/// Trims ascii whitespace from the end of the string slice.
+///
+/// See also [`Parser::auto_trim_end`] to automatically trim strings.
+#[inline(never)]
+pub fn trim_end(s: &str) -> &str {
+	s.trim_end_matches(|chr: char| chr.is_ascii_whitespace())
+}
+
+/// Trims unicode whitespace from the start and end of the string slice.
+///
+/// Slower than the ascii only [`trim`], prefer that unless the document is known to contain unicode whitespace.
+///
+/// See also [`Parser::auto_trim_unicode`] to automatically trim strings.
+///
+/// ```
+/// assert_eq!(ini_core::trim_unicode("\u{3000} Value\u{a0}"), "Value");
+/// assert_eq!(ini_core::trim("\u{3000} Value\u{a0}"), "\u{3000} Value\u{a0}");
+/// ```
+#[inline(never)]
+pub fn trim_unicode(s: &str) -> &str {
+	s.trim()
+}
+
+/// Parses a boolean value.
+///
+/// Accepts `true`, `yes`, `on` and `1` as `true`, and `false`, `no`, `off` and `0` as `false`.
+/// The value is [`trim`]med and compared ignoring ascii case, anything else returns `None`.
+///
+/// ```
+/// assert_eq!(ini_core::parse_bool(" Yes "), Some(true));
+/// assert_eq!(ini_core::parse_bool("OFF"), Some(false));
+/// assert_eq!(ini_core::parse_bool("2"), None);
+/// ```
+pub fn parse_bool(value: &str) -> Option<bool> {
+	const TRUE: [&str; 4] = ["true", "yes", "on", "1"];
+	const FALSE: [&str; 4] = ["false", "no", "off", "0"];
+	let value = trim(value);
+	if TRUE.iter().any(|s| value.eq_ignore_ascii_case(s)) {
+		return Some(true);
+	}
+	if FALSE.iter().any(|s| value.eq_ignore_ascii_case(s)) {
+		return Some(false);
+	}
+	return None;
+}
+
+/// Compares section names or keys ignoring ascii case.
+///
+/// Non-ascii characters are compared exactly, matching the case folding of [`Parser::case_insensitive`].
+///
+/// ```
+/// assert!(ini_core::key_eq("Key", "KEY"));
+/// assert!(!ini_core::key_eq("Ä", "ä"));
+/// ```
+#[inline]
+pub fn key_eq(a: &str, b: &str) -> bool {
+	a.eq_ignore_ascii_case(b)
+}
+
+/// Strips the `[]` suffix marking a key as an array entry.
+///
+/// Some dialects write lists as `key[]=a` lines, see `Parser::multi_map`.
+/// Nothing is trimmed, enable [`auto_trim`](Parser::auto_trim) to strip the whitespace before the separator.
+///
+/// ```
+/// assert_eq!(ini_core::strip_array_suffix("key[]"), "key");
+/// assert_eq!(ini_core::strip_array_suffix("key"), "key");
+/// assert_eq!(ini_core::strip_array_suffix("key[0]"), "key[0]");
+/// ```
+#[inline]
+pub fn strip_array_suffix(key: &str) -> &str {
+	key.strip_suffix("[]").unwrap_or(key)
+}
+
+/// Parses an integer value.
+///
+/// The value is [`trim`]med and parsed with [`str::parse`].
+///
+/// ```
+/// assert_eq!(ini_core::parse_int(" -42 "), Some(-42));
+/// assert_eq!(ini_core::parse_int("0x10"), None);
+/// ```
+#[inline]
+pub fn parse_int(value: &str) -> Option<i64> {
+	trim(value).parse().ok()
+}
+
+/// Parses a floating point value.
+///
+/// The value is [`trim`]med and parsed with [`str::parse`].
+///
+/// ```
+/// assert_eq!(ini_core::parse_float(" 1.5 "), Some(1.5));
+/// assert_eq!(ini_core::parse_float("1,5"), None);
+/// ```
+#[inline]
+pub fn parse_float(value: &str) -> Option<f64> {
+	trim(value).parse().ok()
+}
+
+/// Selects which strings are trimmed by [`Parser::trim`].
+///
+/// Combine the flags with `|` and restrict them to one side with `&`:
+///
+/// ```
+/// use ini_core::Trim;
+///
+/// let trim = Trim::KEY | Trim::SECTION | (Trim::VALUE & Trim::START);
+/// assert!(trim.contains(Trim::KEY));
+/// assert!(trim.contains(Trim::VALUE & Trim::START));
+/// assert!(!trim.contains(Trim::VALUE));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Trim(pub(crate) u8);
+
+impl Trim {
+	/// Trims nothing.
+	pub const NONE: Trim = Trim(0);
+	/// Trims property keys.
+	pub const KEY: Trim = Trim(0b0000_0011);
+	/// Trims property values.
+	pub const VALUE: Trim = Trim(0b0000_1100);
+	/// Trims section names.
+	pub const SECTION: Trim = Trim(0b0011_0000);
+	/// Trims comments, including inline comments.
+	pub const COMMENT: Trim = Trim(0b1100_0000);
+	/// Trims everything.
+	pub const ALL: Trim = Trim(0b1111_1111);
+	/// Trims the start of everything.
+	pub const START: Trim = Trim(0b0101_0101);
+	/// Trims the end of everything.
+	pub const END: Trim = Trim(0b1010_1010);
+
+	/// Returns if all the flags in `other` are set.
 	#[inline]
-	fn skip_ln(&mut self, mut s: &'a [u8]) {
-		if s.len() > 0 {
-			if s[0] == b'\r' {
-				s = &s[1..];
-			}
-			if s.len() > 0 {
-				if s[0] == b'\n' {
-					s = &s[1..];
-				}
-			}
-			self.line += 1;
-		}
-		self.state = s;
+	pub const fn contains(self, other: Trim) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+impl ops::BitOr for Trim {
+	type Output = Trim;
+	#[inline]
+	fn bitor(self, rhs: Trim) -> Trim {
+		Trim(self.0 | rhs.0)
 	}
 }
 
+impl ops::BitAnd for Trim {
+	type Output = Trim;
+	#[inline]
+	fn bitand(self, rhs: Trim) -> Trim {
+		Trim(self.0 & rhs.0)
+	}
+}
+
+/// Newline style.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Newline {
+	/// Line feed `"\n"`.
+	#[default]
+	Lf,
+	/// Carriage return and line feed `"\r\n"`.
+	CrLf,
+	/// Carriage return `"\r"`.
+	Cr,
+}
+
+impl Newline {
+	/// Detects the newline style from the first line terminator in the string.
+	///
+	/// Returns `None` if the string has no newlines.
+	///
+	/// ```
+	/// use ini_core::Newline;
+	///
+	/// assert_eq!(Newline::detect("a=1\r\nb=2\n"), Some(Newline::CrLf));
+	/// assert_eq!(Newline::detect("a=1\rb=2"), Some(Newline::Cr));
+	/// assert_eq!(Newline::detect("a=1"), None);
+	/// ```
+	#[inline]
+	pub fn detect(s: &str) -> Option<Newline> {
+		Newline::detect_bytes(s.as_bytes())
+	}
+
+	pub(crate) fn detect_bytes(s: &[u8]) -> Option<Newline> {
+		let i = parse::find_nl(s);
+		match s.get(i..)? {
+			[b'\r', b'\n', ..] => Some(Newline::CrLf),
+			[b'\r', ..] => Some(Newline::Cr),
+			[b'\n', ..] => Some(Newline::Lf),
+			_ => None,
+		}
+	}
+
+	/// Returns the newline as a string.
+	#[inline]
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Newline::Lf => "\n",
+			Newline::CrLf => "\r\n",
+			Newline::Cr => "\r",
+		}
+	}
+}
+
+impl fmt::Display for Newline {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Error returned by [`Item::write_checked`] and [`Writer`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum WriteError {
+	/// A string contains a newline.
+	Newline,
+	/// A section name contains `]`.
+	SectionBracket,
+	/// A key contains `=`.
+	KeySeparator,
+	/// A key is empty without value or starts with `[` or the comment character.
+	InvalidKey,
+	/// A property was written before any section in strict mode.
+	NoSection,
+	/// The writer returned an error.
+	Fmt,
+}
+
+impl From<fmt::Error> for WriteError {
+	#[inline]
+	fn from(_: fmt::Error) -> WriteError {
+		WriteError::Fmt
+	}
+}
+
+impl fmt::Display for WriteError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			WriteError::Newline => "string contains a newline",
+			WriteError::SectionBracket => "section name contains `]`",
+			WriteError::KeySeparator => "key contains `=`",
+			WriteError::InvalidKey => "key is invalid",
+			WriteError::NoSection => "property outside of a section",
+			WriteError::Fmt => "formatter error",
+		})
+	}
+}
+
+impl core::error::Error for WriteError {}
+
+/// Kind of malformed line.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ErrorKind {
+	/// A property line has no separator, only an error in strict mode.
+	MissingSeparator,
+	/// A section header has no closing `]`.
+	UnterminatedSection,
+	/// A section header has content after the closing `]`.
+	TrailingAfterSection,
+	/// A section header has an empty name, only an error with [`EmptySectionPolicy::Error`].
+	EmptySection,
+	/// A key repeats in its section, only reported by `Validator::duplicate_keys`.
+	DuplicateKey,
+	/// A line is longer than [`Parser::max_line_len`], parsing stops.
+	LineTooLong,
+	/// A line contains a control character, only an error with [`Parser::reject_control_chars`].
+	ControlChar,
+	/// A quoted value has no closing quote, only an error with [`Parser::quoted_values`].
+	UnterminatedQuote,
+}
+
+impl ErrorKind {
+	// Errors are returned with the whole line, the kind can be recovered from it
+	fn of(line: &[u8], open: u8, close: u8) -> ErrorKind {
+		if line.first() != Some(&open) {
+			ErrorKind::MissingSeparator
+		}
+		// Well-formed section headers are only errors if empty
+		else if line.len() >= 2 && line[line.len() - 1] == close {
+			ErrorKind::EmptySection
+		}
+		else if line[1..].contains(&close) {
+			ErrorKind::TrailingAfterSection
+		}
+		else {
+			ErrorKind::UnterminatedSection
+		}
+	}
+}
+
+impl fmt::Display for ErrorKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			ErrorKind::MissingSeparator => "missing separator",
+			ErrorKind::UnterminatedSection => "unterminated section header",
+			ErrorKind::TrailingAfterSection => "trailing characters after section header",
+			ErrorKind::EmptySection => "empty section name",
+			ErrorKind::DuplicateKey => "duplicate key",
+			ErrorKind::LineTooLong => "line too long",
+			ErrorKind::ControlChar => "control character",
+			ErrorKind::UnterminatedQuote => "unterminated quoted value",
+		})
+	}
+}
+
+/// How section headers with an empty name like `[]` are parsed.
+///
+/// See [`Parser::empty_section`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum EmptySectionPolicy {
+	/// Returned as a section with an empty name.
+	#[default]
+	AllowEmpty,
+	/// Returned as [`Item::Error`].
+	Error,
+	/// Ends the current section and returns to the global section.
+	///
+	/// Only the [`Item::SectionEnd`] is returned, the following properties are in the global section.
+	Global,
+}
+
+/// How keys and values are separated.
+///
+/// See [`Parser::separator`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Separator {
+	/// Split on the first occurrence of the character, see [`Parser::separator_char`].
+	Char(u8),
+	/// Split on the first run of spaces and tabs after the key, eg. `key    value`.
+	Whitespace,
+}
+
+impl Default for Separator {
+	#[inline]
+	fn default() -> Separator {
+		Separator::Char(b'=')
+	}
+}
+
+/// Malformed line error.
+///
+/// See [`Parser::strict`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ParseError {
+	line: u32,
+	kind: ErrorKind,
+}
+
+impl ParseError {
+	/// Returns the line number (starting at 1) of the malformed line.
+	#[inline]
+	pub const fn line(&self) -> u32 {
+		self.line
+	}
+
+	/// Returns the kind of error.
+	#[inline]
+	pub const fn kind(&self) -> ErrorKind {
+		self.kind
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "line {}: {}", self.line, self.kind)
+	}
+}
+
+impl core::error::Error for ParseError {}
+
+/// Joins the lines of a value continued with a trailing backslash.
+///
+/// Removes every backslash and newline pair, the leading whitespace of the continued lines is kept.
+/// Returns the string unchanged if it contains no newlines.
+///
+/// See [`Parser::line_continuation`] to parse such values.
+///
+/// ```
+/// assert_eq!(ini_core::join_continuations("foo\\\nbar"), "foobar");
+/// assert_eq!(ini_core::join_continuations("a \\\r\n b"), "a  b");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn join_continuations(s: &str) -> Cow<'_, str> {
+	if !s.contains(['\r', '\n']) {
+		return Cow::Borrowed(s);
+	}
+	let mut result = String::with_capacity(s.len());
+	let mut s = s.as_bytes();
+	loop {
+		let i = parse::find_nl(s);
+		let line = &s[..i];
+		if i == s.len() {
+			result.push_str(from_utf8(line));
+			break;
+		}
+		result.push_str(from_utf8(line.strip_suffix(b"\\").unwrap_or(line)));
+		s = &s[i + nl_len(&s[i..])..];
+	}
+	Cow::Owned(result)
+}
+
+/// Joins the lines of a folded value with the separator.
+///
+/// Removes every newline and the leading whitespace of the line after it, inserting the separator instead.
+/// Returns the string unchanged if it contains no newlines.
+///
+/// See [`Parser::folding`] to parse such values.
+///
+/// ```
+/// assert_eq!(ini_core::join_folded("foo\n  bar", " "), "foo bar");
+/// assert_eq!(ini_core::join_folded("a\r\n\tb\n c", "\n"), "a\nb\nc");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn join_folded<'a>(s: &'a str, separator: &str) -> Cow<'a, str> {
+	if !s.contains(['\r', '\n']) {
+		return Cow::Borrowed(s);
+	}
+	let mut result = String::with_capacity(s.len());
+	let mut s = s.as_bytes();
+	loop {
+		let i = parse::find_nl(s);
+		result.push_str(from_utf8(&s[..i]));
+		if i == s.len() {
+			break;
+		}
+		result.push_str(separator);
+		s = &s[i + nl_len(&s[i..])..];
+		let indent = s.iter().take_while(|&&chr| matches!(chr, b' ' | b'\t')).count();
+		s = &s[indent..];
+	}
+	Cow::Owned(result)
+}
+
+/// Ini streaming parser.
+///
+/// The whole document must be available before parsing starts.
+/// The parser then returns each element as it is being parsed.
+/// Use `StreamParser` to parse a document as it arrives in chunks (requires the `alloc` feature).
+///
+/// See [`crate`] documentation for more information.
+#[derive(Clone, Debug)]
+pub struct Parser<'a> {
+	bytes: BytesParser<'a>,
+}
+
+impl<'a> Parser<'a> {
+	/// Constructs a new `Parser` instance.
+	#[inline]
+	pub const fn new(s: &'a str) -> Parser<'a> {
+		Parser { bytes: BytesParser::new(s.as_bytes()) }
+	}
+
+	/// Sets the comment character, eg. `b'#'`.
+	///
+	/// The default is `b';'`.
+	#[must_use]
+	#[inline]
+	pub const fn comment_char(self, chr: u8) -> Parser<'a> {
+		Parser { bytes: self.bytes.comment_char(chr) }
+	}
+
+	/// Sets the comment characters, eg. `b";#"`.
+	///
+	/// A line starting with any of these characters is a comment.
+	/// An empty set disables comments entirely.
+	///
+	/// The default is `b";"`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new(";a\n#b\n!c").comment_chars(b";#");
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Comment("a")));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Comment("b")));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("!c", None)));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn comment_chars(self, chrs: &[u8]) -> Parser<'a> {
+		Parser { bytes: self.bytes.comment_chars(chrs) }
+	}
+
+	/// Sets both `;` and `#` as comment characters.
+	///
+	/// Shorthand for `comment_chars(b";#")`, common in files such as Git config, systemd units and desktop entries.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new(";a\n#b").default_comments();
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Comment("a")));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Comment("b")));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn default_comments(self) -> Parser<'a> {
+		Parser { bytes: self.bytes.default_comments() }
+	}
+
+	/// Disables comments.
+	///
+	/// Shorthand for `comment_chars(b"")`, lines starting with `;` or `#` are parsed as properties.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new(";a=1").no_comments();
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property(";a", Some("1"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn no_comments(self) -> Parser<'a> {
+		Parser { bytes: self.bytes.no_comments() }
+	}
+
+	/// Sets the key value separator character, eg. `b':'`.
+	///
+	/// The default is `b'='`.
+	///
+	/// ```
+	/// assert_eq!(
+	/// 	ini_core::Parser::new("Key:Value").separator_char(b':').next(),
+	/// 	Some(ini_core::Item::Property("Key", Some("Value"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn separator_char(self, chr: u8) -> Parser<'a> {
+		Parser { bytes: self.bytes.separator_char(chr) }
+	}
+
+	/// Sets how keys and values are separated.
+	///
+	/// With [`Separator::Whitespace`] the key ends at the first space or tab following it and the value starts after the run of spaces and tabs.
+	/// The separating whitespace is part of neither the key nor the value regardless of [`auto_trim`](Parser::auto_trim),
+	/// leading whitespace is part of the key and trailing whitespace part of the value unless trimmed.
+	/// A line without whitespace after the key is a property without a value.
+	/// [`split_last`](Parser::split_last) has no effect.
+	///
+	/// The default is `Separator::Char(b'=')`.
+	///
+	/// ```
+	/// use ini_core::{Item, Parser, Separator};
+	///
+	/// let mut parser = Parser::new("127.0.0.1 \t localhost\nflag").separator(Separator::Whitespace);
+	/// assert_eq!(parser.next(), Some(Item::Property("127.0.0.1", Some("localhost"))));
+	/// assert_eq!(parser.next(), Some(Item::Property("flag", None)));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn separator(self, separator: Separator) -> Parser<'a> {
+		Parser { bytes: self.bytes.separator(separator) }
+	}
+
+	/// Sets the characters opening and closing section headers, eg. `b'<'` and `b'>'`.
+	///
+	/// A line starting with the opening character is a section header, comment characters take precedence.
+	///
+	/// The default is `b'['` and `b']'`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("<S>\n[k]=v").section_delimiters(b'<', b'>');
+	/// assert_eq!(parser.nth(1), Some(ini_core::Item::Section("S")));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("[k]", Some("v"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn section_delimiters(self, open: u8, close: u8) -> Parser<'a> {
+		Parser { bytes: self.bytes.section_delimiters(open, close) }
+	}
+
+	/// Sets auto trimming of all returned strings.
+	///
+	/// The default is `false`.
+	#[must_use]
+	#[inline]
+	pub const fn auto_trim(self, auto_trim: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.auto_trim(auto_trim) }
+	}
+
+	/// Sets which returned strings are trimmed of ascii whitespace.
+	///
+	/// Allows trimming keys and section names while keeping values verbatim.
+	/// Supersedes earlier calls to [`auto_trim`](Parser::auto_trim), [`auto_trim_start`](Parser::auto_trim_start) and [`auto_trim_end`](Parser::auto_trim_end).
+	///
+	/// The default is [`Trim::NONE`].
+	///
+	/// ```
+	/// use ini_core::{Item, Parser, Trim};
+	///
+	/// let mut parser = Parser::new("[ Section ]\n Key =  Value ").trim(Trim::KEY | Trim::SECTION);
+	/// assert_eq!(parser.nth(1), Some(Item::Section("Section")));
+	/// assert_eq!(parser.next(), Some(Item::Property("Key", Some("  Value "))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn trim(self, trim: Trim) -> Parser<'a> {
+		Parser { bytes: self.bytes.trim(trim) }
+	}
+
+	/// Sets the predicate matching the characters to trim.
+	///
+	/// Applies to the strings selected by [`auto_trim`](Parser::auto_trim) or [`trim`](Parser::trim).
+	///
+	/// The default trims [ascii whitespace](u8::is_ascii_whitespace).
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("Key = \tValue ").auto_trim(true).trim_with(|chr| chr == ' ');
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Key", Some("\tValue"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn trim_with(self, f: fn(char) -> bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.trim_with(f) }
+	}
+
+	/// Sets auto trimming of all returned strings with unicode whitespace.
+	///
+	/// Like [`auto_trim`](Parser::auto_trim) but also trims eg. `U+00A0` and `U+3000`, see [`char::is_whitespace`].
+	/// Decoding utf8 is much slower than the ascii fast path, only enable this if the document needs it.
+	///
+	/// Enabling replaces the predicate set with [`trim_with`](Parser::trim_with).
+	/// Disabling turns off trimming and clears the predicate, a later [`auto_trim`](Parser::auto_trim) trims ascii whitespace.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("Key =\u{3000}Value\u{a0}").auto_trim_unicode(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Key", Some("Value"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn auto_trim_unicode(self, auto_trim_unicode: bool) -> Parser<'a> {
+		let trim_with = if auto_trim_unicode { Some(char::is_whitespace as fn(char) -> bool) } else { None };
+		Parser { bytes: self.bytes.auto_trim(auto_trim_unicode).set_trim_with(trim_with) }
+	}
+
+	/// Sets auto trimming the start of all returned strings.
+	///
+	/// Preserves trailing whitespace, eg. `KEY = VALUE ` has key `KEY<space>` and value `VALUE<space>`.
+	/// Use together with [`auto_trim_end`](Parser::auto_trim_end) to trim both ends like [`auto_trim`](Parser::auto_trim).
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new(" KEY = VALUE ").auto_trim_start(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("KEY ", Some("VALUE "))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn auto_trim_start(self, auto_trim_start: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.auto_trim_start(auto_trim_start) }
+	}
+
+	/// Sets auto trimming the end of all returned strings.
+	///
+	/// Preserves leading whitespace, eg. `KEY = VALUE ` has key `KEY` and value `<space>VALUE`.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("KEY = VALUE ").auto_trim_end(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("KEY", Some(" VALUE"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn auto_trim_end(self, auto_trim_end: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.auto_trim_end(auto_trim_end) }
+	}
+
+	/// Sets skipping a leading UTF-8 byte order mark.
+	///
+	/// The byte order mark `"\u{FEFF}"` is skipped if present at the start of the input, otherwise it ends up in the first item.
+	/// This has no effect after parsing has started.
+	/// The option isn't passed on to `StreamParser` and `IoParser`, they have their own `skip_bom`.
+	///
+	/// UTF-16 byte order marks are out of scope, such documents must be converted to UTF-8 first.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("\u{FEFF}Key=Value").skip_bom(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Key", Some("Value"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn skip_bom(self, skip_bom: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.skip_bom(skip_bom) }
+	}
+
+	/// Sets splitting trailing comments off property values.
+	///
+	/// A comment character preceded by a space or tab in a property value starts a trailing comment.
+	/// The value ends right before the comment character and the comment is available through [`inline_comment`](Parser::inline_comment).
+	/// This allows values such as `a;b` to contain the comment character as long as it does not follow whitespace.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("Key=Value ; Comment").inline_comments(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Key", Some("Value "))));
+	/// assert_eq!(parser.inline_comment(), Some(" Comment"));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn inline_comments(self, inline_comments: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.inline_comments(inline_comments) }
+	}
+
+	/// Returns the trailing comment split off the most recently returned property.
+	///
+	/// See [`inline_comments`](Parser::inline_comments).
+	#[inline]
+	pub fn inline_comment(&self) -> Option<&'a str> {
+		self.bytes.inline_comment().map(from_utf8)
+	}
+
+	/// Returns the comment character of the most recently returned comment.
+	///
+	/// [`Item::Comment`] only holds the text after the comment character, use this to tell apart the [`comment_chars`](Parser::comment_chars).
+	/// Returns `None` until the first comment.
+	///
+	/// Displaying the comment with [`DisplayLine::comment_char`] reproduces the line:
+	///
+	/// ```
+	/// use ini_core::Parser;
+	///
+	/// let document = "#note\nk=v\n;other\n";
+	/// let mut parser = Parser::new(document).comment_chars(b";#");
+	/// let mut output = String::new();
+	/// while let Some((item, newline)) = parser.next_with_newline() {
+	/// 	let chr = parser.comment_prefix().unwrap_or(b';');
+	/// 	output += &item.display_line(newline).comment_char(chr).to_string();
+	/// }
+	/// assert_eq!(output, document);
+	/// ```
+	#[inline]
+	pub const fn comment_prefix(&self) -> Option<u8> {
+		self.bytes.comment_prefix()
+	}
+
+	/// Sets accepting section headers followed by trailing text.
+	///
+	/// A line starting with `[` and containing a `]` is a section header, the name ends at the last `]`.
+	/// The text after it is available through [`trailing`](Parser::trailing) to round-trip the line, eg. `[Section] ; comment`.
+	///
+	/// The default is `false`, these lines are returned as [`Item::Error`] with [`ErrorKind::TrailingAfterSection`].
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("[Section] ; comment").section_trailing(true);
+	/// assert_eq!(parser.nth(1), Some(ini_core::Item::Section("Section")));
+	/// assert_eq!(parser.trailing(), Some(" ; comment"));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn section_trailing(self, section_trailing: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.section_trailing(section_trailing) }
+	}
+
+	/// Returns the text after the closing `]` of the most recently returned section header.
+	///
+	/// See [`section_trailing`](Parser::section_trailing).
+	#[inline]
+	pub fn trailing(&self) -> Option<&'a str> {
+		self.bytes.trailing().map(from_utf8)
+	}
+
+	/// Sets joining property values ending in a backslash with the next line.
+	///
+	/// A value line ending in an odd number of backslashes continues on the next line.
+	/// The value is returned as is, spanning all of its lines including the backslashes and newlines.
+	/// Use [`join_continuations`] to join them.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("Key=foo\\\nbar\nNext").line_continuation(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Key", Some("foo\\\nbar"))));
+	/// assert_eq!(parser.line(), 2);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Next", None)));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn line_continuation(self, line_continuation: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.line_continuation(line_continuation) }
+	}
+
+	/// Sets joining property values with the following lines starting with whitespace.
+	///
+	/// Long values are folded by indenting the lines after the first, eg. in mail headers.
+	/// A line starting with a space or tab belongs to the value of the property before it.
+	/// Lines after a section header, a comment or a property without a value are not folded, neither are lines with only whitespace.
+	/// The value is returned as is, spanning all of its lines including the newlines and indentation.
+	/// Use [`join_folded`] to join them.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("Key=foo\n  bar\nNext").folding(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Key", Some("foo\n  bar"))));
+	/// assert_eq!(parser.line(), 2);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Next", None)));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn folding(self, folding: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.folding(folding) }
+	}
+
+	/// Sets how section headers with an empty name are parsed.
+	///
+	/// The name is checked after trimming, eg. `[ ]` is empty with [`auto_trim`](Parser::auto_trim).
+	/// With [`EmptySectionPolicy::Global`] the following properties are in the global section as reported by [`in_global_section`](Parser::in_global_section).
+	/// There is no [`Item::Section`] to mark this, only the [`Item::SectionEnd`] of the previous section.
+	///
+	/// The default is [`EmptySectionPolicy::AllowEmpty`].
+	///
+	/// ```
+	/// use ini_core::{EmptySectionPolicy, Item, Parser};
+	///
+	/// let document = "[S]\na=1\n[]\nb=2";
+	///
+	/// let mut parser = Parser::new(document).empty_section(EmptySectionPolicy::Error);
+	/// assert_eq!(parser.nth(3), Some(Item::SectionEnd));
+	/// assert_eq!(parser.next(), Some(Item::Error("[]")));
+	///
+	/// let mut parser = Parser::new(document).empty_section(EmptySectionPolicy::Global);
+	/// assert_eq!(parser.nth(3), Some(Item::SectionEnd));
+	/// assert_eq!(parser.next(), Some(Item::Property("b", Some("2"))));
+	/// assert!(parser.in_global_section());
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn empty_section(self, empty_section: EmptySectionPolicy) -> Parser<'a> {
+		Parser { bytes: self.bytes.empty_section(empty_section) }
+	}
+
+	/// Sets splitting properties on the last separator character of the line.
+	///
+	/// Allows keys containing the separator character, eg. `a=b=c` has key `a=b` and value `c`.
+	/// The key is split off before [`inline_comments`](Parser::inline_comments), a separator in the trailing comment is taken as the last one.
+	/// Trimming applies to the key and value after splitting.
+	///
+	/// The default is `false`, the key ends at the first separator character.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("a = b = c").split_last(true).auto_trim(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("a = b", Some("c"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn split_last(self, split_last: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.split_last(split_last) }
+	}
+
+	/// Sets the maximum length of a line in bytes, excluding its newline.
+	///
+	/// Protects against pathological input such as a huge document without newlines, eg. when parsing untrusted configuration.
+	/// The search for the end of a line stops at the limit, a longer line is returned as [`Item::Error`] with its first `max_line_len` bytes and parsing stops.
+	/// The reason is available from [`error`](Parser::error) as [`ErrorKind::LineTooLong`], like in [strict](Parser::strict) mode.
+	/// Each of the continued lines of a [`line_continuation`](Parser::line_continuation) and the folded lines of [`folding`](Parser::folding) is limited separately,
+	/// an overlong continued or folded line is returned as the error instead of the property.
+	///
+	/// The default is unlimited.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("a=1\nb=2345678\nc=3").max_line_len(4);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("a", Some("1"))));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Error("b=23")));
+	/// assert_eq!(parser.next(), None);
+	/// assert_eq!(parser.error().unwrap().kind(), ini_core::ErrorKind::LineTooLong);
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn max_line_len(self, max_line_len: usize) -> Parser<'a> {
+		Parser { bytes: self.bytes.max_line_len(max_line_len) }
+	}
+
+	/// Sets returning lines containing control characters as errors.
+	///
+	/// Catches corrupt or binary input such as `\0` bytes within a line.
+	/// The control characters are the ascii bytes `0x00` to `0x1f` and `0x7f`, except tab and the newlines.
+	/// The whole line is returned as [`Item::Error`] with [`ErrorKind::ControlChar`], in [strict](Parser::strict) mode parsing stops.
+	/// The continued lines of a [`line_continuation`](Parser::line_continuation) are returned as a single error.
+	///
+	/// The default is `false`, control characters are part of the strings.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("a=1\nb=\0\0\nc=\t3").reject_control_chars(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("a", Some("1"))));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Error("b=\0\0")));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("c", Some("\t3"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn reject_control_chars(self, reject_control_chars: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.reject_control_chars(reject_control_chars) }
+	}
+
+	/// Sets reading values enclosed in double quotes.
+	///
+	/// A value starting with `"` after optional whitespace is read up to the matching `"`, a backslash escapes the character following it.
+	/// The value is returned without the quotes and [`quoted`](Parser::quoted) returns `true`, decode the escapes with [`unescape_quotes`].
+	/// Separators and comment characters within the quotes are part of the value and whitespace within the quotes is never trimmed.
+	///
+	/// Only whitespace and an [inline comment](Parser::inline_comments) may follow the closing quote, otherwise the value is returned as is.
+	/// A value without a closing quote is returned as [`Item::Error`] with [`ErrorKind::UnterminatedQuote`].
+	///
+	/// The default is `false`, quotes are part of the value.
+	///
+	/// ```
+	/// use ini_core::{Item, Parser};
+	///
+	/// let mut parser = Parser::new(r#"a = " x = y ; z " ; comment
+	/// b="say \"hi\""
+	/// c="open"#).quoted_values(true).inline_comments(true).auto_trim(true);
+	/// assert_eq!(parser.next(), Some(Item::Property("a", Some(" x = y ; z "))));
+	/// assert_eq!(parser.inline_comment(), Some("comment"));
+	/// assert_eq!(parser.next(), Some(Item::Property("b", Some(r#"say \"hi\""#))));
+	/// assert!(parser.quoted());
+	/// assert_eq!(parser.next(), Some(Item::Error(r#"c="open"#)));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn quoted_values(self, quoted_values: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.quoted_values(quoted_values) }
+	}
+
+	/// Returns if the value of the most recently returned property was enclosed in double quotes.
+	///
+	/// See [`quoted_values`](Parser::quoted_values).
+	#[inline]
+	pub const fn quoted(&self) -> bool {
+		self.bytes.quoted()
+	}
+
+	/// Sets comparing section names and keys ignoring ascii case in lookups.
+	///
+	/// Affects [`get`](Parser::get), [`last_property`](Parser::last_property) and `into_map`, see [`key_eq`].
+	/// The parsed items are not affected, names are returned as written in the document.
+	///
+	/// The default is `false`, names are compared case-sensitively.
+	///
+	/// ```
+	/// let parser = ini_core::Parser::new("[Section]\nKey=Value").case_insensitive(true);
+	/// assert_eq!(parser.clone().get(Some("SECTION"), "key"), Some("Value"));
+	/// assert_eq!(parser.clone().nth(1), Some(ini_core::Item::Section("Section")));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn case_insensitive(self, case_insensitive: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.case_insensitive(case_insensitive) }
+	}
+
+	/// Sets the name of the global section when collecting the document.
+	///
+	/// Affects [`into_map`](Parser::into_map), [`into_map_with`](Parser::into_map_with) and [`multi_map`](Parser::multi_map), the properties before the first section header are collected under this name instead of `None`.
+	/// They are merged with a section of the same name, eg. `DEFAULT` to follow the conventions of Python's configparser.
+	/// The name is lowercased like the section names with [`case_insensitive`](Parser::case_insensitive) enabled.
+	/// The parsed items are not affected.
+	///
+	/// The default is no name.
+	///
+	/// ```
+	/// let map = ini_core::Parser::new("a=1\n[Section]\nb=2").global_section_name("DEFAULT").into_map();
+	/// assert_eq!(map[&Some("DEFAULT".to_string())], [("a".to_string(), Some("1".to_string()))]);
+	/// assert!(!map.contains_key(&None));
+	/// ```
+	#[cfg(feature = "alloc")]
+	#[must_use]
+	#[inline]
+	pub const fn global_section_name(self, name: &'a str) -> Parser<'a> {
+		Parser { bytes: self.bytes.global_section_name(name.as_bytes()) }
+	}
+
+	/// Sets returning lines with only whitespace as [`Item::Blank`].
+	///
+	/// By default a line with only spaces or tabs is returned as a property without a value, preserving the whitespace for round-tripping.
+	/// With [`auto_trim`](Parser::auto_trim) (or trimming keys with [`trim`](Parser::trim)) these lines are already blank, this option makes them blank without trimming anything else.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new(" \t\nKey = Value").blank_on_whitespace(true);
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Blank));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Key ", Some(" Value"))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn blank_on_whitespace(self, blank_on_whitespace: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.blank_on_whitespace(blank_on_whitespace) }
+	}
+
+	/// Sets stopping at the first malformed line.
+	///
+	/// Malformed section headers and, in strict mode only, property lines without a separator are returned as [`Item::Error`].
+	/// The error is the last item returned, there is no final [`Item::SectionEnd`].
+	/// The reason is available from [`error`](Parser::error).
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// use ini_core as ini;
+	///
+	/// let mut parser = ini::Parser::new("[Section]\nKey\nKey=Value").strict(true);
+	/// assert_eq!(parser.nth(1), Some(ini::Item::Section("Section")));
+	/// assert_eq!(parser.next(), Some(ini::Item::Error("Key")));
+	/// assert_eq!(parser.next(), None);
+	///
+	/// let error = parser.error().unwrap();
+	/// assert_eq!(error.line(), 2);
+	/// assert_eq!(error.kind(), ini::ErrorKind::MissingSeparator);
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn strict(self, strict: bool) -> Parser<'a> {
+		Parser { bytes: self.bytes.strict(strict) }
+	}
+
+	/// Returns the error which stopped parsing in strict mode or at a line longer than [`max_line_len`](Parser::max_line_len).
+	#[inline]
+	pub const fn error(&self) -> Option<ParseError> {
+		self.bytes.error()
+	}
+
+	/// Returns the number of lines consumed so far.
+	///
+	/// This starts at 0 and is incremented for every newline consumed, see [`line_number`](Parser::line_number) for the line of the returned item.
+	#[inline]
+	pub const fn line(&self) -> u32 {
+		self.bytes.line()
+	}
+
+	/// Returns the line number (starting at 1) of the most recently returned item.
+	///
+	/// This is the line as shown in text editors, `"\r\n"`, `"\n"` and `"\r"` each end a line.
+	/// Property values continued over multiple lines report the line they start on.
+	/// [`Item::SectionEnd`] reports the line of the section header which follows it, or the line after the last newline at the end of the document.
+	///
+	/// Returns 0 before the first item.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("[Section]\r\n\nKey=Value\r;comment");
+	/// let lines: Vec<_> = core::iter::from_fn(|| parser.next().map(|_| parser.line_number())).collect();
+	/// assert_eq!(lines, [1, 1, 2, 3, 4, 4]);
+	/// ```
+	#[inline]
+	pub const fn line_number(&self) -> u32 {
+		self.bytes.line_number()
+	}
+
+	/// Returns if the parser is in the global section before the first section header.
+	///
+	/// The global section contains the items before the first section header and is ended by the leading [`Item::SectionEnd`].
+	/// That `SectionEnd` is still returned while in the global section, this returns `false` once the following section header has been returned.
+	/// A malformed section header returned as [`Item::Error`] also ends the global section.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("Global=1\n[Section]\nKey=Value");
+	/// assert!(parser.in_global_section());
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Global", Some("1"))));
+	/// assert!(parser.in_global_section());
+	/// assert_eq!(parser.next(), Some(ini_core::Item::SectionEnd));
+	/// assert!(parser.in_global_section());
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Section("Section")));
+	/// assert!(!parser.in_global_section());
+	/// ```
+	#[inline]
+	pub const fn in_global_section(&self) -> bool {
+		self.bytes.in_global_section()
+	}
+
+	// Section the most recently returned property belongs to for lookups, see BytesParser::lookup_section
+	#[inline]
+	pub(crate) fn lookup_section(&self) -> Option<Option<&'a str>> {
+		self.bytes.lookup_section().map(|section| section.map(from_utf8))
+	}
+
+	/// Returns if the parser has returned its last item.
+	///
+	/// This is `true` once the [`Item::SectionEnd`] ending the document has been returned, or parsing was stopped by an [`error`](Parser::error).
+	/// The next call to `next` returns `None`, use this to act on the end of the document while handling its last item.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("[Section]\nKey=Value");
+	/// let mut ends = 0;
+	/// while let Some(item) = parser.next() {
+	/// 	if item == ini_core::Item::SectionEnd && parser.is_exhausted() {
+	/// 		ends += 1;
+	/// 	}
+	/// }
+	/// assert_eq!(ends, 1);
+	/// ```
+	#[inline]
+	pub const fn is_exhausted(&self) -> bool {
+		self.bytes.is_exhausted()
+	}
+
+	/// Returns the byte column (starting at 1) where a string returned by the parser starts.
+	///
+	/// Together with [`line_number`](Parser::line_number) this gives the `line:column` of the keys, values, section names and comments of the most recently returned item.
+	/// The column counts bytes, not characters, multi-byte UTF-8 characters before the string count more than once.
+	/// A byte order mark skipped with [`skip_bom`](Parser::skip_bom) is not counted.
+	///
+	/// Returns `None` if the string is not a slice of the input.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("[Section]\nKey = Value").auto_trim(true);
+	/// let Some(ini_core::Item::Property(key, Some(value))) = parser.nth(2) else { panic!() };
+	/// assert_eq!(parser.line_number(), 2);
+	/// assert_eq!(parser.column(key), Some(1));
+	/// assert_eq!(parser.column(value), Some(7));
+	/// assert_eq!(parser.column("Value"), None);
+	/// ```
+	#[inline]
+	pub fn column(&self, s: &str) -> Option<u32> {
+		self.bytes.column(s.as_bytes())
+	}
+
+	/// Returns the name of the current section.
+	///
+	/// This is the name of the most recently returned [`Item::Section`], or `None` in the global section.
+	/// It stays the same on the [`Item::SectionEnd`] which ends the section.
+	/// A malformed section header returned as [`Item::Error`] starts a section without a name.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("Global=1\n[Section]\nKey=Value");
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Global", Some("1"))));
+	/// assert_eq!(parser.current_section(), None);
+	/// assert_eq!(parser.nth(1), Some(ini_core::Item::Section("Section")));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("Key", Some("Value"))));
+	/// assert_eq!(parser.current_section(), Some("Section"));
+	/// ```
+	#[inline]
+	pub fn current_section(&self) -> Option<&'a str> {
+		self.bytes.current_section().map(from_utf8)
+	}
+
+	/// Returns the number of items remaining without parsing them.
+	///
+	/// Equal to `self.clone().count()`, including the [`Item::SectionEnd`] before every section header and at the end of the document.
+	/// Only counts the lines and section headers, which is faster than parsing every item.
+	/// With [`line_continuation`](Parser::line_continuation), [`folding`](Parser::folding) or [`strict`](Parser::strict) enabled the items are parsed instead.
+	///
+	/// ```
+	/// let parser = ini_core::Parser::new("a=1\n[Section]\n;comment\nb=2\n");
+	/// assert_eq!(parser.count_items(), 6);
+	/// assert_eq!(parser.count_items(), parser.clone().count());
+	/// ```
+	#[inline]
+	pub fn count_items(&self) -> usize {
+		self.bytes.count_items()
+	}
+
+	/// Skips ahead to the next section header or the end of the document.
+	///
+	/// The parser then returns the [`Item::SectionEnd`] and the header next, exactly like after iterating over the skipped items.
+	/// Malformed section headers count as headers, they also end the current section.
+	///
+	/// Only the first character of every line is looked at, which is faster than parsing every item.
+	/// The skipped lines don't update [`inline_comment`](Parser::inline_comment).
+	/// With [`line_continuation`](Parser::line_continuation), [`folding`](Parser::folding), [`strict`](Parser::strict), [`max_line_len`](Parser::max_line_len)
+	/// or the global [`empty_section`](Parser::empty_section) policy enabled the items are parsed instead.
+	///
+	/// ```
+	/// use ini_core::{Item, Parser};
+	///
+	/// let mut parser = Parser::new("[Huge]\na=1\nb=2\n[Small]\nc=3");
+	/// assert_eq!(parser.nth(1), Some(Item::Section("Huge")));
+	/// parser.skip_section();
+	/// assert_eq!(parser.next(), Some(Item::SectionEnd));
+	/// assert_eq!(parser.next(), Some(Item::Section("Small")));
+	/// ```
+	#[inline]
+	pub fn skip_section(&mut self) {
+		self.bytes.skip_section()
+	}
+
+	/// Skips ahead past the header of the next section with the given name.
+	///
+	/// Returns `true` if the section was found, the parser then returns the items of the section next.
+	/// Returns `false` if there is no such section, the parser is then at the end of the document.
+	///
+	/// Names are compared with the section names as returned by the parser, enable [`auto_trim`](Parser::auto_trim) to ignore padding whitespace
+	/// and [`case_insensitive`](Parser::case_insensitive) to ignore ascii case.
+	///
+	/// Only the lines starting with `[` are parsed, which is faster than parsing every item.
+	/// The skipped lines don't update [`inline_comment`](Parser::inline_comment).
+	/// With [`line_continuation`](Parser::line_continuation), [`folding`](Parser::folding), [`strict`](Parser::strict), [`max_line_len`](Parser::max_line_len)
+	/// or the global [`empty_section`](Parser::empty_section) policy enabled the items are parsed instead.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("a=1\n[A]\nb=2\n[ B ]\nc=3\n[C]").auto_trim(true);
+	/// assert!(parser.skip_to_section("B"));
+	/// assert_eq!(parser.next(), Some(ini_core::Item::Property("c", Some("3"))));
+	/// assert!(!parser.skip_to_section("A"));
+	/// assert_eq!(parser.next(), None);
+	/// ```
+	#[inline]
+	pub fn skip_to_section(&mut self, name: &str) -> bool {
+		self.bytes.skip_to_section(name.as_bytes())
+	}
+
+	/// Returns the remainder of the input string.
+	#[inline]
+	pub fn remainder(&self) -> &'a str {
+		from_utf8(self.bytes.remainder())
+	}
+
+	/// Detects the newline style of the input.
+	///
+	/// See [`Newline::detect`], use with [`Writer::newline`] to write documents in the same style.
+	///
+	/// ```
+	/// let parser = ini_core::Parser::new("[Section]\r\nKey=Value\r\n");
+	/// assert_eq!(parser.detect_newline(), Some(ini_core::Newline::CrLf));
+	/// ```
+	#[inline]
+	pub fn detect_newline(&self) -> Option<Newline> {
+		Newline::detect_bytes(self.bytes.input())
+	}
+
+	/// Returns the remainder of the input as bytes.
+	///
+	/// Same as [`remainder`](Parser::remainder) without the conversion to `&str`.
+	#[inline]
+	pub const fn remainder_bytes(&self) -> &'a [u8] {
+		self.bytes.remainder()
+	}
+
+	/// Returns the whole input string.
+	///
+	/// The remainder is always a suffix of the input, including any byte order mark skipped with [`skip_bom`](Parser::skip_bom).
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("a=1\nb=2");
+	/// parser.next();
+	/// assert_eq!(parser.as_str(), "a=1\nb=2");
+	/// assert_eq!(parser.remainder_bytes(), b"b=2");
+	/// assert_eq!(&parser.input_bytes()[parser.offset()..], parser.remainder_bytes());
+	/// ```
+	#[inline]
+	pub fn as_str(&self) -> &'a str {
+		from_utf8(self.bytes.input())
+	}
+
+	/// Returns the whole input as bytes.
+	#[inline]
+	pub const fn input_bytes(&self) -> &'a [u8] {
+		self.bytes.input()
+	}
+
+	/// Returns the number of bytes of the input consumed so far.
+	///
+	/// Call before and after [`next`](Iterator::next) to get the byte span of the returned item, including its newline.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("Key=Value\n;comment");
+	/// assert_eq!(parser.offset(), 0);
+	/// parser.next();
+	/// assert_eq!(parser.offset(), 10);
+	/// ```
+	#[inline]
+	pub const fn offset(&self) -> usize {
+		self.bytes.offset()
+	}
+
+	/// Returns the current position of the parser.
+	///
+	/// The position is a snapshot of the parsing state, such as the remaining input and the current section, which can be restored with [`seek`](Parser::seek) to parse the same items again.
+	/// It does not capture the configuration of the parser.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("a=1\nb=2\nc=3");
+	/// let pos = parser.position();
+	/// let first = parser.next();
+	/// parser.seek(pos);
+	/// assert_eq!(parser.next(), first);
+	/// ```
+	#[inline]
+	pub const fn position(&self) -> Position<'a> {
+		self.bytes.position()
+	}
+
+	/// Restores a position previously returned by [`position`](Parser::position).
+	///
+	/// The position must have been returned by a parser over the same input.
+	#[inline]
+	pub fn seek(&mut self, pos: Position<'a>) {
+		self.bytes.seek(pos)
+	}
+
+	/// Returns the line which produced the most recently returned item.
+	///
+	/// The line excludes its newline, continued lines are included with their inner newlines.
+	/// Returns an empty string after an [`Item::SectionEnd`] and before the first item.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("[Section]\r\n  flag  \r\n").auto_trim(true);
+	/// assert_eq!(parser.nth(2), Some(ini_core::Item::Property("flag", None)));
+	/// assert_eq!(parser.current_line(), "  flag  ");
+	/// assert_eq!(parser.next(), Some(ini_core::Item::SectionEnd));
+	/// assert_eq!(parser.current_line(), "");
+	/// ```
+	#[inline]
+	pub fn current_line(&self) -> &'a str {
+		from_utf8(self.bytes.current_line())
+	}
+
+	/// Parses the line with the given line number (starting at 1) on its own.
+	///
+	/// Skips to the line from the start of the input without parsing the lines before it, the parser is left unchanged.
+	/// Returns `None` if the document has fewer lines.
+	///
+	/// The line is parsed without its context and the raw item is returned:
+	///
+	/// * A section header is returned as [`Item::Section`], never as [`Item::SectionEnd`]. Use [`current_section`](Parser::current_section) after parsing up to the line to find the section of a property.
+	/// * A line continuing the previous line with [`line_continuation`](Parser::line_continuation) or [`folding`](Parser::folding) is parsed as if it started a new item.
+	///
+	/// Line numbers count lines the same way as [`line_number`](Parser::line_number), eg. from [`ParseError::line`].
+	///
+	/// ```
+	/// use ini_core::{Item, Parser};
+	///
+	/// let parser = Parser::new("[Section]\r\nKey=Value\n\n;comment");
+	/// assert_eq!(parser.nth_line(1), Some(Item::Section("Section")));
+	/// assert_eq!(parser.nth_line(2), Some(Item::Property("Key", Some("Value"))));
+	/// assert_eq!(parser.nth_line(3), Some(Item::Blank));
+	/// assert_eq!(parser.nth_line(4), Some(Item::Comment("comment")));
+	/// assert_eq!(parser.nth_line(5), None);
+	/// ```
+	#[inline]
+	pub fn nth_line(&self, n: u32) -> Option<Item<'a>> {
+		self.bytes.nth_line(n).map(Item::from_bytes)
+	}
+
+	/// Returns the next item and the newline which ended its line.
+	///
+	/// The newline is `None` for [`Item::SectionEnd`] and for the last line of a document without a trailing newline.
+	/// Displaying every item with [`Item::display_line`] or writing it with [`Writer::item_with_newline`] reproduces the document exactly,
+	/// provided trimming is disabled and the default options are used.
+	///
+	/// ```
+	/// use ini_core::{Item, Newline};
+	///
+	/// let mut parser = ini_core::Parser::new("a=1\r\nb=2\rc=3");
+	/// assert_eq!(parser.next_with_newline(), Some((Item::Property("a", Some("1")), Some(Newline::CrLf))));
+	/// assert_eq!(parser.next_with_newline(), Some((Item::Property("b", Some("2")), Some(Newline::Cr))));
+	/// assert_eq!(parser.next_with_newline(), Some((Item::Property("c", Some("3")), None)));
+	/// assert_eq!(parser.next_with_newline(), Some((Item::SectionEnd, None)));
+	/// ```
+	#[inline]
+	pub fn next_with_newline(&mut self) -> Option<(Item<'a>, Option<Newline>)> {
+		self.bytes.next_with_newline().map(|(item, newline)| (Item::from_bytes(item), newline))
+	}
+
+	/// Returns the next item, usable in const contexts.
+	///
+	/// Takes the parser by value and returns it along with the item as [`Iterator::next`] can't be called in const contexts.
+	/// Returns the same items as `next` using the generic scalar routines instead of SIMD, prefer `next` at runtime.
+	///
+	/// Supports the comment characters, the separator, the section delimiters, ascii trimming, [`blank_on_whitespace`](Parser::blank_on_whitespace),
+	/// [`split_last`](Parser::split_last) and [`empty_section`](Parser::empty_section).
+	/// Panics if any other parsing option is enabled, in const contexts this fails to compile.
+	///
+	/// ```
+	/// use ini_core::{Item, Parser};
+	///
+	/// const CONFIG: &str = "; Baked in\n[Window]\nwidth = 640\nheight = 480\n";
+	///
+	/// const fn get(document: &'static str, key: &[u8]) -> Option<&'static str> {
+	/// 	let mut parser = Parser::new(document).auto_trim(true);
+	/// 	loop {
+	/// 		let (item, next) = parser.next_const();
+	/// 		parser = next;
+	/// 		match item {
+	/// 			Some(Item::Property(k, value)) if k.as_bytes().eq_ignore_ascii_case(key) => return value,
+	/// 			Some(_) => (),
+	/// 			None => return None,
+	/// 		}
+	/// 	}
+	/// }
+	///
+	/// const WIDTH: Option<&str> = get(CONFIG, b"width");
+	/// assert_eq!(WIDTH, Some("640"));
+	/// ```
+	#[inline]
+	pub const fn next_const(self) -> (Option<Item<'a>>, Parser<'a>) {
+		let (item, bytes) = self.bytes.next_const();
+		let item = match item {
+			Some(item) => Some(Item::from_bytes(item)),
+			None => None,
+		};
+		(item, Parser { bytes })
+	}
+
+	/// Returns the next item and its byte range in the input.
+	///
+	/// The range covers the whole line which produced the item, excluding its newline.
+	/// The range of [`Item::SectionEnd`] is empty and positioned where the section ended.
+	///
+	/// ```
+	/// let s = "[Section]\r\nKey=Value";
+	/// let mut parser = ini_core::Parser::new(s);
+	/// assert_eq!(parser.next_spanned(), Some((ini_core::Item::SectionEnd, 0..0)));
+	/// assert_eq!(parser.next_spanned(), Some((ini_core::Item::Section("Section"), 0..9)));
+	/// assert_eq!(parser.next_spanned(), Some((ini_core::Item::Property("Key", Some("Value")), 11..20)));
+	/// assert_eq!(parser.next_spanned(), Some((ini_core::Item::SectionEnd, 20..20)));
+	/// assert_eq!(parser.next_spanned(), None);
+	/// ```
+	#[inline]
+	pub fn next_spanned(&mut self) -> Option<(Item<'a>, Range<usize>)> {
+		self.bytes.next_spanned().map(|(item, span)| (Item::from_bytes(item), span))
+	}
+}
+
+impl<'a> Parser<'a> {
+	/// Groups the items by section.
+	///
+	/// Returns the section name and an iterator over its properties, comments and blank lines.
+	/// The first group is always the global section with name `None`, it contains the items before the first section header and may be empty.
+	/// A malformed section header starts a group with name `None` whose first item is the [`Item::Error`].
+	///
+	/// The [`Item::SectionEnd`] pseudo elements are not returned.
+	///
+	/// ```
+	/// let document = "Global=1\n[A]\nKey=Value\n;comment\n[B]";
+	/// let mut sections = ini_core::Parser::new(document).sections();
+	///
+	/// let (name, mut items) = sections.next().unwrap();
+	/// assert_eq!(name, None);
+	/// assert_eq!(items.next(), Some(ini_core::Item::Property("Global", Some("1"))));
+	/// assert_eq!(items.next(), None);
+	///
+	/// let (name, items) = sections.next().unwrap();
+	/// assert_eq!(name, Some("A"));
+	/// assert_eq!(items.count(), 2);
+	///
+	/// let (name, items) = sections.next().unwrap();
+	/// assert_eq!(name, Some("B"));
+	/// assert_eq!(items.count(), 0);
+	///
+	/// assert!(sections.next().is_none());
+	/// ```
+	#[inline]
+	pub const fn sections(self) -> Sections<'a> {
+		Sections::new(self)
+	}
+
+	/// Calls the visitor for every item until it breaks.
+	///
+	/// The item the visitor breaks on is consumed, calling `visit` again resumes after it.
+	/// Returns [`ControlFlow::Break`](core::ops::ControlFlow::Break) if the visitor stopped early.
+	///
+	/// ```
+	/// use core::ops::ControlFlow;
+	///
+	/// // Stops at every section header
+	/// struct Sections<'a>(Option<&'a str>);
+	/// impl<'a> ini_core::Visitor<'a> for Sections<'a> {
+	/// 	fn section(&mut self, name: &'a str) -> ControlFlow<()> {
+	/// 		self.0 = Some(name);
+	/// 		ControlFlow::Break(())
+	/// 	}
+	/// }
+	///
+	/// let mut parser = ini_core::Parser::new("[A]\nk=v\n[B]");
+	/// let mut visitor = Sections(None);
+	/// assert!(parser.visit(&mut visitor).is_break());
+	/// assert_eq!(visitor.0, Some("A"));
+	/// assert!(parser.visit(&mut visitor).is_break());
+	/// assert_eq!(visitor.0, Some("B"));
+	/// assert!(parser.visit(&mut visitor).is_continue());
+	/// ```
+	pub fn visit<V: Visitor<'a>>(&mut self, visitor: &mut V) -> core::ops::ControlFlow<()> {
+		for item in self {
+			match item {
+				Item::Error(error) => visitor.error(error)?,
+				Item::Section(name) => visitor.section(name)?,
+				Item::SectionEnd => visitor.section_end()?,
+				Item::Property(key, value) => visitor.property(key, value)?,
+				Item::Comment(comment) => visitor.comment(comment)?,
+				Item::Blank => visitor.blank()?,
+			}
+		}
+		return core::ops::ControlFlow::Continue(());
+	}
+
+	/// Returns an iterator over the items without blank lines and comments.
+	///
+	/// Disable [`section_ends`](Significant::section_ends) to also skip [`Item::SectionEnd`].
+	///
+	/// ```
+	/// use ini_core as ini;
+	///
+	/// let items: Vec<_> = ini::Parser::new(";comment\n\n[Section]\nKey=Value").significant().section_ends(false).collect();
+	/// assert_eq!(items, [ini::Item::Section("Section"), ini::Item::Property("Key", Some("Value"))]);
+	/// ```
+	#[inline]
+	pub const fn significant(self) -> Significant<'a> {
+		Significant::new(self)
+	}
+
+	/// Returns an iterator over the keys and values of all properties.
+	///
+	/// Ignores the section the properties are in, see [`sections`](Parser::sections) to group them.
+	///
+	/// ```
+	/// let properties: Vec<_> = ini_core::Parser::new("a=1\n[Section]\n;comment\nb").properties().collect();
+	/// assert_eq!(properties, [("a", Some("1")), ("b", None)]);
+	/// ```
+	#[inline]
+	pub const fn properties(self) -> Properties<'a> {
+		Properties::new(self)
+	}
+
+	/// Returns an iterator over the keys and values of the properties in the section with the given name.
+	///
+	/// Skips ahead to the section with [`skip_to_section`](Parser::skip_to_section), the iterator is empty if there is no such section.
+	/// Section names and keys are returned as parsed, enable [`auto_trim`](Parser::auto_trim) to ignore padding whitespace.
+	///
+	/// The parser is left after the end of the section, calling this again with the same name finds the next section with that name.
+	///
+	/// ```
+	/// let mut parser = ini_core::Parser::new("a=1\n[server]\nhost = localhost\n;comment\nport = 80\n[client]\nx=2").auto_trim(true);
+	/// let properties: Vec<_> = parser.properties_of("server").collect();
+	/// assert_eq!(properties, [("host", Some("localhost")), ("port", Some("80"))]);
+	/// assert_eq!(parser.properties_of("server").next(), None);
+	/// ```
+	#[inline]
+	pub fn properties_of<'p>(&'p mut self, name: &str) -> PropertiesOf<'p, 'a> {
+		PropertiesOf::new(self, name)
+	}
+
+	/// Returns an iterator over the items with malformed lines as errors.
+	///
+	/// Every [`Item::Error`] is returned as an `Err` with the line number, the kind and the text of the line, all other items as `Ok`.
+	/// Which lines are malformed depends on the parser options, eg. a key without a value is only an error in [`strict`](Parser::strict) mode.
+	///
+	/// ```
+	/// use ini_core::{ErrorKind, Item, Parser};
+	///
+	/// let items: Result<Vec<_>, _> = Parser::new("[S]\nk=v").results().collect();
+	/// assert_eq!(items.unwrap(), [Item::SectionEnd, Item::Section("S"), Item::Property("k", Some("v")), Item::SectionEnd]);
+	///
+	/// let error = Parser::new("k=v\n[S\nk=v").results().collect::<Result<Vec<_>, _>>().unwrap_err();
+	/// assert_eq!((error.line(), error.kind(), error.text()), (2, ErrorKind::UnterminatedSection, "[S"));
+	/// ```
+	#[inline]
+	pub const fn results(self) -> Results<'a> {
+		Results::new(self)
+	}
+
+	/// Finds the value of the first matching property.
+	///
+	/// Looks up the key in the given section, or in the global section before the first section header if `None`.
+	/// Duplicate sections are all searched in document order.
+	/// Names are compared exactly, enable [`auto_trim`](Parser::auto_trim) to ignore padding whitespace and [`case_insensitive`](Parser::case_insensitive) to ignore ascii case.
+	///
+	/// Properties without a value are skipped, as are the properties after a malformed section header.
+	///
+	/// This scans the document without allocating and is O(n) per call, collect the document with `into_map` for repeated lookups.
+	///
+	/// ```
+	/// let document = "a=1\n[S]\nb = 2\n[T]\nb=3";
+	/// let parser = ini_core::Parser::new(document).auto_trim(true);
+	/// assert_eq!(parser.clone().get(None, "a"), Some("1"));
+	/// assert_eq!(parser.clone().get(Some("S"), "b"), Some("2"));
+	/// assert_eq!(parser.clone().get(Some("S"), "a"), None);
+	/// ```
+	pub fn get(self, section: Option<&str>, key: &str) -> Option<&'a str> {
+		let mut parser = self;
+		while let Some(item) = parser.next() {
+			match item {
+				// The global section ends at the first section header
+				Item::Section(_) if section.is_none() => return None,
+				Item::Property(k, Some(value)) => {
+					let Some(current) = parser.lookup_section() else { continue };
+					if parser.bytes.section_eq(current.map(str::as_bytes), section.map(str::as_bytes)) && parser.bytes.name_eq(k.as_bytes(), key.as_bytes()) {
+						return Some(value);
+					}
+				},
+				_ => (),
+			}
+		}
+		return None;
+	}
+
+	/// Finds the value of the last matching property.
+	///
+	/// Same as [`get`](Parser::get) except the last of any duplicate properties wins.
+	/// The document is scanned backward line by line so a match near the end is found quickly.
+	/// With [`line_continuation`](Parser::line_continuation), [`folding`](Parser::folding) or [`strict`](Parser::strict) enabled the document is scanned forward instead.
+	///
+	/// ```
+	/// let document = "a=1\n[S]\nb=2\nb=3\n[T]\nb=4\n[S]\nb=5\nc=6";
+	/// let parser = ini_core::Parser::new(document);
+	/// assert_eq!(parser.clone().last_property(Some("S"), "b"), Some("5"));
+	/// assert_eq!(parser.clone().last_property(Some("T"), "b"), Some("4"));
+	/// assert_eq!(parser.clone().last_property(None, "a"), Some("1"));
+	/// assert_eq!(parser.clone().last_property(None, "b"), None);
+	/// ```
+	pub fn last_property(self, section: Option<&str>, key: &str) -> Option<&'a str> {
+		self.bytes.last_property(section.map(str::as_bytes), key.as_bytes()).map(from_utf8)
+	}
+}
+
+impl<'a> Iterator for Parser<'a> {
+	type Item = Item<'a>;
+
+	#[inline]
+	fn next(&mut self) -> Option<Item<'a>> {
+		self.bytes.next().map(Item::from_bytes)
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Parser<'a> {}
+
 #[cfg(test)]
 mod tests;
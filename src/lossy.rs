@@ -0,0 +1,110 @@
+use core::fmt;
+use alloc::{borrow::Cow, string::String};
+use crate::{ByteItem, BytesParser, Item};
+
+/// Ini element decoded lossily from raw bytes.
+///
+/// Mirrors [`Item`] with strings which borrow the input unless they contained invalid UTF-8.
+///
+/// See [`BytesParser::lossy`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum LossyItem<'a> {
+	/// Syntax error.
+	///
+	/// See [`Item::Error`].
+	Error(Cow<'a, str>),
+	/// Section header element.
+	///
+	/// See [`Item::Section`].
+	Section(Cow<'a, str>),
+	/// End of section.
+	///
+	/// See [`Item::SectionEnd`].
+	SectionEnd,
+	/// Property element.
+	///
+	/// See [`Item::Property`].
+	Property(Cow<'a, str>, Option<Cow<'a, str>>),
+	/// Comment.
+	///
+	/// See [`Item::Comment`].
+	Comment(Cow<'a, str>),
+	/// Blank line.
+	///
+	/// See [`Item::Blank`].
+	Blank,
+}
+
+impl<'a> LossyItem<'a> {
+	/// Decodes the byte slices, invalid sequences are replaced with `U+FFFD`.
+	///
+	/// See [`String::from_utf8_lossy`].
+	pub fn from_bytes(item: ByteItem<'a>) -> LossyItem<'a> {
+		match item {
+			ByteItem::Error(error) => LossyItem::Error(String::from_utf8_lossy(error)),
+			ByteItem::Section(section) => LossyItem::Section(String::from_utf8_lossy(section)),
+			ByteItem::SectionEnd => LossyItem::SectionEnd,
+			ByteItem::Property(key, value) => LossyItem::Property(String::from_utf8_lossy(key), value.map(String::from_utf8_lossy)),
+			ByteItem::Comment(comment) => LossyItem::Comment(String::from_utf8_lossy(comment)),
+			ByteItem::Blank => LossyItem::Blank,
+		}
+	}
+
+	/// Borrows the item.
+	pub fn as_item(&self) -> Item<'_> {
+		match self {
+			LossyItem::Error(error) => Item::Error(error),
+			LossyItem::Section(section) => Item::Section(section),
+			LossyItem::SectionEnd => Item::SectionEnd,
+			LossyItem::Property(key, value) => Item::Property(key, value.as_deref()),
+			LossyItem::Comment(comment) => Item::Comment(comment),
+			LossyItem::Blank => Item::Blank,
+		}
+	}
+}
+
+impl<'a> From<ByteItem<'a>> for LossyItem<'a> {
+	#[inline]
+	fn from(item: ByteItem<'a>) -> LossyItem<'a> {
+		LossyItem::from_bytes(item)
+	}
+}
+
+impl<'a> fmt::Display for LossyItem<'a> {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.as_item(), f)
+	}
+}
+
+/// Iterator decoding the items of a byte parser lossily.
+///
+/// See [`BytesParser::lossy`].
+#[derive(Clone, Debug)]
+pub struct Lossy<'a> {
+	parser: BytesParser<'a>,
+}
+
+impl<'a> Lossy<'a> {
+	#[inline]
+	pub(crate) const fn new(parser: BytesParser<'a>) -> Lossy<'a> {
+		Lossy { parser }
+	}
+
+	/// Returns the underlying parser.
+	#[inline]
+	pub const fn parser(&self) -> &BytesParser<'a> {
+		&self.parser
+	}
+}
+
+impl<'a> Iterator for Lossy<'a> {
+	type Item = LossyItem<'a>;
+
+	#[inline]
+	fn next(&mut self) -> Option<LossyItem<'a>> {
+		self.parser.next().map(LossyItem::from_bytes)
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Lossy<'a> {}
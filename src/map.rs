@@ -0,0 +1,122 @@
+use alloc::{borrow::ToOwned, collections::BTreeMap, string::String, vec::Vec};
+use crate::{from_utf8, strip_array_suffix, Item, Parser};
+
+/// Document collected by [`Parser::into_map`].
+///
+/// Maps the section name to its properties in document order, the global section has name `None`.
+pub type Map = BTreeMap<Option<String>, Vec<(String, Option<String>)>>;
+
+/// Document collected by [`Parser::multi_map`].
+///
+/// Maps the section name and key to all of its values in document order, the global section has name `None`.
+pub type MultiMap = BTreeMap<(Option<String>, String), Vec<Option<String>>>;
+
+/// Policy for duplicate keys within a section.
+///
+/// See [`Parser::into_map_with`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum DuplicateKeys {
+	/// Keeps every property in document order.
+	#[default]
+	KeepAll,
+	/// Keeps the first property, later duplicates are ignored.
+	KeepFirst,
+	/// Keeps the last property, it replaces the earlier value in place.
+	KeepLast,
+}
+
+impl<'a> Parser<'a> {
+	/// Collects the properties into a map keyed by section name.
+	///
+	/// Duplicate sections are merged and all duplicate keys are kept, see [`into_map_with`](Parser::into_map_with).
+	///
+	/// Comments, blank lines and errors are skipped, as are the properties after a malformed section header.
+	///
+	/// ```
+	/// let map = ini_core::Parser::new("a=1\n[S]\nb=2\n[S]\nb=3").into_map();
+	/// assert_eq!(map[&None], [("a".to_string(), Some("1".to_string()))]);
+	/// assert_eq!(map[&Some("S".to_string())].len(), 2);
+	/// ```
+	#[inline]
+	pub fn into_map(self) -> Map {
+		self.into_map_with(DuplicateKeys::KeepAll)
+	}
+
+	/// Collects the properties into a map keyed by section name with the given policy for duplicate keys.
+	///
+	/// Duplicate sections are merged, their properties are appended in document order.
+	/// Keys are compared case-sensitively, with [`case_insensitive`](Parser::case_insensitive) enabled section names and keys are lowercased.
+	///
+	/// The global section is present only if it has properties, its name is set with [`global_section_name`](Parser::global_section_name).
+	///
+	/// ```
+	/// use ini_core::DuplicateKeys;
+	///
+	/// let document = "[S]\nkey=1\nother\n[S]\nkey=2";
+	/// let map = ini_core::Parser::new(document).into_map_with(DuplicateKeys::KeepLast);
+	/// let section = &map[&Some("S".to_string())];
+	/// assert_eq!(section[0], ("key".to_string(), Some("2".to_string())));
+	/// assert_eq!(section[1], ("other".to_string(), None));
+	/// ```
+	pub fn into_map_with(self, duplicates: DuplicateKeys) -> Map {
+		let case_insensitive = self.bytes.is_case_insensitive();
+		let fold = |s: &str| if case_insensitive { s.to_ascii_lowercase() } else { s.to_owned() };
+		let global = self.bytes.global_name().map(|name| fold(from_utf8(name)));
+		let mut map = Map::new();
+		let mut parser = self;
+		while let Some(item) = parser.next() {
+			if let Item::Property(key, value) = item {
+				let Some(section) = parser.lookup_section() else { continue };
+				let name = section.map(fold).or_else(|| global.clone());
+				let props = map.entry(name).or_default();
+				let key = fold(key);
+				let value = value.map(ToOwned::to_owned);
+				let existing = match duplicates {
+					DuplicateKeys::KeepAll => None,
+					_ => props.iter_mut().find(|(k, _)| *k == key),
+				};
+				match existing {
+					Some(prop) => if duplicates == DuplicateKeys::KeepLast {
+						prop.1 = value;
+					},
+					None => props.push((key, value)),
+				}
+			}
+		}
+		map
+	}
+	/// Collects the values of every key into a list.
+	///
+	/// Repeated keys and array entries written as `key[]` are collapsed into the list of the key with the suffix stripped, see [`strip_array_suffix`].
+	/// Duplicate sections are merged.
+	/// Keys are compared case-sensitively, with [`case_insensitive`](Parser::case_insensitive) enabled section names and keys are lowercased.
+	///
+	/// The entries are sorted by section name and key, the global section comes first unless named with [`global_section_name`](Parser::global_section_name).
+	/// The values of a key are in document order.
+	///
+	/// Comments, blank lines and errors are skipped, as are the properties after a malformed section header.
+	///
+	/// ```
+	/// let document = "[Paths]\ndir[]=/usr\ndir[]=/opt\nhome=/root\n[Other]\n[Paths]\ndir=/srv";
+	/// let map = ini_core::Parser::new(document).multi_map();
+	/// let dirs = &map[&(Some("Paths".to_string()), "dir".to_string())];
+	/// assert_eq!(dirs, &[Some("/usr".to_string()), Some("/opt".to_string()), Some("/srv".to_string())]);
+	/// assert_eq!(map.len(), 2);
+	/// ```
+	pub fn multi_map(self) -> MultiMap {
+		let case_insensitive = self.bytes.is_case_insensitive();
+		let fold = |s: &str| if case_insensitive { s.to_ascii_lowercase() } else { s.to_owned() };
+		let global = self.bytes.global_name().map(|name| fold(from_utf8(name)));
+		let mut map = MultiMap::new();
+		let mut parser = self;
+		while let Some(item) = parser.next() {
+			if let Item::Property(key, value) = item {
+				let Some(section) = parser.lookup_section() else { continue };
+				let name = section.map(fold).or_else(|| global.clone());
+				let key = (name, fold(strip_array_suffix(key)));
+				map.entry(key).or_default().push(value.map(ToOwned::to_owned));
+			}
+		}
+		map
+	}
+}
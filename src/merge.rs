@@ -0,0 +1,185 @@
+use alloc::{string::String, vec::Vec};
+use crate::{Item, Newline, Parser};
+
+// Properties of a section in the overlay, the last value of a key wins
+struct Section<'a> {
+	name: Option<&'a str>,
+	props: Vec<(&'a str, Option<&'a str>)>,
+	// Set when the section was found in the base
+	found: bool,
+	// Set when the property was written to the output
+	done: Vec<bool>,
+}
+
+fn overlay_sections(overlay: &str) -> Vec<Section<'_>> {
+	let mut sections = Vec::new();
+	sections.push(Section { name: None, props: Vec::new(), found: true, done: Vec::new() });
+	let mut parser = Parser::new(overlay).auto_trim(true);
+	while let Some(item) = parser.next() {
+		match item {
+			// Empty sections are appended too
+			Item::Section(name) => _ = section_mut(&mut sections, Some(name)),
+			Item::Property(key, value) => {
+				let Some(name) = parser.lookup_section() else { continue };
+				let props = &mut section_mut(&mut sections, name).props;
+				match props.iter_mut().find(|prop| prop.0 == key) {
+					Some(prop) => prop.1 = value,
+					None => props.push((key, value)),
+				}
+			},
+			_ => (),
+		}
+	}
+	for section in &mut sections {
+		section.done = alloc::vec![false; section.props.len()];
+	}
+	return sections;
+}
+
+// Finds the section by name, adding it if it is new
+fn section_mut<'s, 'a>(sections: &'s mut Vec<Section<'a>>, name: Option<&'a str>) -> &'s mut Section<'a> {
+	let index = match sections.iter().position(|section| section.name == name) {
+		Some(index) => index,
+		None => {
+			sections.push(Section { name, props: Vec::new(), found: false, done: Vec::new() });
+			sections.len() - 1
+		},
+	};
+	&mut sections[index]
+}
+
+fn push_line(out: &mut String, newline: Newline, key: &str, value: Option<&str>) {
+	out.push_str(key);
+	if let Some(value) = value {
+		out.push('=');
+		out.push_str(value);
+	}
+	out.push_str(newline.as_str());
+}
+
+// Inserts the properties not found in the base at the given position
+fn insert_missing(out: &mut String, at: usize, newline: Newline, section: &mut Section) {
+	if section.done.iter().all(|&done| done) {
+		return;
+	}
+	let mut lines = String::new();
+	if at > 0 && !out[..at].ends_with(['\n', '\r']) {
+		lines.push_str(newline.as_str());
+	}
+	for (prop, done) in section.props.iter().zip(&mut section.done) {
+		if !*done {
+			*done = true;
+			push_line(&mut lines, newline, prop.0, prop.1);
+		}
+	}
+	out.insert_str(at, &lines);
+}
+
+/// Merges an overlay document into a base document.
+///
+/// Intended for layered configuration where user settings override defaults.
+///
+/// * Properties of the overlay replace the values of the properties with the same section and key in the base.
+///   Every duplicate of the key in the base section is replaced, the key, the whitespace around the value and the line ending are kept.
+/// * Within the overlay the last value of a duplicate key wins.
+/// * Properties not in the base are inserted after the last property of the first matching base section,
+///   global properties after the last global property or at the start of the document.
+/// * Sections not in the base are appended at the end of the document in the order they appear in the overlay.
+/// * Comments, blank lines and malformed lines of the base are kept verbatim, those of the overlay are dropped.
+/// * Properties after a malformed section header belong to no section, those of the base are kept verbatim and those of the overlay are dropped.
+///
+/// Both documents are parsed with [`auto_trim`](Parser::auto_trim) enabled.
+/// Section names and keys are compared case sensitively, sections are tracked the same way as [`Parser::get`].
+/// Inserted lines use the newline style of the base, see [`Newline::detect`].
+///
+/// ```
+/// let base = "; Defaults\nname=app\n\n[window]\nwidth = 640\nheight = 480\n";
+/// let overlay = "[window]\nwidth=1280\nfullscreen=true\n[user]\ntheme=dark\n";
+/// assert_eq!(ini_core::merge(base, overlay),
+/// 	"; Defaults\nname=app\n\n[window]\nwidth = 1280\nheight = 480\nfullscreen=true\n[user]\ntheme=dark\n");
+/// ```
+pub fn merge(base: &str, overlay: &str) -> String {
+	let mut sections = overlay_sections(overlay);
+	let newline = Newline::detect(base).unwrap_or_default();
+	let mut out = String::with_capacity(base.len() + overlay.len());
+
+	let mut parser = Parser::new(base).auto_trim(true);
+	// Index of the current overlay section, if any
+	let mut current = Some(0);
+	// Insertion point for missing properties of the current section
+	let mut insert_at = 0;
+	loop {
+		let start = parser.offset();
+		let Some(item) = parser.next() else { break };
+		let line = &base[start..parser.offset()];
+		match item {
+			Item::Section(name) => {
+				current = sections.iter().position(|section| section.name == Some(name));
+				if let Some(index) = current {
+					sections[index].found = true;
+				}
+				out.push_str(line);
+				insert_at = out.len();
+			},
+			Item::SectionEnd => {
+				if let Some(index) = current {
+					insert_missing(&mut out, insert_at, newline, &mut sections[index]);
+				}
+				if parser.in_global_section() {
+					current = Some(0);
+					insert_at = out.len();
+				}
+			},
+			// A malformed section header starts a section which matches no overlay section
+			Item::Error(_) if parser.lookup_section().is_none() => {
+				current = None;
+				out.push_str(line);
+			},
+			Item::Property(key, value) => {
+				let prop = current.and_then(|index| {
+					let section = &mut sections[index];
+					let i = section.props.iter().position(|prop| prop.0 == key)?;
+					section.done[i] = true;
+					Some(section.props[i])
+				});
+				match (prop, value) {
+					(Some((_, Some(new))), Some(old)) => {
+						// Replace the value in place, keeping the surrounding whitespace
+						let i = old.as_ptr() as usize - line.as_ptr() as usize;
+						out.push_str(&line[..i]);
+						out.push_str(new);
+						out.push_str(&line[i + old.len()..]);
+					},
+					(Some((_, new)), _) => {
+						let end = line.trim_end_matches(['\n', '\r']).len();
+						out.push_str(key);
+						if let Some(new) = new {
+							out.push('=');
+							out.push_str(new);
+						}
+						out.push_str(&line[end..]);
+					},
+					(None, _) => out.push_str(line),
+				}
+				insert_at = out.len();
+			},
+			_ => out.push_str(line),
+		}
+	}
+
+	for section in &mut sections {
+		if !section.found {
+			let name = section.name.unwrap_or_default();
+			if !out.is_empty() && !out.ends_with(['\n', '\r']) {
+				out.push_str(newline.as_str());
+			}
+			out.push('[');
+			out.push_str(name);
+			out.push(']');
+			out.push_str(newline.as_str());
+			let at = out.len();
+			insert_missing(&mut out, at, newline, section);
+		}
+	}
+	return out;
+}
@@ -0,0 +1,96 @@
+use core::fmt;
+use alloc::string::String;
+use crate::Item;
+
+/// Owned ini element.
+///
+/// Mirrors [`Item`] with owned strings so items can be kept past the lifetime of the input.
+///
+/// ```
+/// let mut items: Vec<_> = ini_core::Parser::new(";comment\n[Section]\nKey=Value\n")
+/// 	.map(|item| item.to_owned())
+/// 	.collect();
+///
+/// if let ini_core::OwnedItem::Property(_, value) = &mut items[3] {
+/// 	*value = Some("Changed".to_string());
+/// }
+///
+/// let document: String = items.iter().map(|item| item.to_string()).collect();
+/// assert_eq!(document, ";comment\n[Section]\nKey=Changed\n");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum OwnedItem {
+	/// Syntax error.
+	///
+	/// See [`Item::Error`].
+	Error(String),
+	/// Section header element.
+	///
+	/// See [`Item::Section`].
+	Section(String),
+	/// End of section.
+	///
+	/// See [`Item::SectionEnd`].
+	SectionEnd,
+	/// Property element.
+	///
+	/// See [`Item::Property`].
+	Property(String, Option<String>),
+	/// Comment.
+	///
+	/// See [`Item::Comment`].
+	Comment(String),
+	/// Blank line.
+	///
+	/// See [`Item::Blank`].
+	Blank,
+}
+
+impl OwnedItem {
+	/// Borrows the item.
+	pub fn as_item(&self) -> Item<'_> {
+		match self {
+			OwnedItem::Error(error) => Item::Error(error),
+			OwnedItem::Section(section) => Item::Section(section),
+			OwnedItem::SectionEnd => Item::SectionEnd,
+			OwnedItem::Property(key, value) => Item::Property(key, value.as_deref()),
+			OwnedItem::Comment(comment) => Item::Comment(comment),
+			OwnedItem::Blank => Item::Blank,
+		}
+	}
+}
+
+impl<'a> Item<'a> {
+	/// Copies the strings into an [`OwnedItem`].
+	pub fn to_owned(&self) -> OwnedItem {
+		match self {
+			&Item::Error(error) => OwnedItem::Error(error.into()),
+			&Item::Section(section) => OwnedItem::Section(section.into()),
+			&Item::SectionEnd => OwnedItem::SectionEnd,
+			&Item::Property(key, value) => OwnedItem::Property(key.into(), value.map(String::from)),
+			&Item::Comment(comment) => OwnedItem::Comment(comment.into()),
+			&Item::Blank => OwnedItem::Blank,
+		}
+	}
+}
+
+impl<'a> From<Item<'a>> for OwnedItem {
+	#[inline]
+	fn from(item: Item<'a>) -> OwnedItem {
+		item.to_owned()
+	}
+}
+
+impl<'a> PartialEq<Item<'a>> for OwnedItem {
+	#[inline]
+	fn eq(&self, other: &Item<'a>) -> bool {
+		self.as_item() == *other
+	}
+}
+
+impl fmt::Display for OwnedItem {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.as_item(), f)
+	}
+}
@@ -0,0 +1,70 @@
+use core::{mem, sync::atomic::{AtomicPtr, Ordering}};
+
+#[cfg(target_pointer_width = "64")]
+use super::swar64 as scalar;
+#[cfg(not(target_pointer_width = "64"))]
+use super::swar32 as scalar;
+
+type FindNl = unsafe fn(&[u8]) -> usize;
+type FindChrNl = unsafe fn(&[u8], u8) -> (usize, usize);
+
+// The implementations are inlined into these wrappers and compiled with the target feature enabled
+#[target_feature(enable = "avx2")]
+unsafe fn find_nl_avx2(s: &[u8]) -> usize {
+	super::avx2::find_nl(s)
+}
+#[target_feature(enable = "avx2")]
+unsafe fn find_chr_nl_avx2(s: &[u8], chr: u8) -> (usize, usize) {
+	super::avx2::find_chr_nl(s, chr)
+}
+#[target_feature(enable = "sse2")]
+unsafe fn find_nl_sse2(s: &[u8]) -> usize {
+	super::sse2::find_nl(s)
+}
+#[target_feature(enable = "sse2")]
+unsafe fn find_chr_nl_sse2(s: &[u8], chr: u8) -> (usize, usize) {
+	super::sse2::find_chr_nl(s, chr)
+}
+unsafe fn find_nl_scalar(s: &[u8]) -> usize {
+	scalar::find_nl(s)
+}
+unsafe fn find_chr_nl_scalar(s: &[u8], chr: u8) -> (usize, usize) {
+	scalar::find_chr_nl(s, chr)
+}
+
+// Start out with the detection routines which replace themselves on first use
+static FIND_NL: AtomicPtr<()> = AtomicPtr::new(detect_find_nl as *mut ());
+static FIND_CHR_NL: AtomicPtr<()> = AtomicPtr::new(detect_find_chr_nl as *mut ());
+
+unsafe fn detect_find_nl(s: &[u8]) -> usize {
+	let f: FindNl = if std::is_x86_feature_detected!("avx2") { find_nl_avx2 }
+	else if std::is_x86_feature_detected!("sse2") { find_nl_sse2 }
+	else { find_nl_scalar };
+	FIND_NL.store(f as *mut (), Ordering::Relaxed);
+	f(s)
+}
+
+unsafe fn detect_find_chr_nl(s: &[u8], chr: u8) -> (usize, usize) {
+	let f: FindChrNl = if std::is_x86_feature_detected!("avx2") { find_chr_nl_avx2 }
+	else if std::is_x86_feature_detected!("sse2") { find_chr_nl_sse2 }
+	else { find_chr_nl_scalar };
+	FIND_CHR_NL.store(f as *mut (), Ordering::Relaxed);
+	f(s, chr)
+}
+
+#[inline]
+pub fn find_nl(s: &[u8]) -> usize {
+	// Only ever contains one of the functions above, the required target features have been detected
+	unsafe {
+		let f = mem::transmute::<*mut (), FindNl>(FIND_NL.load(Ordering::Relaxed));
+		f(s)
+	}
+}
+
+#[inline]
+pub fn find_chr_nl(s: &[u8], chr: u8) -> (usize, usize) {
+	unsafe {
+		let f = mem::transmute::<*mut (), FindChrNl>(FIND_CHR_NL.load(Ordering::Relaxed));
+		f(s, chr)
+	}
+}
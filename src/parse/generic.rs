@@ -1,6 +1,6 @@
 
 #[inline]
-pub fn find_nl(s: &[u8]) -> usize {
+pub const fn find_nl(s: &[u8]) -> usize {
 	let mut i = 0;
 	while i < s.len() {
 		if s[i] == b'\n' || s[i] == b'\r' {
@@ -13,7 +13,7 @@ pub fn find_nl(s: &[u8]) -> usize {
 }
 
 #[inline]
-pub fn find_nl_chr(s: &[u8], chr: u8) -> usize {
+pub const fn find_nl_chr(s: &[u8], chr: u8) -> usize {
 	let mut i = 0;
 	while i < s.len() {
 		if s[i] == b'\n' || s[i] == b'\r' || s[i] == chr {
@@ -24,3 +24,14 @@ pub fn find_nl_chr(s: &[u8], chr: u8) -> usize {
 	unsafe_assert!(i <= s.len());
 	return i;
 }
+
+// Unused by the SWAR implementations which compose their own
+#[allow(dead_code)]
+#[inline]
+pub fn find_chr_nl(s: &[u8], chr: u8) -> (usize, usize) {
+	let i = find_nl_chr(s, chr);
+	if i < s.len() && s[i] == chr {
+		return (i, i + 1 + find_nl(&s[i + 1..]));
+	}
+	return (i, i);
+}
@@ -0,0 +1,13 @@
+#[inline]
+pub fn find_nl(s: &[u8]) -> usize {
+	::memchr::memchr2(b'\n', b'\r', s).unwrap_or(s.len())
+}
+
+#[inline]
+pub fn find_chr_nl(s: &[u8], chr: u8) -> (usize, usize) {
+	let i = ::memchr::memchr3(b'\n', b'\r', chr, s).unwrap_or(s.len());
+	if i < s.len() && s[i] == chr {
+		return (i, i + 1 + find_nl(&s[i + 1..]));
+	}
+	return (i, i);
+}
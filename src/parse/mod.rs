@@ -1,17 +1,35 @@
 /*!
 Optimized routines for parsing INI.
 
-This module provides 2 functions: `find_nl` and `find_nl_chr`:
+This module provides 3 functions: `find_nl`, `find_chr_nl` and `rfind_nl`:
 
 * `fn find_nl(s: &[u8]) -> usize`
 
   Finds the first `b'\r'` or `b'\n'` in the input byte string and returns its index.
   If no match was found returns the length of the input.
 
-* `fn find_nl_chr(s: &[u8], chr: u8) -> usize`
+* `fn find_chr_nl(s: &[u8], chr: u8) -> (usize, usize)`
 
-  Finds the first `b'\r'`, `b'\n'` or `chr` in the input byte string and returns its index.
-  If no match was found returns the length of the input.
+  Finds the first `b'\r'`, `b'\n'` or `chr` and the first `b'\r'` or `b'\n'` in the input byte string in a single pass.
+  Both indices are equal if `chr` does not precede the newline.
+  If no newline was found the length of the input is returned instead.
+
+* `fn rfind_nl(s: &[u8]) -> usize`
+
+  Finds the last `b'\r'` or `b'\n'` in the input byte string and returns the index following it.
+  If no match was found returns zero.
+  Only used for backward lookups and not optimized.
+
+With the `runtime-dispatch` feature the x86 implementation is selected on first use based on the detected CPU features.
+This requires `std` and is skipped if AVX2 is already enabled at compile time.
+
+With the `memchr` feature the `memchr` crate is used instead, it brings SIMD to the architectures without a hand written implementation.
+On x86_64 it is faster for long lines but about 50% slower for typical short lines due to its higher setup cost.
+
+With the `safe` feature the generic implementation is always used as all others require `unsafe` code.
+The generic `find_nl` and `find_nl_chr` are `const fn` for parsing in const contexts.
+
+On aarch64 the NEON implementation narrows the comparison result to a nibble per byte as NEON has no movemask instruction.
 
 For more information on the SWAR approaches see: <http://0x80.pl/articles/simd-strfind.html#swar>.
 In reality I only see minor improvements with SWAR (about 33% faster).
@@ -19,30 +37,57 @@ In reality I only see minor improvements with SWAR (about 33% faster).
 */
 
 // LLVM is big dum dum, trust me I'm a human
-#[cfg(not(debug_assertions))]
+#[cfg(not(any(debug_assertions, feature = "safe")))]
 macro_rules! unsafe_assert {
 	($e:expr) => { unsafe { if !$e { ::core::hint::unreachable_unchecked(); } } };
 }
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "safe"))]
 macro_rules! unsafe_assert {
 	($e:expr) => {};
 }
 
-mod generic;
+// Also used in const contexts
+pub mod generic;
 
 cfg_if::cfg_if! {
+	// The optimizations require unsafe code
+	if #[cfg(feature = "safe")] {
+		pub use self::generic::*;
+	}
+	else if #[cfg(feature = "memchr")] {
+		mod memchr;
+		pub use self::memchr::*;
+	}
 	// These optimizations are little endian specific
-	if #[cfg(not(target_endian = "little"))] {
+	else if #[cfg(not(target_endian = "little"))] {
 		pub use self::generic::*;
 	}
 	else if #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx2"))] {
 		mod avx2;
 		pub use self::avx2::*;
 	}
+	else if #[cfg(all(feature = "runtime-dispatch", any(target_arch = "x86", target_arch = "x86_64")))] {
+		mod avx2;
+		mod sse2;
+		#[cfg(target_pointer_width = "64")]
+		mod swar64;
+		#[cfg(not(target_pointer_width = "64"))]
+		mod swar32;
+		mod dispatch;
+		pub use self::dispatch::*;
+	}
 	else if #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))] {
 		mod sse2;
 		pub use self::sse2::*;
 	}
+	else if #[cfg(all(target_arch = "aarch64", target_feature = "neon"))] {
+		mod neon;
+		pub use self::neon::*;
+	}
+	else if #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))] {
+		mod simd128;
+		pub use self::simd128::*;
+	}
 	else if #[cfg(target_pointer_width = "64")] {
 		mod swar64;
 		pub use self::swar64::*;
@@ -56,6 +101,18 @@ cfg_if::cfg_if! {
 	}
 }
 
+#[inline]
+pub fn rfind_nl(s: &[u8]) -> usize {
+	let mut i = s.len();
+	while i > 0 {
+		if s[i - 1] == b'\n' || s[i - 1] == b'\r' {
+			break;
+		}
+		i -= 1;
+	}
+	return i;
+}
+
 #[test]
 fn test_parse() {
 	let mut buffer = [b'-'; 254];
@@ -68,9 +125,33 @@ fn test_parse() {
 
 		// Check target implementation
 		assert_eq!(find_nl(&buffer), i);
-		assert_eq!(find_nl_chr(&buffer, b'='), i);
+		assert_eq!(find_chr_nl(&buffer, b'='), (i, i));
+
+		// Check the character followed by the newline
+		for j in 0..i {
+			let prev = buffer[j];
+			buffer[j] = b'=';
+			assert_eq!(generic::find_chr_nl(&buffer, b'='), (j, i));
+			assert_eq!(find_chr_nl(&buffer, b'='), (j, i));
+			buffer[j] = prev;
+		}
+
+		assert_eq!(rfind_nl(&buffer[..i]), 0);
+		assert_eq!(rfind_nl(&buffer[..i + 1]), i + 1);
+		assert_eq!(rfind_nl(&buffer), i + 1);
 
 		// Write annoying byte back
 		buffer[i] = if i & 1 == 0 { !0x0D } else { !0x0A };
 	}
+
+	// Check the character without a newline
+	let len = buffer.len();
+	assert_eq!(find_chr_nl(&buffer, b'='), (len, len));
+	for j in 0..len {
+		let prev = buffer[j];
+		buffer[j] = b'=';
+		assert_eq!(generic::find_chr_nl(&buffer, b'='), (j, len));
+		assert_eq!(find_chr_nl(&buffer, b'='), (j, len));
+		buffer[j] = prev;
+	}
 }
@@ -0,0 +1,87 @@
+use core::arch::aarch64::*;
+
+// NEON has no movemask, narrow every byte of the comparison to a nibble instead
+// The resulting 64-bit mask has 4 bits set for every matching byte
+#[inline(always)]
+unsafe fn nibble_mask(eq: uint8x16_t) -> u64 {
+	let res = vshrn_n_u16(vreinterpretq_u16_u8(eq), 4);
+	vget_lane_u64(vreinterpret_u64_u8(res), 0)
+}
+
+#[inline]
+pub fn find_nl(s: &[u8]) -> usize {
+	let mut offset = 0;
+
+	unsafe {
+		let n_lit = vdupq_n_u8(b'\n');
+		let r_lit = vdupq_n_u8(b'\r');
+
+		while offset + 16 <= s.len() {
+			let block = vld1q_u8(s.as_ptr().add(offset));
+
+			let n_eq = vceqq_u8(n_lit, block);
+			let r_eq = vceqq_u8(r_lit, block);
+
+			let mask = nibble_mask(vorrq_u8(n_eq, r_eq));
+
+			if mask != 0 {
+				return offset + (mask.trailing_zeros() >> 2) as usize;
+			}
+
+			offset += 16;
+		}
+	}
+
+	unsafe_assert!(offset <= s.len());
+	offset += super::generic::find_nl(&s[offset..]);
+	unsafe_assert!(offset <= s.len());
+	return offset;
+}
+
+#[inline]
+pub fn find_chr_nl(s: &[u8], chr: u8) -> (usize, usize) {
+	let mut offset = 0;
+	let mut found = usize::MAX;
+
+	unsafe {
+		let n_lit = vdupq_n_u8(b'\n');
+		let r_lit = vdupq_n_u8(b'\r');
+		let c_lit = vdupq_n_u8(chr);
+
+		while offset + 16 <= s.len() {
+			let block = vld1q_u8(s.as_ptr().add(offset));
+
+			let n_eq = vceqq_u8(n_lit, block);
+			let r_eq = vceqq_u8(r_lit, block);
+			let nl_mask = nibble_mask(vorrq_u8(n_eq, r_eq));
+
+			// Look for the character until it is found, the newline always ends the search
+			if found == usize::MAX {
+				let c_mask = nibble_mask(vceqq_u8(c_lit, block));
+				let mask = nl_mask | c_mask;
+				if mask != 0 {
+					let i = offset + (mask.trailing_zeros() >> 2) as usize;
+					if nl_mask & (1 << mask.trailing_zeros()) != 0 {
+						return (i, i);
+					}
+					found = i;
+				}
+			}
+
+			if nl_mask != 0 {
+				return (found, offset + (nl_mask.trailing_zeros() >> 2) as usize);
+			}
+
+			offset += 16;
+		}
+	}
+
+	unsafe_assert!(offset <= s.len());
+	if found == usize::MAX {
+		let (i, j) = super::generic::find_chr_nl(&s[offset..], chr);
+		return (offset + i, offset + j);
+	}
+	offset += super::generic::find_nl(&s[offset..]);
+	unsafe_assert!(offset <= s.len());
+	return (found, offset);
+}
@@ -0,0 +1,79 @@
+use core::arch::wasm32::*;
+
+#[inline]
+pub fn find_nl(s: &[u8]) -> usize {
+	let mut offset = 0;
+
+	unsafe {
+		let n_lit = u8x16_splat(b'\n');
+		let r_lit = u8x16_splat(b'\r');
+
+		while offset + 16 <= s.len() {
+			let block = v128_load(s.as_ptr().add(offset) as *const v128);
+
+			let n_eq = u8x16_eq(n_lit, block);
+			let r_eq = u8x16_eq(r_lit, block);
+
+			let mask = u8x16_bitmask(v128_or(n_eq, r_eq));
+
+			if mask != 0 {
+				return offset + mask.trailing_zeros() as usize;
+			}
+
+			offset += 16;
+		}
+	}
+
+	unsafe_assert!(offset <= s.len());
+	offset += super::generic::find_nl(&s[offset..]);
+	unsafe_assert!(offset <= s.len());
+	return offset;
+}
+
+#[inline]
+pub fn find_chr_nl(s: &[u8], chr: u8) -> (usize, usize) {
+	let mut offset = 0;
+	let mut found = usize::MAX;
+
+	unsafe {
+		let n_lit = u8x16_splat(b'\n');
+		let r_lit = u8x16_splat(b'\r');
+		let c_lit = u8x16_splat(chr);
+
+		while offset + 16 <= s.len() {
+			let block = v128_load(s.as_ptr().add(offset) as *const v128);
+
+			let n_eq = u8x16_eq(n_lit, block);
+			let r_eq = u8x16_eq(r_lit, block);
+			let nl_mask = u8x16_bitmask(v128_or(n_eq, r_eq));
+
+			// Look for the character until it is found, the newline always ends the search
+			if found == usize::MAX {
+				let c_mask = u8x16_bitmask(u8x16_eq(c_lit, block));
+				let mask = nl_mask | c_mask;
+				if mask != 0 {
+					let i = offset + mask.trailing_zeros() as usize;
+					if nl_mask & (1 << mask.trailing_zeros()) != 0 {
+						return (i, i);
+					}
+					found = i;
+				}
+			}
+
+			if nl_mask != 0 {
+				return (found, offset + nl_mask.trailing_zeros() as usize);
+			}
+
+			offset += 16;
+		}
+	}
+
+	unsafe_assert!(offset <= s.len());
+	if found == usize::MAX {
+		let (i, j) = super::generic::find_chr_nl(&s[offset..], chr);
+		return (offset + i, offset + j);
+	}
+	offset += super::generic::find_nl(&s[offset..]);
+	unsafe_assert!(offset <= s.len());
+	return (found, offset);
+}
@@ -35,8 +35,9 @@ pub fn find_nl(s: &[u8]) -> usize {
 }
 
 #[inline]
-pub fn find_nl_chr(s: &[u8], chr: u8) -> usize {
+pub fn find_chr_nl(s: &[u8], chr: u8) -> (usize, usize) {
 	let mut offset = 0;
+	let mut found = usize::MAX;
 
 	unsafe {
 		let n_lit = _mm_set1_epi8(b'\n' as i8);
@@ -48,12 +49,23 @@ pub fn find_nl_chr(s: &[u8], chr: u8) -> usize {
 
 			let n_eq = _mm_cmpeq_epi8(n_lit, block);
 			let r_eq = _mm_cmpeq_epi8(r_lit, block);
-			let c_eq = _mm_cmpeq_epi8(c_lit, block);
-
-			let mask = _mm_movemask_epi8(_mm_or_si128(_mm_or_si128(n_eq, r_eq), c_eq));
+			let nl_mask = _mm_movemask_epi8(_mm_or_si128(n_eq, r_eq));
+
+			// Look for the character until it is found, the newline always ends the search
+			if found == usize::MAX {
+				let c_mask = _mm_movemask_epi8(_mm_cmpeq_epi8(c_lit, block));
+				let mask = nl_mask | c_mask;
+				if mask != 0 {
+					let i = offset + mask.trailing_zeros() as usize;
+					if nl_mask & (1 << mask.trailing_zeros()) != 0 {
+						return (i, i);
+					}
+					found = i;
+				}
+			}
 
-			if mask != 0 {
-				return offset + mask.trailing_zeros() as usize;
+			if nl_mask != 0 {
+				return (found, offset + nl_mask.trailing_zeros() as usize);
 			}
 
 			offset += 16;
@@ -61,7 +73,11 @@ pub fn find_nl_chr(s: &[u8], chr: u8) -> usize {
 	}
 
 	unsafe_assert!(offset <= s.len());
-	offset += super::generic::find_nl_chr(&s[offset..], chr);
+	if found == usize::MAX {
+		let (i, j) = super::generic::find_chr_nl(&s[offset..], chr);
+		return (offset + i, offset + j);
+	}
+	offset += super::generic::find_nl(&s[offset..]);
 	unsafe_assert!(offset <= s.len());
-	return offset;
+	return (found, offset);
 }
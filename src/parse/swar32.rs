@@ -44,6 +44,16 @@ pub fn find_nl_chr(s: &[u8], chr: u8) -> usize {
 	return offset;
 }
 
+// The SWAR scans are cheap to restart, simply continue after the character
+#[inline]
+pub fn find_chr_nl(s: &[u8], chr: u8) -> (usize, usize) {
+	let i = find_nl_chr(s, chr);
+	if i < s.len() && s[i] == chr {
+		return (i, i + 1 + find_nl(&s[i + 1..]));
+	}
+	return (i, i);
+}
+
 #[inline]
 fn cmpeq(needle: u32, haystack: u32) -> u32 {
 	let neq = !(needle ^ haystack);
@@ -0,0 +1,94 @@
+use core::fmt;
+use crate::{ErrorKind, Item, ParseError, Parser};
+
+/// Malformed line along with its text.
+///
+/// See [`Parser::results`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LineError<'a> {
+	error: ParseError,
+	text: &'a str,
+}
+
+impl<'a> LineError<'a> {
+	/// Returns the line number (starting at 1) of the malformed line.
+	#[inline]
+	pub const fn line(&self) -> u32 {
+		self.error.line
+	}
+
+	/// Returns the kind of error.
+	#[inline]
+	pub const fn kind(&self) -> ErrorKind {
+		self.error.kind
+	}
+
+	/// Returns the malformed line.
+	#[inline]
+	pub const fn text(&self) -> &'a str {
+		self.text
+	}
+
+	/// Returns the error without the text.
+	#[inline]
+	pub const fn error(&self) -> ParseError {
+		self.error
+	}
+}
+
+impl<'a> From<LineError<'a>> for ParseError {
+	#[inline]
+	fn from(error: LineError<'a>) -> ParseError {
+		error.error
+	}
+}
+
+impl<'a> fmt::Display for LineError<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {:?}", self.error, self.text)
+	}
+}
+
+impl<'a> core::error::Error for LineError<'a> {}
+
+/// Iterator over the items of a document with malformed lines as errors.
+///
+/// See [`Parser::results`].
+#[derive(Clone, Debug)]
+pub struct Results<'a> {
+	parser: Parser<'a>,
+}
+
+impl<'a> Results<'a> {
+	#[inline]
+	pub(crate) const fn new(parser: Parser<'a>) -> Results<'a> {
+		Results { parser }
+	}
+
+	/// Returns the underlying parser.
+	#[inline]
+	pub const fn parser(&self) -> &Parser<'a> {
+		&self.parser
+	}
+}
+
+impl<'a> Iterator for Results<'a> {
+	type Item = Result<Item<'a>, LineError<'a>>;
+
+	#[inline]
+	fn next(&mut self) -> Option<Result<Item<'a>, LineError<'a>>> {
+		match self.parser.next()? {
+			Item::Error(text) => {
+				// Errors which stopped parsing are already classified
+				let error = match self.parser.error() {
+					Some(error) => error,
+					None => ParseError { line: self.parser.line_number(), kind: self.parser.bytes.error_kind(text.as_bytes()) },
+				};
+				Some(Err(LineError { error, text }))
+			},
+			item => Some(Ok(item)),
+		}
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Results<'a> {}
@@ -0,0 +1,75 @@
+use crate::{Item, Parser};
+
+/// Iterator over the sections of a document.
+///
+/// See [`Parser::sections`].
+#[derive(Clone, Debug)]
+pub struct Sections<'a> {
+	parser: Parser<'a>,
+	started: bool,
+}
+
+impl<'a> Sections<'a> {
+	#[inline]
+	pub(crate) const fn new(parser: Parser<'a>) -> Sections<'a> {
+		Sections { parser, started: false }
+	}
+}
+
+impl<'a> Iterator for Sections<'a> {
+	type Item = (Option<&'a str>, SectionItems<'a>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		// The global section is always returned first
+		if !self.started {
+			self.started = true;
+			return Some((None, SectionItems::new(self.parser.clone())));
+		}
+		// Skip the items of the current section
+		while self.parser.next()? != Item::SectionEnd {}
+		let start = self.parser.clone();
+		match self.parser.next()? {
+			Item::Section(name) => Some((Some(name), SectionItems::new(self.parser.clone()))),
+			// Malformed section header, include the error in the section items
+			_ => Some((None, SectionItems::new(start))),
+		}
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Sections<'a> {}
+
+/// Iterator over the items of a section.
+///
+/// See [`Parser::sections`].
+#[derive(Clone, Debug)]
+pub struct SectionItems<'a> {
+	parser: Parser<'a>,
+	done: bool,
+}
+
+impl<'a> SectionItems<'a> {
+	#[inline]
+	const fn new(parser: Parser<'a>) -> SectionItems<'a> {
+		SectionItems { parser, done: false }
+	}
+}
+
+impl<'a> Iterator for SectionItems<'a> {
+	type Item = Item<'a>;
+
+	#[inline]
+	fn next(&mut self) -> Option<Item<'a>> {
+		if self.done {
+			return None;
+		}
+		match self.parser.next() {
+			Some(Item::SectionEnd) | None => {
+				self.done = true;
+				None
+			},
+			item => item,
+		}
+	}
+}
+
+impl<'a> core::iter::FusedIterator for SectionItems<'a> {}
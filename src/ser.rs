@@ -0,0 +1,595 @@
+/*!
+Serialize INI documents with serde.
+
+The inverse of the [`de`](crate::de) module:
+
+* Scalar fields of the top-level struct become global properties emitted before any section.
+* Struct (or map) fields of the top-level struct become sections, their fields become properties.
+* Sequences become repeated keys, eg. `items=1` followed by `items=2`.
+* `None` values are omitted.
+* Nested sections are not supported.
+
+Strings are validated with [`Item::write_checked`], strings containing newlines are rejected so the output can always be parsed back.
+Keys are also checked like [`Writer::property`](crate::Writer::property) does, keys starting with `[` or `;` are rejected.
+
+```
+#[derive(serde::Serialize)]
+struct Config {
+	name: &'static str,
+	server: Server,
+}
+#[derive(serde::Serialize)]
+struct Server {
+	host: &'static str,
+	ports: Vec<u16>,
+	timeout: Option<u32>,
+}
+
+let config = Config {
+	name: "example",
+	server: Server { host: "localhost", ports: vec![80, 443], timeout: None },
+};
+
+assert_eq!(ini_core::ser::to_string(&config).unwrap(), "\
+name=example
+
+[server]
+host=localhost
+ports=80
+ports=443
+");
+```
+*/
+
+use core::fmt;
+use alloc::string::{String, ToString};
+use serde::ser::{self, Impossible, Serialize};
+use crate::{writer, Item, WriteError};
+
+pub use crate::de::Error;
+
+impl ser::Error for Error {
+	#[cold]
+	fn custom<T: fmt::Display>(msg: T) -> Error {
+		<Error as serde::de::Error>::custom(msg)
+	}
+}
+
+/// Serializes the value as an INI document.
+pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String, Error> {
+	value.serialize(DocumentSerializer)
+}
+
+#[cold]
+fn unsupported(what: &str) -> Error {
+	ser::Error::custom(format_args!("{} is not supported", what))
+}
+
+fn write_item(out: &mut String, item: Item) -> Result<(), Error> {
+	let checked = match item {
+		Item::Property(key, value) if writer::is_invalid_key(key, value, b';') => Err(WriteError::InvalidKey),
+		_ => item.write_checked(out),
+	};
+	checked.map_err(|err| ser::Error::custom(format_args!("{}: {:?}", err, item)))
+}
+
+macro_rules! unsupported {
+	($($method:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+		$(
+			fn $method(self, $(_: $ty),*) -> Result<$ret, Error> {
+				Err(unsupported(stringify!($method)))
+			}
+		)*
+	};
+}
+
+//----------------------------------------------------------------
+// Scalar serializer
+
+struct ScalarSerializer;
+
+macro_rules! scalar_to_string {
+	($($method:ident($ty:ty);)*) => {
+		$(
+			fn $method(self, v: $ty) -> Result<String, Error> {
+				Ok(v.to_string())
+			}
+		)*
+	};
+}
+
+impl ser::Serializer for ScalarSerializer {
+	type Ok = String;
+	type Error = Error;
+	type SerializeSeq = Impossible<String, Error>;
+	type SerializeTuple = Impossible<String, Error>;
+	type SerializeTupleStruct = Impossible<String, Error>;
+	type SerializeTupleVariant = Impossible<String, Error>;
+	type SerializeMap = Impossible<String, Error>;
+	type SerializeStruct = Impossible<String, Error>;
+	type SerializeStructVariant = Impossible<String, Error>;
+
+	scalar_to_string! {
+		serialize_bool(bool);
+		serialize_i8(i8);
+		serialize_i16(i16);
+		serialize_i32(i32);
+		serialize_i64(i64);
+		serialize_i128(i128);
+		serialize_u8(u8);
+		serialize_u16(u16);
+		serialize_u32(u32);
+		serialize_u64(u64);
+		serialize_u128(u128);
+		serialize_f32(f32);
+		serialize_f64(f64);
+		serialize_char(char);
+		serialize_str(&str);
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<String, Error> {
+		match core::str::from_utf8(v) {
+			Ok(s) => Ok(s.into()),
+			Err(_) => Err(ser::Error::custom("bytes are not valid utf8")),
+		}
+	}
+
+	fn serialize_unit(self) -> Result<String, Error> {
+		Ok(String::new())
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+		Ok(String::new())
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<String, Error> {
+		Ok(variant.into())
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<String, Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_none(self) -> Result<String, Error> {
+		Err(unsupported("nested option"))
+	}
+
+	fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<String, Error> {
+		Err(unsupported("nested option"))
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<String, Error> {
+		Err(unsupported("newtype variant"))
+	}
+
+	unsupported! {
+		serialize_seq(len: Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(len: usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+		serialize_map(len: Option<usize>) -> Self::SerializeMap;
+		serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct;
+		serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+	}
+}
+
+//----------------------------------------------------------------
+// Property serializer
+
+struct PropertySerializer<'a> {
+	out: &'a mut String,
+	key: &'a str,
+}
+
+impl<'a> PropertySerializer<'a> {
+	fn write(self, value: String) -> Result<(), Error> {
+		write_item(self.out, Item::Property(self.key, Some(&value)))
+	}
+}
+
+macro_rules! property_scalar {
+	($($method:ident($ty:ty);)*) => {
+		$(
+			fn $method(self, v: $ty) -> Result<(), Error> {
+				let value = ser::Serializer::$method(ScalarSerializer, v)?;
+				self.write(value)
+			}
+		)*
+	};
+}
+
+impl<'a> ser::Serializer for PropertySerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+	type SerializeSeq = Self;
+	type SerializeTuple = Self;
+	type SerializeTupleStruct = Self;
+	type SerializeTupleVariant = Impossible<(), Error>;
+	type SerializeMap = Impossible<(), Error>;
+	type SerializeStruct = Impossible<(), Error>;
+	type SerializeStructVariant = Impossible<(), Error>;
+
+	property_scalar! {
+		serialize_bool(bool);
+		serialize_i8(i8);
+		serialize_i16(i16);
+		serialize_i32(i32);
+		serialize_i64(i64);
+		serialize_i128(i128);
+		serialize_u8(u8);
+		serialize_u16(u16);
+		serialize_u32(u32);
+		serialize_u64(u64);
+		serialize_u128(u128);
+		serialize_f32(f32);
+		serialize_f64(f64);
+		serialize_char(char);
+		serialize_str(&str);
+		serialize_bytes(&[u8]);
+	}
+
+	fn serialize_unit(self) -> Result<(), Error> {
+		self.write(String::new())
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+		self.write(String::new())
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<(), Error> {
+		self.write(variant.into())
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_none(self) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self, Error> {
+		Ok(self)
+	}
+
+	fn serialize_tuple(self, _len: usize) -> Result<Self, Error> {
+		Ok(self)
+	}
+
+	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+		Ok(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<(), Error> {
+		Err(unsupported("newtype variant"))
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+		Err(unsupported("nested section"))
+	}
+
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Error> {
+		Err(unsupported("nested section"))
+	}
+
+	unsupported! {
+		serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+		serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+	}
+}
+
+// Sequences are written as repeated keys
+impl<'a> ser::SerializeSeq for PropertySerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		let value = value.serialize(ScalarSerializer)?;
+		write_item(self.out, Item::Property(self.key, Some(&value)))
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeTuple for PropertySerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		ser::SerializeSeq::serialize_element(self, value)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeTupleStruct for PropertySerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		ser::SerializeSeq::serialize_element(self, value)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+//----------------------------------------------------------------
+// Section serializer
+
+struct SectionSerializer<'a> {
+	out: &'a mut String,
+	key: String,
+}
+
+impl<'a> ser::SerializeStruct for SectionSerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+		value.serialize(PropertySerializer { out: self.out, key })
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeMap for SectionSerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+		self.key = key.serialize(ScalarSerializer)?;
+		Ok(())
+	}
+
+	fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(PropertySerializer { out: self.out, key: &self.key })
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+//----------------------------------------------------------------
+// Field serializer, a top-level field is either a global property or a section
+
+struct FieldSerializer<'a> {
+	document: &'a mut DocumentWriter,
+	key: &'a str,
+}
+
+impl<'a> FieldSerializer<'a> {
+	#[inline]
+	fn property(self) -> PropertySerializer<'a> {
+		PropertySerializer { out: &mut self.document.globals, key: self.key }
+	}
+
+	fn section(self) -> Result<SectionSerializer<'a>, Error> {
+		let out = &mut self.document.sections;
+		if !out.is_empty() {
+			write_item(out, Item::Blank)?;
+		}
+		write_item(out, Item::Section(self.key))?;
+		Ok(SectionSerializer { out, key: String::new() })
+	}
+}
+
+macro_rules! field_property {
+	($($method:ident($ty:ty);)*) => {
+		$(
+			fn $method(self, v: $ty) -> Result<(), Error> {
+				self.property().$method(v)
+			}
+		)*
+	};
+}
+
+impl<'a> ser::Serializer for FieldSerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+	type SerializeSeq = PropertySerializer<'a>;
+	type SerializeTuple = PropertySerializer<'a>;
+	type SerializeTupleStruct = PropertySerializer<'a>;
+	type SerializeTupleVariant = Impossible<(), Error>;
+	type SerializeMap = SectionSerializer<'a>;
+	type SerializeStruct = SectionSerializer<'a>;
+	type SerializeStructVariant = Impossible<(), Error>;
+
+	field_property! {
+		serialize_bool(bool);
+		serialize_i8(i8);
+		serialize_i16(i16);
+		serialize_i32(i32);
+		serialize_i64(i64);
+		serialize_i128(i128);
+		serialize_u8(u8);
+		serialize_u16(u16);
+		serialize_u32(u32);
+		serialize_u64(u64);
+		serialize_u128(u128);
+		serialize_f32(f32);
+		serialize_f64(f64);
+		serialize_char(char);
+		serialize_str(&str);
+		serialize_bytes(&[u8]);
+	}
+
+	fn serialize_unit(self) -> Result<(), Error> {
+		self.property().serialize_unit()
+	}
+
+	fn serialize_unit_struct(self, name: &'static str) -> Result<(), Error> {
+		self.property().serialize_unit_struct(name)
+	}
+
+	fn serialize_unit_variant(self, name: &'static str, index: u32, variant: &'static str) -> Result<(), Error> {
+		self.property().serialize_unit_variant(name, index, variant)
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_none(self) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<PropertySerializer<'a>, Error> {
+		ser::Serializer::serialize_seq(self.property(), len)
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<PropertySerializer<'a>, Error> {
+		ser::Serializer::serialize_tuple(self.property(), len)
+	}
+
+	fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<PropertySerializer<'a>, Error> {
+		ser::Serializer::serialize_tuple_struct(self.property(), name, len)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<(), Error> {
+		Err(unsupported("newtype variant"))
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<SectionSerializer<'a>, Error> {
+		self.section()
+	}
+
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<SectionSerializer<'a>, Error> {
+		self.section()
+	}
+
+	unsupported! {
+		serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+		serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+	}
+}
+
+//----------------------------------------------------------------
+// Document serializer
+
+struct DocumentSerializer;
+
+struct DocumentWriter {
+	globals: String,
+	sections: String,
+	key: String,
+}
+
+impl DocumentWriter {
+	fn finish(self) -> String {
+		let mut result = self.globals;
+		if !result.is_empty() && !self.sections.is_empty() {
+			result.push('\n');
+		}
+		result.push_str(&self.sections);
+		result
+	}
+}
+
+impl ser::SerializeStruct for DocumentWriter {
+	type Ok = String;
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+		value.serialize(FieldSerializer { document: self, key })
+	}
+
+	fn end(self) -> Result<String, Error> {
+		Ok(self.finish())
+	}
+}
+
+impl ser::SerializeMap for DocumentWriter {
+	type Ok = String;
+	type Error = Error;
+
+	fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+		self.key = key.serialize(ScalarSerializer)?;
+		Ok(())
+	}
+
+	fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		let key = core::mem::take(&mut self.key);
+		value.serialize(FieldSerializer { document: self, key: &key })
+	}
+
+	fn end(self) -> Result<String, Error> {
+		Ok(self.finish())
+	}
+}
+
+impl ser::Serializer for DocumentSerializer {
+	type Ok = String;
+	type Error = Error;
+	type SerializeSeq = Impossible<String, Error>;
+	type SerializeTuple = Impossible<String, Error>;
+	type SerializeTupleStruct = Impossible<String, Error>;
+	type SerializeTupleVariant = Impossible<String, Error>;
+	type SerializeMap = DocumentWriter;
+	type SerializeStruct = DocumentWriter;
+	type SerializeStructVariant = Impossible<String, Error>;
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<DocumentWriter, Error> {
+		Ok(DocumentWriter { globals: String::new(), sections: String::new(), key: String::new() })
+	}
+
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<DocumentWriter, Error> {
+		self.serialize_map(Some(len))
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<String, Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<String, Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<String, Error> {
+		Err(unsupported("top-level newtype variant"))
+	}
+
+	unsupported! {
+		serialize_bool(v: bool) -> String;
+		serialize_i8(v: i8) -> String;
+		serialize_i16(v: i16) -> String;
+		serialize_i32(v: i32) -> String;
+		serialize_i64(v: i64) -> String;
+		serialize_i128(v: i128) -> String;
+		serialize_u8(v: u8) -> String;
+		serialize_u16(v: u16) -> String;
+		serialize_u32(v: u32) -> String;
+		serialize_u64(v: u64) -> String;
+		serialize_u128(v: u128) -> String;
+		serialize_f32(v: f32) -> String;
+		serialize_f64(v: f64) -> String;
+		serialize_char(v: char) -> String;
+		serialize_str(v: &str) -> String;
+		serialize_bytes(v: &[u8]) -> String;
+		serialize_none() -> String;
+		serialize_unit() -> String;
+		serialize_unit_struct(name: &'static str) -> String;
+		serialize_unit_variant(name: &'static str, index: u32, variant: &'static str) -> String;
+		serialize_seq(len: Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(len: usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+		serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+	}
+}
@@ -0,0 +1,162 @@
+use core::str;
+use alloc::vec::Vec;
+use crate::{ByteItem, BytesParser, Item, ParseError, Parser};
+
+/// Ini streaming parser fed with chunks of input.
+///
+/// Parses documents as they arrive, eg. from a socket or a growing file.
+/// Only complete lines are parsed, a partial trailing line is buffered until more input is fed or [`finish`](StreamParser::finish) is called.
+///
+/// Items borrow the internal buffer, the consumed input is discarded on the next [`feed`](StreamParser::feed).
+/// The input is validated as UTF-8 one line at a time.
+///
+/// ```
+/// use ini_core as ini;
+///
+/// let mut stream = ini::StreamParser::new(ini::Parser::new("").auto_trim(true));
+/// stream.feed(b"[Sect");
+/// assert_eq!(stream.next(), None);
+///
+/// stream.feed(b"ion]\nKey = Val");
+/// assert_eq!(stream.next(), Some(Ok(ini::Item::SectionEnd)));
+/// assert_eq!(stream.next(), Some(Ok(ini::Item::Section("Section"))));
+/// assert_eq!(stream.next(), None);
+///
+/// stream.feed(b"ue");
+/// stream.finish();
+/// assert_eq!(stream.next(), Some(Ok(ini::Item::Property("Key", Some("Value")))));
+/// assert_eq!(stream.next(), Some(Ok(ini::Item::SectionEnd)));
+/// assert_eq!(stream.next(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct StreamParser {
+	options: BytesParser<'static>,
+	buffer: Vec<u8>,
+	pos: usize,
+	complete: usize,
+	finished: bool,
+	skip_bom: bool,
+}
+
+impl StreamParser {
+	/// Constructs a new `StreamParser` instance.
+	///
+	/// Uses the options of the given parser, its input is ignored.
+	/// [`Parser::skip_bom`] only applies to the input of the parser, use [`skip_bom`](StreamParser::skip_bom) instead.
+	#[inline]
+	pub fn new(options: Parser<'static>) -> StreamParser {
+		StreamParser { options: options.bytes, buffer: Vec::new(), pos: 0, complete: 0, finished: false, skip_bom: false }
+	}
+
+	/// Sets skipping a leading UTF-8 byte order mark.
+	///
+	/// The byte order mark is skipped if the fed input starts with it, it may be split over multiple chunks.
+	/// This has no effect after input has been fed.
+	///
+	/// The default is `false`.
+	///
+	/// ```
+	/// let mut stream = ini_core::StreamParser::default().skip_bom(true);
+	/// stream.feed(b"\xEF\xBB");
+	/// stream.feed(b"\xBFKey=Value\n");
+	/// assert_eq!(stream.next(), Some(Ok(ini_core::Item::Property("Key", Some("Value")))));
+	/// ```
+	#[must_use]
+	#[inline]
+	pub fn skip_bom(self, skip_bom: bool) -> StreamParser {
+		let skip_bom = skip_bom && self.buffer.is_empty() && self.pos == 0;
+		StreamParser { skip_bom, ..self }
+	}
+
+	/// Appends a chunk of input.
+	///
+	/// Chunks may be split anywhere, including in the middle of a line or UTF-8 character.
+	pub fn feed(&mut self, bytes: &[u8]) {
+		debug_assert!(!self.finished, "feed after finish");
+		self.buffer.drain(..self.pos);
+		self.pos = 0;
+		self.buffer.extend_from_slice(bytes);
+		if self.skip_bom {
+			const BOM: &[u8] = b"\xEF\xBB\xBF";
+			// Wait for the rest of a byte order mark split over chunks
+			if self.buffer.len() < BOM.len() && BOM.starts_with(&self.buffer) {
+				return;
+			}
+			if self.buffer.starts_with(BOM) {
+				self.buffer.drain(..BOM.len());
+			}
+			self.skip_bom = false;
+		}
+		self.complete = self.options.complete_len(&self.buffer);
+	}
+
+	// Feeds the buffered input of the reader, finishes at the end of the input
+	#[cfg(feature = "std")]
+	pub(crate) fn read_from<R: std::io::BufRead>(&mut self, reader: &mut R) -> std::io::Result<()> {
+		let bytes = reader.fill_buf()?;
+		if bytes.is_empty() {
+			self.finish();
+			return Ok(());
+		}
+		let n = bytes.len();
+		self.feed(bytes);
+		reader.consume(n);
+		Ok(())
+	}
+
+	/// Marks the end of the input.
+	///
+	/// The buffered partial line is parsed and the final [`Item::SectionEnd`] is returned.
+	#[inline]
+	pub fn finish(&mut self) {
+		self.finished = true;
+	}
+
+	/// Returns if [`finish`](StreamParser::finish) was called.
+	#[inline]
+	pub const fn is_finished(&self) -> bool {
+		self.finished
+	}
+
+	/// Returns the next item.
+	///
+	/// Returns `None` if more input is needed, or at the end of the document after [`finish`](StreamParser::finish).
+	/// Lines with invalid UTF-8 are skipped and return an error instead.
+	#[allow(clippy::should_implement_trait)]
+	pub fn next(&mut self) -> Option<Result<Item<'_>, str::Utf8Error>> {
+		let end = if self.finished { self.buffer.len() } else { self.complete };
+		let rest = &self.buffer[self.pos..end];
+		let (item, n) = self.options.parse_stream(rest, self.finished)?;
+		self.pos += n;
+		// An error which stops parsing consumes the rest of the input, only its line is returned
+		let line = match item {
+			ByteItem::Error(error) if self.options.error().is_some() => error,
+			_ => &rest[..n],
+		};
+		if let Err(err) = str::from_utf8(line) {
+			return Some(Err(err));
+		}
+		Some(Ok(Item::from_bytes(item)))
+	}
+
+	/// Returns the number of lines consumed so far.
+	#[inline]
+	pub const fn line(&self) -> u32 {
+		self.options.line()
+	}
+
+	/// Returns the error which stopped parsing in strict mode.
+	///
+	/// See [`Parser::strict`].
+	#[inline]
+	pub const fn error(&self) -> Option<ParseError> {
+		self.options.error()
+	}
+}
+
+impl Default for StreamParser {
+	#[inline]
+	fn default() -> StreamParser {
+		StreamParser::new(Parser::new(""))
+	}
+}
@@ -4,13 +4,18 @@ use crate::*;
 fn check(s: &str, expected: &[Item]) {
 	let value: Vec<_> = Parser::new(s).collect();
 	assert_eq!(value, expected);
+	let value: Vec<_> = BytesParser::new(s.as_bytes()).map(Item::from_bytes).collect();
+	assert_eq!(value, expected);
+	let value: Vec<_> = ConstParser::<b';'>::new(s).collect();
+	assert_eq!(value, expected);
 }
 
 #[track_caller]
-fn check_err(s: &str, line: usize) {
+fn check_err(s: &str, line: usize, kind: ErrorKind) {
 	for (index, item) in Parser::new(s).enumerate() {
 		if index == line {
 			assert!(matches!(item, Item::Error(_)));
+			assert_eq!(item.error_kind(), Some(kind));
 		}
 	}
 }
@@ -49,16 +54,16 @@ fn test_empty_strings() {
 
 #[test]
 fn test_syntax_errors() {
-	check_err("[foo] ", 1);
-	check_err("[foo] \r", 1);
-	check_err("[foo] \n", 1);
-	check_err("[foo", 1);
-	check_err("[foo\r", 1);
-	check_err("[foo\n", 1);
-	check_err("[", 1);
-	check_err("[\r", 1);
-	check_err("[\n", 1);
-	check_err("[foo]\n[", 3);
+	check_err("[foo] ", 1, ErrorKind::TrailingAfterSection);
+	check_err("[foo] \r", 1, ErrorKind::TrailingAfterSection);
+	check_err("[foo] \n", 1, ErrorKind::TrailingAfterSection);
+	check_err("[foo", 1, ErrorKind::UnterminatedSection);
+	check_err("[foo\r", 1, ErrorKind::UnterminatedSection);
+	check_err("[foo\n", 1, ErrorKind::UnterminatedSection);
+	check_err("[", 1, ErrorKind::UnterminatedSection);
+	check_err("[\r", 1, ErrorKind::UnterminatedSection);
+	check_err("[\n", 1, ErrorKind::UnterminatedSection);
+	check_err("[foo]\n[", 3, ErrorKind::UnterminatedSection);
 }
 
 #[test]
@@ -81,3 +86,2619 @@ fn test_terminates() {
 	for _ in Parser::new("[") {}
 	for _ in Parser::new("[] ") {}
 }
+
+#[test]
+fn test_bytes() {
+	let value: Vec<_> = BytesParser::new(b"[S\xff]\r\nk\xfe=\x80v\n;\xc0").collect();
+	assert_eq!(value, [
+		ByteItem::SectionEnd,
+		ByteItem::Section(b"S\xff"),
+		ByteItem::Property(b"k\xfe", Some(b"\x80v")),
+		ByteItem::Comment(b"\xc0"),
+		ByteItem::SectionEnd,
+	]);
+}
+
+#[test]
+fn test_comment_chars() {
+	let value: Vec<_> = Parser::new(";a\n#b\n!c\nd").comment_chars(b";#!").collect();
+	assert_eq!(value, [Item::Comment("a"), Item::Comment("b"), Item::Comment("c"), Item::Property("d", None), Item::SectionEnd]);
+
+	// High bit is masked off
+	let value: Vec<_> = Parser::new(";a").comment_chars(b"\xbb").collect();
+	assert_eq!(value, [Item::Comment("a"), Item::SectionEnd]);
+	let value: Vec<_> = BytesParser::new(b"\xbba").comment_chars(b";").collect();
+	assert_eq!(value, [ByteItem::Property(b"\xbba", None), ByteItem::SectionEnd]);
+
+	// Empty set disables comments
+	let value: Vec<_> = Parser::new(";a=b\n[S]").comment_chars(b"").collect();
+	assert_eq!(value, [Item::Property(";a", Some("b")), Item::SectionEnd, Item::Section("S"), Item::SectionEnd]);
+}
+
+#[test]
+fn test_offset() {
+	let s = "[A]\r\nk=v\n;c\r\n\nx";
+	let mut parser = Parser::new(s);
+	let mut spans = Vec::new();
+	loop {
+		let start = parser.offset();
+		let Some(item) = parser.next() else { break };
+		spans.push((item, &s[start..parser.offset()]));
+	}
+	assert_eq!(spans, [
+		(Item::SectionEnd, ""),
+		(Item::Section("A"), "[A]\r\n"),
+		(Item::Property("k", Some("v")), "k=v\n"),
+		(Item::Comment("c"), ";c\r\n"),
+		(Item::Blank, "\n"),
+		(Item::Property("x", None), "x"),
+		(Item::SectionEnd, ""),
+	]);
+}
+
+#[test]
+fn test_spans() {
+	let s = "[A]\r\nk = v\r;c\n\n[B\r\n";
+	let mut parser = Parser::new(s).auto_trim(true);
+	let mut spans = Vec::new();
+	while let Some((item, span)) = parser.next_spanned() {
+		spans.push((item, &s[span]));
+	}
+	assert_eq!(spans, [
+		(Item::SectionEnd, ""),
+		(Item::Section("A"), "[A]"),
+		(Item::Property("k", Some("v")), "k = v"),
+		(Item::Comment("c"), ";c"),
+		(Item::Blank, ""),
+		(Item::SectionEnd, ""),
+		(Item::Error("[B"), "[B"),
+		(Item::SectionEnd, ""),
+	]);
+}
+
+#[test]
+fn test_separator_char() {
+	let value: Vec<_> = Parser::new("a:b\nc=d:e\nf").separator_char(b':').collect();
+	assert_eq!(value, [
+		Item::Property("a", Some("b")),
+		Item::Property("c=d", Some("e")),
+		Item::Property("f", None),
+		Item::SectionEnd,
+	]);
+}
+
+#[test]
+fn test_separator_whitespace() {
+	let value: Vec<_> = Parser::new("a b\n  c \t d e \nf\ng \n \n=x y").separator(Separator::Whitespace).collect();
+	assert_eq!(value, [
+		Item::Property("a", Some("b")),
+		Item::Property("  c", Some("d e ")),
+		Item::Property("f", None),
+		Item::Property("g", Some("")),
+		Item::Property(" ", None),
+		Item::Property("=x", Some("y")),
+		Item::SectionEnd,
+	]);
+
+	let mut parser = Parser::new(" k  v ;c\r\nk=v").separator(Separator::Whitespace).inline_comments(true).auto_trim(true);
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("v"))));
+	assert_eq!(parser.inline_comment(), Some("c"));
+	assert_eq!(parser.next(), Some(Item::Property("k=v", None)));
+
+	// Setting the separator character switches back
+	let mut parser = Parser::new("k v=w").separator(Separator::Whitespace).separator_char(b'=');
+	assert_eq!(parser.next(), Some(Item::Property("k v", Some("w"))));
+}
+
+#[test]
+fn test_section_delimiters() {
+	let document = "<S>\n[k]=v\n<T\n<U> x\n<>\n<\n;<c>\n<V>";
+	let value: Vec<_> = Parser::new(document).section_delimiters(b'<', b'>').collect();
+	assert_eq!(value, [
+		Item::SectionEnd,
+		Item::Section("S"),
+		Item::Property("[k]", Some("v")),
+		Item::SectionEnd,
+		Item::Error("<T"),
+		Item::SectionEnd,
+		Item::Error("<U> x"),
+		Item::SectionEnd,
+		Item::Section(""),
+		Item::SectionEnd,
+		Item::Error("<"),
+		Item::Comment("<c>"),
+		Item::SectionEnd,
+		Item::Section("V"),
+		Item::SectionEnd,
+	]);
+	for (line, kind) in [("<T", ErrorKind::UnterminatedSection), ("<U> x", ErrorKind::TrailingAfterSection)] {
+		let mut parser = Parser::new(line).section_delimiters(b'<', b'>').strict(true);
+		parser.by_ref().for_each(drop);
+		assert_eq!(parser.error().map(|error| error.kind()), Some(kind));
+	}
+
+	// The same character opening and closing
+	let value: Vec<_> = Parser::new("|S|\n|\n||").section_delimiters(b'|', b'|').collect();
+	assert_eq!(value, [Item::SectionEnd, Item::Section("S"), Item::SectionEnd, Item::Error("|"), Item::SectionEnd, Item::Section(""), Item::SectionEnd]);
+
+	let mut parser = BytesParser::new(b"k=1\n{S}\nk=2\n{T}\nk=3").section_delimiters(b'{', b'}');
+	assert_eq!(parser.count_items(), parser.clone().count());
+	assert!(parser.skip_to_section(b"S"));
+	parser.skip_section();
+	assert_eq!(parser.nth(1), Some(ByteItem::Section(b"T")));
+}
+
+#[test]
+fn test_comment_prefix() {
+	let mut parser = Parser::new("k=v\n# a\n[S]\n; b").comment_chars(b";#").auto_trim(true);
+	assert_eq!(parser.comment_prefix(), None);
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("v"))));
+	assert_eq!(parser.comment_prefix(), None);
+	assert_eq!(parser.next(), Some(Item::Comment("a")));
+	assert_eq!(parser.comment_prefix(), Some(b'#'));
+	let pos = parser.position();
+	assert_eq!(parser.nth(2), Some(Item::Comment("b")));
+	assert_eq!(parser.comment_prefix(), Some(b';'));
+	parser.seek(pos);
+	assert_eq!(parser.comment_prefix(), Some(b'#'));
+
+	let (item, parser) = Parser::new("#x").comment_chars(b";#").next_const();
+	assert_eq!((item, parser.comment_prefix()), (Some(Item::Comment("x")), Some(b'#')));
+
+	assert_eq!(Item::Comment("x").display_line(Some(Newline::CrLf)).comment_char(b'#').to_string(), "#x\r\n");
+	assert_eq!(Item::Property("k", Some("v")).display_line(None).comment_char(b'#').to_string(), "k=v");
+}
+
+#[test]
+fn test_display_with_comment_char() {
+	let document = "!a\n[S]\nk=v\n\n!b\n";
+	let output: String = Parser::new(document).comment_char(b'!').map(|item| item.display_with_comment_char(b'!').to_string()).collect();
+	assert_eq!(output, document);
+	assert_eq!(Item::Comment("c").display_with_comment_char(b'#').to_string(), Item::Comment("c").to_string().replace(';', "#"));
+	assert_eq!(Item::SectionEnd.display_with_comment_char(b'#').to_string(), "");
+}
+
+#[test]
+fn test_inline_comments() {
+	let mut parser = Parser::new("a = b ; c\nd=e;f\ng=\t#h\ni=j").comment_chars(b";#").inline_comments(true).auto_trim(true);
+	assert_eq!(parser.next(), Some(Item::Property("a", Some("b"))));
+	assert_eq!(parser.inline_comment(), Some("c"));
+	assert_eq!(parser.next(), Some(Item::Property("d", Some("e;f"))));
+	assert_eq!(parser.inline_comment(), None);
+	assert_eq!(parser.next(), Some(Item::Property("g", Some(""))));
+	assert_eq!(parser.inline_comment(), Some("h"));
+	assert_eq!(parser.next(), Some(Item::Property("i", Some("j"))));
+	assert_eq!(parser.inline_comment(), None);
+
+	// Disabled by default
+	check("a=b ;c", &[Item::Property("a", Some("b ;c")), Item::SectionEnd]);
+}
+
+#[test]
+fn test_line_continuation() {
+	let mut parser = Parser::new("a=b\\\r\nc\\\rd\ne=f\\\\\ng\\").line_continuation(true);
+	assert_eq!(parser.next(), Some(Item::Property("a", Some("b\\\r\nc\\\rd"))));
+	assert_eq!(parser.line(), 3);
+	assert_eq!(parser.next(), Some(Item::Property("e", Some("f\\\\"))));
+	assert_eq!(parser.line(), 4);
+	assert_eq!(parser.next(), Some(Item::Property("g\\", None)));
+	assert_eq!(parser.next(), Some(Item::SectionEnd));
+
+	// Disabled by default
+	check("a=b\\\nc", &[Item::Property("a", Some("b\\")), Item::Property("c", None), Item::SectionEnd]);
+}
+
+#[test]
+fn test_folding() {
+	let mut parser = Parser::new("a=b\r\n c\n\td\ne=f\n \n g\n[S]\n h\n;i\n j\nk\n l").folding(true);
+	assert_eq!(parser.next(), Some(Item::Property("a", Some("b\r\n c\n\td"))));
+	assert_eq!(parser.line(), 3);
+	assert_eq!(parser.next(), Some(Item::Property("e", Some("f"))));
+	assert_eq!(parser.next(), Some(Item::Property(" ", None)));
+	assert_eq!(parser.next(), Some(Item::Property(" g", None)));
+	assert_eq!(parser.next(), Some(Item::SectionEnd));
+	assert_eq!(parser.next(), Some(Item::Section("S")));
+	assert_eq!(parser.next(), Some(Item::Property(" h", None)));
+	assert_eq!(parser.next(), Some(Item::Comment("i")));
+	assert_eq!(parser.next(), Some(Item::Property(" j", None)));
+	assert_eq!(parser.next(), Some(Item::Property("k", None)));
+	assert_eq!(parser.next(), Some(Item::Property(" l", None)));
+	assert_eq!(parser.next(), Some(Item::SectionEnd));
+
+	// Combined with line continuation
+	let mut parser = Parser::new("a=b\\\nc\n d\\\ne\nf=g").folding(true).line_continuation(true);
+	assert_eq!(parser.next(), Some(Item::Property("a", Some("b\\\nc\n d\\\ne"))));
+	assert_eq!(parser.line(), 4);
+	assert_eq!(parser.next(), Some(Item::Property("f", Some("g"))));
+
+	// The folded lines are not looked at in isolation
+	let parser = Parser::new("a=1\n [S]\nb=2").folding(true);
+	assert_eq!(parser.clone().count_items(), 3);
+	assert_eq!(parser.last_property(None, "b"), Some("2"));
+
+	// Disabled by default
+	check("a=b\n c", &[Item::Property("a", Some("b")), Item::Property(" c", None), Item::SectionEnd]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_join_folded() {
+	use alloc::borrow::Cow;
+	assert!(matches!(join_folded(" abc ", " "), Cow::Borrowed(" abc ")));
+	assert_eq!(join_folded("a\r\n b\r \t c\n", ", "), "a, b, c, ");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_join_continuations() {
+	use alloc::borrow::Cow;
+	assert!(matches!(join_continuations("abc\\"), Cow::Borrowed("abc\\")));
+	assert_eq!(join_continuations("b\\\r\nc\\\rd\\\n"), "bcd");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_escape() {
+	use alloc::borrow::Cow;
+	assert!(matches!(escape("plain value"), Cow::Borrowed(_)));
+	assert!(matches!(unescape("plain value"), Cow::Borrowed(_)));
+
+	for s in ["", "a=b", "\\;#=", "\r\n\t", "\x00\x7f", "\\n", "caf\u{e9} \u{1F600}", "trailing\\"] {
+		let escaped = escape(s);
+		assert!(!escaped.contains(['\r', '\n']));
+		assert_eq!(unescape(&escaped), s);
+	}
+
+	assert_eq!(unescape(r"\x4"), r"\x4");
+	assert_eq!(unescape(r"\x+1"), r"\x+1");
+	assert_eq!(unescape(r"\u{110000}"), r"\u{110000}");
+	assert_eq!(unescape(r"\u{1234567}"), r"\u{1234567}");
+	assert_eq!(unescape(r"\u{41"), r"\u{41");
+	assert_eq!(unescape(r"\q\"), r"\q\");
+}
+
+#[test]
+fn test_display_escaped() {
+	let items = [
+		Item::Error("[a\nb"),
+		Item::Section("a]\r\n[b"),
+		Item::SectionEnd,
+		Item::Property("k=;", Some("a\nb")),
+		Item::Property("#k", None),
+		Item::Comment("a\nb\\"),
+		Item::Blank,
+	];
+	let mut s = String::new();
+	for item in &items {
+		s.push_str(&item.display_escaped().to_string());
+	}
+	assert_eq!(s, "[a\\nb\n[a]\\r\\n[b]\nk\\=\\;=a\\nb\n\\#k\n;a\\nb\\\\\n\n");
+	// Every item occupies a single line
+	assert_eq!(Parser::new(&s).filter(|item| *item != Item::SectionEnd).count(), items.len() - 1);
+
+	// Keys which don't parse back as the same property
+	let s = Item::Property("a=b", Some("c")).display_escaped().to_string();
+	assert_eq!(Parser::new(&s).next(), Some(Item::Property("a\\", Some("b=c"))));
+	let s = Item::Property("[a]", None).display_escaped().to_string();
+	assert_eq!(Parser::new(&s).nth(1), Some(Item::Section("a")));
+	let s = Item::Property("", None).display_escaped().to_string();
+	assert_eq!(Parser::new(&s).next(), Some(Item::Blank));
+}
+
+#[test]
+fn test_write_checked() {
+	let mut s = String::new();
+	assert_eq!(Item::Error("[a\rb").write_checked(&mut s), Err(WriteError::Newline));
+	assert_eq!(Item::Section("a\nb").write_checked(&mut s), Err(WriteError::Newline));
+	assert_eq!(Item::Section("a]b").write_checked(&mut s), Err(WriteError::SectionBracket));
+	assert_eq!(Item::Property("a\nb", None).write_checked(&mut s), Err(WriteError::Newline));
+	assert_eq!(Item::Property("a", Some("b\r")).write_checked(&mut s), Err(WriteError::Newline));
+	assert_eq!(Item::Property("a=b", Some("c")).write_checked(&mut s), Err(WriteError::KeySeparator));
+	assert_eq!(Item::Comment("\n").write_checked(&mut s), Err(WriteError::Newline));
+	assert_eq!(s, "");
+
+	assert_eq!(Item::Section("S").write_checked(&mut s), Ok(()));
+	assert_eq!(Item::SectionEnd.write_checked(&mut s), Ok(()));
+	assert_eq!(Item::Property("a", Some("b=c")).write_checked(&mut s), Ok(()));
+	assert_eq!(Item::Comment("c").write_checked(&mut s), Ok(()));
+	assert_eq!(Item::Blank.write_checked(&mut s), Ok(()));
+	assert_eq!(s, "[S]\na=b=c\n;c\n\n");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_de() {
+	use std::collections::BTreeMap;
+
+	#[derive(Debug, PartialEq, serde::Deserialize)]
+	#[serde(rename_all = "lowercase")]
+	enum Mode { Fast, Slow }
+
+	#[derive(Debug, PartialEq, serde::Deserialize)]
+	struct Section<'a> {
+		name: &'a str,
+		flag: bool,
+		mode: Mode,
+		items: Vec<u32>,
+		empty: String,
+		none: Option<i32>,
+	}
+
+	#[derive(Debug, PartialEq, serde::Deserialize)]
+	struct Document<'a> {
+		global: f32,
+		#[serde(borrow)]
+		section: Section<'a>,
+		map: BTreeMap<String, String>,
+	}
+
+	let document: Document = de::from_str("\
+global = 1.5
+[section]
+; comment
+name = value
+flag = true
+mode = slow
+items = 1
+items = 2
+empty
+[map]
+a = b
+[section]
+items = 3
+").unwrap();
+	assert_eq!(document, Document {
+		global: 1.5,
+		section: Section { name: "value", flag: true, mode: Mode::Slow, items: vec![1, 2, 3], empty: String::new(), none: None },
+		map: BTreeMap::from([("a".into(), "b".into())]),
+	});
+
+	#[derive(Debug, serde::Deserialize)]
+	#[allow(dead_code)]
+	struct Port { port: u16 }
+	#[derive(Debug, serde::Deserialize)]
+	#[allow(dead_code)]
+	struct Server { server: Port }
+
+	let err = de::from_str::<Server>("[server]\nport = big\n").unwrap_err();
+	assert_eq!(err.line(), Some(2));
+	let err = de::from_str::<Server>("\n[server]\nhost = x\n").unwrap_err();
+	assert_eq!(err.line(), Some(2));
+	let err = de::from_str::<Server>("[server\n").unwrap_err();
+	assert_eq!(err.line(), Some(1));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_ser() {
+	use std::collections::BTreeMap;
+
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	enum Mode { Fast, Slow }
+
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	struct Section {
+		name: String,
+		mode: Mode,
+		items: Vec<u32>,
+		none: Option<i32>,
+	}
+
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	struct Document {
+		section: Section,
+		global: f32,
+		map: BTreeMap<String, bool>,
+		list: Vec<String>,
+	}
+
+	let document = Document {
+		section: Section { name: "value".into(), mode: Mode::Fast, items: vec![1, 2], none: None },
+		global: 1.5,
+		map: BTreeMap::from([("a".into(), true), ("b".into(), false)]),
+		list: vec!["x".into(), "y".into()],
+	};
+	let s = ser::to_string(&document).unwrap();
+	assert_eq!(s, "\
+global=1.5
+list=x
+list=y
+
+[section]
+name=value
+mode=Fast
+items=1
+items=2
+
+[map]
+a=true
+b=false
+");
+	assert_eq!(de::from_str::<Document>(&s).unwrap(), document);
+
+	// Values containing newlines are rejected
+	let document = Section { name: "a\nb".into(), mode: Mode::Slow, items: vec![], none: Some(1) };
+	assert!(ser::to_string(&BTreeMap::from([("s", document)])).is_err());
+	// Nested sections are rejected
+	assert!(ser::to_string(&BTreeMap::from([("a", BTreeMap::from([("b", BTreeMap::from([("c", 1)]))]))])).is_err());
+	// Top-level must be a struct or map
+	assert!(ser::to_string(&1).is_err());
+	// Keys which parse back as sections or comments are rejected
+	assert!(ser::to_string(&BTreeMap::from([("[x", 1)])).is_err());
+	assert!(ser::to_string(&BTreeMap::from([("s", BTreeMap::from([(";x", 1)]))])).is_err());
+	assert_eq!(ser::to_string(&BTreeMap::from([("", 1)])).unwrap(), "=1\n");
+}
+
+#[test]
+fn test_writer() {
+	let mut writer = Writer::new(String::new()).strict(true);
+	assert_eq!(writer.property("a", Some("b")), Err(WriteError::NoSection));
+	assert_eq!(writer.comment("comment"), Ok(()));
+	assert_eq!(writer.section("a]b"), Err(WriteError::SectionBracket));
+	assert_eq!(writer.section("S"), Ok(()));
+	assert_eq!(writer.property("a", Some("b\nc")), Err(WriteError::Newline));
+	assert_eq!(writer.property("a=b", None), Err(WriteError::KeySeparator));
+	assert_eq!(writer.property("[a", None), Err(WriteError::InvalidKey));
+	assert_eq!(writer.property(";a", None), Err(WriteError::InvalidKey));
+	assert_eq!(writer.property("", None), Err(WriteError::InvalidKey));
+	assert_eq!(writer.property("", Some("")), Ok(()));
+	assert_eq!(writer.property("a", None), Ok(()));
+	assert_eq!(writer.blank(), Ok(()));
+	assert_eq!(writer.finish(), ";comment\n[S]\n=\na\n\n");
+
+	let mut writer = Writer::new(String::new()).escape(true);
+	assert_eq!(writer.property("k=", Some("\n")), Err(WriteError::KeySeparator));
+	let items = [Item::Property("k", Some("a\nb=")), Item::Comment("x\r"), Item::SectionEnd, Item::Section("s\n")];
+	for item in items {
+		assert_eq!(writer.item(item), Ok(()));
+	}
+	let s = writer.finish();
+	assert_eq!(s, "k=a\\nb\\=\n;x\\r\n[s\\n]\n");
+	let parsed: Vec<_> = Parser::new(&s).collect();
+	assert_eq!(parsed, [
+		Item::Property("k", Some("a\\nb\\=")),
+		Item::Comment("x\\r"),
+		Item::SectionEnd,
+		Item::Section("s\\n"),
+		Item::SectionEnd,
+	]);
+}
+
+#[test]
+fn test_writer_newline() {
+	let document = "; comment\r\n[Section]\r\nKey=Value\r\n\r\nAction\r\n";
+	let mut writer = Writer::new(String::new()).newline(Newline::CrLf);
+	for item in Parser::new(document) {
+		writer.item(item).unwrap();
+	}
+	assert_eq!(writer.finish(), document);
+
+	let document = "[A]\rk=v\r";
+	let mut writer = Writer::new(String::new()).newline(Newline::Cr);
+	for item in Parser::new(document) {
+		writer.item(item).unwrap();
+	}
+	assert_eq!(writer.finish(), document);
+}
+
+#[test]
+fn test_sections() {
+	let document = "[A]\nk=v\n\n[B\nx\n[C]\n;c\n";
+	let mut groups = Vec::new();
+	for (section, items) in Parser::new(document).sections() {
+		groups.push((section, items.collect::<Vec<_>>()));
+	}
+	assert_eq!(groups, [
+		(None, vec![]),
+		(Some("A"), vec![Item::Property("k", Some("v")), Item::Blank]),
+		(None, vec![Item::Error("[B"), Item::Property("x", None)]),
+		(Some("C"), vec![Item::Comment("c")]),
+	]);
+
+	// Groups can be skipped without consuming their items
+	let names: Vec<_> = Parser::new(document).sections().map(|(section, _)| section).collect();
+	assert_eq!(names, [None, Some("A"), None, Some("C")]);
+}
+
+#[test]
+fn test_nested_brackets() {
+	let mut parser = Parser::new("[section[32]]\n[a][b]\n[[x]]\n[]]\n[a]b\n[a]]b");
+	assert_eq!(parser.nth(1), Some(Item::Section("section[32]")));
+	assert_eq!(parser.nth(1), Some(Item::Section("a][b")));
+	assert_eq!(parser.nth(1), Some(Item::Section("[x]")));
+	assert_eq!(parser.nth(1), Some(Item::Section("]")));
+	assert_eq!(parser.nth(1), Some(Item::Error("[a]b")));
+	assert_eq!(parser.nth(1), Some(Item::Error("[a]]b")));
+	assert_eq!(parser.next(), Some(Item::SectionEnd));
+	assert_eq!(parser.next(), None);
+
+	let parser = Parser::new("[a][b]\nk=v").auto_trim(true);
+	assert_eq!(parser.clone().get(Some("a][b"), "k"), Some("v"));
+	assert_eq!(parser.clone().last_property(Some("a][b"), "k"), Some("v"));
+}
+
+#[test]
+fn test_sections_global() {
+	fn groups(parser: Parser<'_>) -> Vec<(Option<&str>, Vec<Item<'_>>)> {
+		parser.sections().map(|(section, items)| (section, items.collect())).collect()
+	}
+
+	// Entirely global document
+	assert_eq!(groups(Parser::new("a=1
+
+b
+")), [
+		(None, vec![Item::Property("a", Some("1")), Item::Blank, Item::Property("b", None)]),
+	]);
+	assert_eq!(groups(Parser::new("")), [(None, vec![])]);
+
+	// Comment followed by global properties
+	assert_eq!(groups(Parser::new(";comment
+a=1
+b=2
+[S]
+c=3")), [
+		(None, vec![Item::Comment("comment"), Item::Property("a", Some("1")), Item::Property("b", Some("2"))]),
+		(Some("S"), vec![Item::Property("c", Some("3"))]),
+	]);
+
+	// The leading section end of a document starting with a section header gives an empty global section
+	let parser = Parser::new("[S]\na=1");
+	assert_eq!(parser.clone().next(), Some(Item::SectionEnd));
+	assert_eq!(groups(parser.clone()), [(None, vec![]), (Some("S"), vec![Item::Property("a", Some("1"))])]);
+	assert_eq!(parser.clone().get(None, "a"), None);
+	assert_eq!(parser.get(Some("S"), "a"), Some("1"));
+
+	// An empty section header returning to the global section starts a group with name `None`
+	let parser = Parser::new("a=1\n[S]\nb=2\n[]\nc=3").empty_section(EmptySectionPolicy::Global);
+	assert_eq!(groups(parser), [
+		(None, vec![Item::Property("a", Some("1"))]),
+		(Some("S"), vec![Item::Property("b", Some("2"))]),
+		(None, vec![Item::Property("c", Some("3"))]),
+	]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_into_map_global() {
+	let s = |s: &str| s.to_string();
+
+	let map = Parser::new(";comment\na=1\nb\n").into_map();
+	assert_eq!(map.len(), 1);
+	assert_eq!(map[&None], [(s("a"), Some(s("1"))), (s("b"), None)]);
+
+	let map = Parser::new(";comment\na=1\n[S]\nb=2").into_map();
+	assert_eq!(map[&None], [(s("a"), Some(s("1")))]);
+	assert_eq!(map[&Some(s("S"))], [(s("b"), Some(s("2")))]);
+
+	let map = Parser::new("a=1\n[S]\nb=2\n[]\nc=3").empty_section(EmptySectionPolicy::Global).into_map();
+	assert_eq!(map[&None], [(s("a"), Some(s("1"))), (s("c"), Some(s("3")))]);
+	assert_eq!(map[&Some(s("S"))], [(s("b"), Some(s("2")))]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_into_map() {
+	use crate::DuplicateKeys;
+
+	let document = "g\n[A]\nk=1\n;c\nk=2\n[B]\nx=y\n[A]\nk=3\nz=\n[";
+	let s = |s: &str| s.to_string();
+
+	let map = Parser::new(document).into_map();
+	assert_eq!(map.len(), 3);
+	assert_eq!(map[&None], [(s("g"), None)]);
+	assert_eq!(map[&Some(s("A"))], [(s("k"), Some(s("1"))), (s("k"), Some(s("2"))), (s("k"), Some(s("3"))), (s("z"), Some(s("")))]);
+	assert_eq!(map[&Some(s("B"))], [(s("x"), Some(s("y")))]);
+
+	let map = Parser::new(document).into_map_with(DuplicateKeys::KeepFirst);
+	assert_eq!(map[&Some(s("A"))], [(s("k"), Some(s("1"))), (s("z"), Some(s("")))]);
+
+	let map = Parser::new(document).into_map_with(DuplicateKeys::KeepLast);
+	assert_eq!(map[&Some(s("A"))], [(s("k"), Some(s("3"))), (s("z"), Some(s("")))]);
+
+	assert!(Parser::new("[A]\n;c").into_map().is_empty());
+
+	// Properties after a malformed section header belong to no section
+	let map = Parser::new("[S]\na=1\n[T\nb=2").into_map();
+	assert_eq!(map.len(), 1);
+	assert_eq!(map[&Some(s("S"))], [(s("a"), Some(s("1")))]);
+}
+
+#[test]
+fn test_get() {
+	let document = "a=1\nflag\n[S]\nk=2\nflag\nk=3\n[T]\nk=4\n[S]\nx=5\n[U\nk=6";
+	let parser = Parser::new(document);
+	assert_eq!(parser.clone().get(None, "a"), Some("1"));
+	assert_eq!(parser.clone().get(None, "k"), None);
+	assert_eq!(parser.clone().get(None, "flag"), None);
+	assert_eq!(parser.clone().get(Some("S"), "k"), Some("2"));
+	assert_eq!(parser.clone().get(Some("S"), "x"), Some("5"));
+	assert_eq!(parser.clone().get(Some("T"), "k"), Some("4"));
+	assert_eq!(parser.clone().get(Some("T"), "x"), None);
+	assert_eq!(parser.clone().get(Some("U"), "k"), None);
+
+	// Properties after a malformed section header belong to no section
+	let parser = Parser::new("[S]\na=1\n[T\nb=2");
+	assert_eq!(parser.clone().get(Some("S"), "a"), Some("1"));
+	assert_eq!(parser.clone().get(Some("S"), "b"), None);
+	assert_eq!(parser.clone().get(None, "b"), None);
+
+	let parser = Parser::new("[ S ]\n k = v ");
+	assert_eq!(parser.clone().get(Some("S"), "k"), None);
+	assert_eq!(parser.auto_trim(true).get(Some("S"), "k"), Some("v"));
+}
+
+#[test]
+fn test_position() {
+	let mut parser = Parser::new("a=1\n[S]\nb=2 ;x\nc=3\n").inline_comments(true);
+	assert_eq!(parser.next(), Some(Item::Property("a", Some("1"))));
+	assert_eq!(parser.next(), Some(Item::SectionEnd));
+
+	let pos = parser.position();
+	let line = parser.line();
+	let items = [parser.next(), parser.next()];
+	assert_eq!(items, [Some(Item::Section("S")), Some(Item::Property("b", Some("2 ")))]);
+	assert_eq!(parser.inline_comment(), Some("x"));
+	let after = parser.position();
+
+	parser.seek(pos);
+	assert_eq!(parser.line(), line);
+	assert_eq!(parser.inline_comment(), None);
+	assert_eq!([parser.next(), parser.next()], items);
+	assert_eq!(parser.position(), after);
+	assert_eq!(parser.inline_comment(), Some("x"));
+
+	// Restore a position at the end of the input
+	let rest: Vec<_> = parser.by_ref().collect();
+	assert_eq!(rest, [Item::Property("c", Some("3")), Item::SectionEnd]);
+	let end = parser.position();
+	parser.seek(after);
+	assert_eq!(parser.by_ref().count(), 2);
+	parser.seek(end);
+	assert_eq!(parser.next(), None);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_collect_heapless() {
+	use crate::CapacityError;
+
+	let document = "g\n[A]\nk=1\n;c\n[B]\n[C]\nx=y\n[A]\nk=2\n[";
+	let sections = Parser::new(document).collect_heapless::<3, 2>().unwrap();
+	assert_eq!(sections.len(), 3);
+	assert_eq!(sections[0], (None, heapless::Vec::<_, 2>::from_slice(&[("g", None)]).unwrap()));
+	assert_eq!(sections[1].0, Some("A"));
+	assert_eq!(sections[1].1, [("k", Some("1")), ("k", Some("2"))]);
+	assert_eq!(sections[2].0, Some("C"));
+	assert_eq!(sections[2].1, [("x", Some("y"))]);
+
+	assert_eq!(Parser::new(document).collect_heapless::<2, 2>(), Err(CapacityError::Sections));
+	assert_eq!(Parser::new(document).collect_heapless::<3, 1>(), Err(CapacityError::Properties));
+	assert!(Parser::new("[A]\n;c").collect_heapless::<0, 0>().unwrap().is_empty());
+}
+
+#[test]
+fn test_const_parser() {
+	let document = "#a\n;b\n[S]\n k = v #c\n";
+	let expected: Vec<_> = Parser::new(document).comment_char(b'#').auto_trim(true).inline_comments(true).collect();
+	let mut parser = ConstParser::<b'#'>::new(document).auto_trim(true).inline_comments(true);
+	let mut value = Vec::new();
+	while let Some(item) = parser.next() {
+		if let Item::Property(_, Some(_)) = item {
+			assert_eq!(parser.inline_comment(), Some("c"));
+		}
+		value.push(item);
+	}
+	assert_eq!(value, expected);
+	assert_eq!(value[0], Item::Comment("a"));
+	assert_eq!(value[1], Item::Property(";b", None));
+
+	// Non-ascii comment characters are masked like the runtime setting
+	let value: Vec<_> = ConstParser::<{b';' | 0x80}>::new(";x").collect();
+	assert_eq!(value, [Item::Comment("x"), Item::SectionEnd]);
+}
+
+#[test]
+fn test_in_global_section() {
+	let mut parser = Parser::new("a=1\n;c\n[A]\nb=2\n[C]\n");
+	let mut global = Vec::new();
+	while let Some(item) = parser.next() {
+		global.push((item, parser.in_global_section()));
+	}
+	assert_eq!(global, [
+		(Item::Property("a", Some("1")), true),
+		(Item::Comment("c"), true),
+		(Item::SectionEnd, true),
+		(Item::Section("A"), false),
+		(Item::Property("b", Some("2")), false),
+		(Item::SectionEnd, false),
+		(Item::Section("C"), false),
+		(Item::SectionEnd, false),
+	]);
+
+	// Malformed section header ends the global section
+	let mut parser = Parser::new("[A\nb=2");
+	assert_eq!(parser.next(), Some(Item::SectionEnd));
+	assert!(parser.in_global_section());
+	assert_eq!(parser.next(), Some(Item::Error("[A")));
+	assert!(!parser.in_global_section());
+
+	// Seeking restores the global section
+	let mut parser = Parser::new("[A]");
+	let pos = parser.position();
+	parser.by_ref().for_each(drop);
+	assert!(!parser.in_global_section());
+	parser.seek(pos);
+	assert!(parser.in_global_section());
+}
+
+#[test]
+fn test_section_boundaries() {
+	let documents = [
+		"",
+		"a=1",
+		"[A]",
+		"[A]\n[B]\n",
+		"a=1\n[A]\n;c\n\n[B\nx\n[C]\ny=2\n",
+		"[A\n[B",
+	];
+	for document in documents {
+		let items: Vec<_> = Parser::new(document).collect();
+		assert!(items.last().unwrap().is_section_end(), "{:?}", document);
+		for (i, item) in items.iter().enumerate() {
+			assert_eq!(item.is_section_boundary(), item.is_section_start() || item.is_section_end());
+			if item.is_section_start() {
+				assert!(i > 0 && items[i - 1].is_section_end(), "{:?}", document);
+			}
+			if item.is_section_end() && i + 1 < items.len() {
+				assert!(matches!(items[i + 1], Item::Section(_) | Item::Error(_)), "{:?}", document);
+			}
+		}
+	}
+}
+
+#[test]
+fn test_accessors() {
+	let items: Vec<_> = Parser::new("[A\n[S]\nk=v\n;c\n\n").collect();
+	let kinds: Vec<_> = items.iter().map(|item| {
+		[item.is_error(), item.is_section(), item.is_section_end(), item.is_property(), item.is_comment(), item.is_blank()]
+	}).collect();
+	for (item, kind) in items.iter().zip(&kinds) {
+		assert_eq!(kind.iter().filter(|&&is| is).count(), 1, "{:?}", item);
+	}
+	assert_eq!(items.iter().filter_map(|item| item.as_error()).collect::<Vec<_>>(), ["[A"]);
+	assert_eq!(items.iter().filter_map(|item| item.as_section()).collect::<Vec<_>>(), ["S"]);
+	assert_eq!(items.iter().filter_map(|item| item.as_property()).collect::<Vec<_>>(), [("k", Some("v"))]);
+	assert_eq!(items.iter().filter_map(|item| item.as_comment()).collect::<Vec<_>>(), ["c"]);
+	assert_eq!(items.iter().filter(|item| item.is_blank()).count(), 1);
+	assert_eq!(items.iter().filter(|item| item.is_section_end()).count(), 3);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_owned_item() {
+	let document = "[A\n;c\n\n[S]\nk=v\nflag\n";
+	let items: Vec<OwnedItem> = Parser::new(document).map(OwnedItem::from).collect();
+	let expected: Vec<_> = Parser::new(document).collect();
+	assert_eq!(items, expected);
+	assert_eq!(items[5], OwnedItem::Section("S".to_string()));
+	assert_eq!(items[7], OwnedItem::Property("flag".to_string(), None));
+	for (item, expected) in items.iter().zip(&expected) {
+		assert_eq!(item.as_item(), *expected);
+		assert_eq!(item.to_string(), expected.to_string());
+	}
+	let output: String = items.iter().map(|item| item.to_string()).collect();
+	assert_eq!(output, document);
+}
+
+#[test]
+fn test_skip_bom() {
+	let document = "\u{FEFF}[S]\nk=v";
+	assert_eq!(Parser::new(document).next(), Some(Item::Property("\u{FEFF}[S]", None)));
+	assert_eq!(Parser::new(document).skip_bom(false).next(), Some(Item::Property("\u{FEFF}[S]", None)));
+
+	let mut parser = Parser::new(document).skip_bom(true);
+	assert_eq!(parser.offset(), 3);
+	assert_eq!(parser.next_spanned(), Some((Item::SectionEnd, 3..3)));
+	assert_eq!(parser.next_spanned(), Some((Item::Section("S"), 3..6)));
+
+	// Only skipped at the start of the input
+	assert_eq!(Parser::new("k=\u{FEFF}").skip_bom(true).next(), Some(Item::Property("k", Some("\u{FEFF}"))));
+	let mut parser = Parser::new("\n\u{FEFF}k");
+	parser.next();
+	assert_eq!(parser.skip_bom(true).next(), Some(Item::Property("\u{FEFF}k", None)));
+
+	let mut parser = BytesParser::new(b"\xEF\xBB\xBF;c").skip_bom(true);
+	assert_eq!(parser.next(), Some(ByteItem::Comment(b"c")));
+	assert_eq!(BytesParser::new(b"\xEF\xBB").skip_bom(true).next(), Some(ByteItem::Property(b"\xEF\xBB", None)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_skip_bom_stream() {
+	let document = b"\xEF\xBB\xBF[S]\nk=v";
+	let expected: Vec<_> = Parser::new("[S]\nk=v").map(OwnedItem::from).collect();
+	for chunk in 1..=document.len() {
+		let mut stream = StreamParser::default().skip_bom(true);
+		let mut items = Vec::new();
+		for bytes in document.chunks(chunk) {
+			stream.feed(bytes);
+			while let Some(item) = stream.next() {
+				items.push(OwnedItem::from(item.unwrap()));
+			}
+		}
+		stream.finish();
+		while let Some(item) = stream.next() {
+			items.push(OwnedItem::from(item.unwrap()));
+		}
+		assert_eq!(items, expected, "{chunk}");
+	}
+
+	let reader = std::io::BufReader::with_capacity(2, &document[..]);
+	let items: Vec<_> = IoParser::new(reader, Parser::new("")).skip_bom(true).collect::<std::io::Result<_>>().unwrap();
+	assert_eq!(items, expected);
+
+	// Not a byte order mark
+	let mut stream = StreamParser::default().skip_bom(true);
+	stream.feed(b"\xEF");
+	stream.finish();
+	assert!(stream.next().unwrap().is_err());
+	let mut stream = StreamParser::default().skip_bom(true);
+	stream.feed(b"\xEF\xBBk\n");
+	assert!(stream.next().unwrap().is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_duplicate_keys() {
+	let document = "a=1\na\n[S]\na=2\n[s]\nA=3\n[T]\nb\n[S]\nb\na\n";
+	let duplicates: Vec<_> = Parser::new(document).duplicate_keys().collect();
+	assert_eq!(duplicates, [(None, "a"), (Some("S"), "a")]);
+
+	let duplicates: Vec<_> = Parser::new(document).duplicate_keys().ignore_case(true).collect();
+	assert_eq!(duplicates, [(None, "a"), (Some("s"), "A"), (Some("S"), "a")]);
+
+	// Padding whitespace only matches with auto trimming
+	assert_eq!(Parser::new("a=1\n a =2").duplicate_keys().count(), 0);
+	assert_eq!(Parser::new("a=1\n a =2").auto_trim(true).duplicate_keys().count(), 1);
+
+	// Properties after a malformed section header belong to no section
+	assert_eq!(Parser::new("[S]\na=1\n[T\na=2\na=3").duplicate_keys().count(), 0);
+}
+
+#[test]
+fn test_strict() {
+	let errors = [
+		("[S]\nk=v\nflag\nx=y", "flag", 3, ErrorKind::MissingSeparator),
+		("[S\nk=v", "[S", 1, ErrorKind::UnterminatedSection),
+		("\r\n[S] \r\nk=v", "[S] ", 2, ErrorKind::TrailingAfterSection),
+		("[S]x]y", "[S]x]y", 1, ErrorKind::TrailingAfterSection),
+		("[", "[", 1, ErrorKind::UnterminatedSection),
+	];
+	for (document, text, line, kind) in errors {
+		let mut parser = Parser::new(document).strict(true);
+		let items: Vec<_> = parser.by_ref().collect();
+		assert_eq!(items.last(), Some(&Item::Error(text)), "{:?}", document);
+		assert_eq!(parser.next(), None);
+		let error = parser.error().unwrap();
+		assert_eq!((error.line(), error.kind()), (line, kind), "{:?}", document);
+	}
+
+	// Well formed documents are unaffected
+	let document = "a=1\n\n  \n;c\n[S]\nk=v\n";
+	let expected: Vec<_> = Parser::new(document).collect();
+	let mut parser = Parser::new(document).strict(true);
+	assert_eq!(parser.by_ref().collect::<Vec<_>>(), expected);
+	assert_eq!(parser.error(), None);
+	assert_eq!(Parser::new("  \n").strict(true).auto_trim(true).next(), Some(Item::Blank));
+
+	// Non-strict mode keeps going
+	let mut parser = Parser::new("[S\nflag");
+	assert_eq!(parser.by_ref().count(), 4);
+	assert_eq!(parser.error(), None);
+
+	// The span and newline of the error cover the malformed line only
+	let mut parser = Parser::new("[S\nk=v\nx=y").strict(true);
+	assert_eq!(parser.next_spanned(), Some((Item::SectionEnd, 0..0)));
+	assert_eq!(parser.clone().next_spanned(), Some((Item::Error("[S"), 0..2)));
+	assert_eq!(parser.next_with_newline(), Some((Item::Error("[S"), Some(Newline::Lf))));
+	let mut parser = Parser::new("a=1\nflag\r\nx=y").strict(true);
+	parser.next();
+	assert_eq!(parser.clone().next_spanned(), Some((Item::Error("flag"), 4..8)));
+	assert_eq!(parser.next_with_newline(), Some((Item::Error("flag"), Some(Newline::CrLf))));
+	assert_eq!(Parser::new("flag").strict(true).next_with_newline(), Some((Item::Error("flag"), None)));
+}
+
+#[test]
+fn test_current_section() {
+	let mut parser = Parser::new("a=1\n[A]\nb=2\n[ B ]\n[C\nc=3\n[D]").auto_trim(true);
+	let mut sections = Vec::new();
+	while let Some(item) = parser.next() {
+		sections.push((item, parser.current_section()));
+	}
+	assert_eq!(sections, [
+		(Item::Property("a", Some("1")), None),
+		(Item::SectionEnd, None),
+		(Item::Section("A"), Some("A")),
+		(Item::Property("b", Some("2")), Some("A")),
+		(Item::SectionEnd, Some("A")),
+		(Item::Section("B"), Some("B")),
+		(Item::SectionEnd, Some("B")),
+		(Item::Error("[C"), None),
+		(Item::Property("c", Some("3")), None),
+		(Item::SectionEnd, None),
+		(Item::Section("D"), Some("D")),
+		(Item::SectionEnd, Some("D")),
+	]);
+}
+
+#[test]
+fn test_line_number() {
+	let document = "a=1\r\n\r\n[S]\n;c\rk=v \\\n w\r\n[T]\nx\n";
+	let mut parser = Parser::new(document).line_continuation(true);
+	assert_eq!(parser.line_number(), 0);
+	let mut lines = Vec::new();
+	while let Some(item) = parser.next() {
+		lines.push((item, parser.line_number()));
+	}
+	assert_eq!(lines, [
+		(Item::Property("a", Some("1")), 1),
+		(Item::Blank, 2),
+		(Item::SectionEnd, 3),
+		(Item::Section("S"), 3),
+		(Item::Comment("c"), 4),
+		(Item::Property("k", Some("v \\\n w")), 5),
+		(Item::SectionEnd, 7),
+		(Item::Section("T"), 7),
+		(Item::Property("x", None), 8),
+		(Item::SectionEnd, 9),
+	]);
+	assert_eq!(parser.line(), 8);
+
+	// Errors report the same line number
+	let mut parser = Parser::new("\n[S\n").strict(true);
+	assert_eq!(parser.nth(2), Some(Item::Error("[S")));
+	assert_eq!(parser.line_number(), parser.error().unwrap().line());
+}
+
+#[test]
+fn test_column() {
+	let document = "[ S ]\r\n;c\rk\u{e9}y=\r\n\tv=w";
+	let parser = Parser::new(document);
+	let items: Vec<_> = parser.clone().collect();
+	assert_eq!(items[1], Item::Section(" S "));
+	assert_eq!(parser.column(items[1].as_section().unwrap()), Some(2));
+	assert_eq!(parser.column(items[2].as_comment().unwrap()), Some(2));
+	let (key, value) = items[3].as_property().unwrap();
+	assert_eq!((parser.column(key), parser.column(value.unwrap())), (Some(1), Some(6)));
+	let (key, value) = items[4].as_property().unwrap();
+	assert_eq!((parser.column(key), parser.column(value.unwrap())), (Some(1), Some(4)));
+
+	assert_eq!(parser.column(&document[..0]), Some(1));
+	assert_eq!(parser.column(&document[document.len()..]), Some(5));
+	assert_eq!(parser.column(&String::from("S")), None);
+
+	// The skipped byte order mark isn't counted
+	let document = "\u{FEFF}k=v\nx=y";
+	let mut parser = Parser::new(document).skip_bom(true);
+	let (key, value) = parser.next().unwrap().as_property().unwrap();
+	assert_eq!((parser.column(key), parser.column(value.unwrap())), (Some(1), Some(3)));
+	let mut parser = Parser::new(document);
+	let (key, value) = parser.next().unwrap().as_property().unwrap();
+	assert_eq!((parser.column(key), parser.column(value.unwrap())), (Some(1), Some(6)));
+}
+
+#[test]
+fn test_trim_start_end() {
+	assert_eq!(trim(" \t a b \r\n"), "a b");
+	assert_eq!(trim_start(" \t a b \r\n"), "a b \r\n");
+	assert_eq!(trim_end(" \t a b \r\n"), " \t a b");
+	assert_eq!(trim_end("\u{a0}a\u{a0}"), "\u{a0}a\u{a0}");
+
+	let document = "[ S ]\n; c \n K = V \n   \n";
+	fn items(parser: Parser) -> Vec<Item> { parser.collect() }
+	assert_eq!(items(Parser::new(document).auto_trim_start(true)), [
+		Item::SectionEnd, Item::Section("S "), Item::Comment("c "), Item::Property("K ", Some("V ")), Item::Blank, Item::SectionEnd,
+	]);
+	assert_eq!(items(Parser::new(document).auto_trim_end(true)), [
+		Item::SectionEnd, Item::Section(" S"), Item::Comment(" c"), Item::Property(" K", Some(" V")), Item::Blank, Item::SectionEnd,
+	]);
+	assert_eq!(items(Parser::new(document).auto_trim_start(true).auto_trim_end(true)), items(Parser::new(document).auto_trim(true)));
+	assert_eq!(items(Parser::new(document).auto_trim(true).auto_trim_end(false)), items(Parser::new(document).auto_trim_start(true)));
+	assert_eq!(items(Parser::new(document).auto_trim_start(true).auto_trim(false)), items(Parser::new(document)));
+}
+
+#[test]
+fn test_trim_flags() {
+	let document = "[ S ]\n; c \n K = V ; i \n";
+	fn items(parser: Parser) -> Vec<Item> { parser.collect() }
+	assert_eq!(items(Parser::new(document).trim(Trim::NONE)), items(Parser::new(document)));
+	assert_eq!(items(Parser::new(document).trim(Trim::ALL)), items(Parser::new(document).auto_trim(true)));
+	assert_eq!(items(Parser::new(document).trim(Trim::START)), items(Parser::new(document).auto_trim_start(true)));
+	assert_eq!(items(Parser::new(document).trim(Trim::END)), items(Parser::new(document).auto_trim_end(true)));
+	assert_eq!(items(Parser::new(document).trim(Trim::KEY | Trim::SECTION)), [
+		Item::SectionEnd, Item::Section("S"), Item::Comment(" c "), Item::Property("K", Some(" V ; i ")), Item::SectionEnd,
+	]);
+	assert_eq!(items(Parser::new(document).trim(Trim::VALUE | Trim::COMMENT & Trim::END)), [
+		Item::SectionEnd, Item::Section(" S "), Item::Comment(" c"), Item::Property(" K ", Some("V ; i")), Item::SectionEnd,
+	]);
+
+	let mut parser = Parser::new(document).inline_comments(true).trim(Trim::COMMENT);
+	assert_eq!(parser.nth(3), Some(Item::Property(" K ", Some(" V "))));
+	assert_eq!(parser.inline_comment(), Some("i"));
+
+	let trim = Trim::KEY | (Trim::VALUE & Trim::START);
+	assert!(trim.contains(Trim::KEY & Trim::END));
+	assert!(!trim.contains(Trim::VALUE));
+	assert!(Trim::ALL.contains(Trim::START | Trim::END));
+	assert_eq!(Trim::default(), Trim::NONE);
+}
+
+#[test]
+fn test_trim_with() {
+	let document = "[\u{a0}S\u{a0}]\n;\u{a0}c\n\u{a0}K\t=\u{a0}V \n";
+	let mut parser = Parser::new(document).auto_trim(true).trim_with(|chr| chr == '\u{a0}');
+	assert_eq!(parser.nth(1), Some(Item::Section("S")));
+	assert_eq!(parser.next(), Some(Item::Comment("c")));
+	assert_eq!(parser.next(), Some(Item::Property("K\t", Some("V "))));
+
+	let mut parser = Parser::new(document).trim(Trim::VALUE).trim_with(char::is_whitespace);
+	assert_eq!(parser.nth(3), Some(Item::Property("\u{a0}K\t", Some("V"))));
+
+	// Trimming stops at invalid utf8
+	let mut parser = BytesParser::new(b"K= \xff \n").auto_trim(true).trim_with(|chr| chr == ' ');
+	assert_eq!(parser.next(), Some(ByteItem::Property(b"K", Some(b"\xff"))));
+	let mut parser = BytesParser::new(b"K= x\xff \xff\n").auto_trim(true).trim_with(|chr| chr == ' ');
+	assert_eq!(parser.next(), Some(ByteItem::Property(b"K", Some(b"x\xff \xff"))));
+}
+
+#[test]
+fn test_trim_unicode() {
+	assert_eq!(trim_unicode("\u{3000}\t a b \u{a0}\r\n"), "a b");
+	assert_eq!(trim_unicode(" \u{200b} "), "\u{200b}");
+
+	let document = "[\u{3000}S ]\n;\u{a0}c\nK\u{2003}=\u{a0}V\u{3000}\n";
+	let mut parser = Parser::new(document).auto_trim_unicode(true);
+	assert_eq!(parser.nth(1), Some(Item::Section("S")));
+	assert_eq!(parser.next(), Some(Item::Comment("c")));
+	assert_eq!(parser.next(), Some(Item::Property("K", Some("V"))));
+
+	let mut parser = Parser::new(document).auto_trim(true);
+	assert_eq!(parser.nth(3), Some(Item::Property("K\u{2003}", Some("\u{a0}V\u{3000}"))));
+
+	// Disabling clears the predicate in either order
+	let mut parser = Parser::new(document).auto_trim_unicode(false).auto_trim(true);
+	assert_eq!(parser.nth(3), Some(Item::Property("K\u{2003}", Some("\u{a0}V\u{3000}"))));
+	let mut parser = Parser::new(document).auto_trim(true).auto_trim_unicode(false);
+	assert_eq!(parser.nth(1), Some(Item::Section("\u{3000}S ")));
+	let mut parser = Parser::new(document).trim_with(|chr| chr == '\u{3000}').auto_trim_unicode(false).auto_trim(true);
+	assert_eq!(parser.nth(1), Some(Item::Section("\u{3000}S")));
+	let mut parser = Parser::new(document).auto_trim_unicode(true).auto_trim_unicode(false);
+	assert_eq!(parser.nth(1), Some(Item::Section("\u{3000}S ")));
+
+	// Enabling replaces the custom predicate
+	let mut parser = Parser::new(document).trim_with(|chr| chr == 'S').auto_trim_unicode(true);
+	assert_eq!(parser.nth(1), Some(Item::Section("S")));
+}
+
+#[test]
+fn test_blank_on_whitespace() {
+	let document = "[]\n=\r\n = \n;\n \r= \r\n =\n\t \t\nKey\n=";
+	let mut parser = Parser::new(document).blank_on_whitespace(true);
+	assert_eq!(parser.nth(5), Some(Item::Blank));
+	assert_eq!(parser.nth(2), Some(Item::Blank));
+	assert_eq!(parser.next(), Some(Item::Property("Key", None)));
+
+	// Without the option the whitespace is preserved
+	let mut parser = Parser::new(document);
+	assert_eq!(parser.nth(5), Some(Item::Property(" ", None)));
+	assert_eq!(parser.nth(2), Some(Item::Property("\t \t", None)));
+
+	// Values are not trimmed
+	let items: Vec<_> = Parser::new(document).blank_on_whitespace(true).collect();
+	assert_eq!(items.iter().filter(|item| matches!(item, Item::Property(_, _))).count(), 6);
+	assert!(items.contains(&Item::Property(" ", Some(" "))));
+
+	// Strict mode accepts whitespace lines either way
+	let mut parser = Parser::new(" \n\t\nKey").strict(true).blank_on_whitespace(true);
+	assert_eq!(parser.next(), Some(Item::Blank));
+	assert_eq!(parser.next(), Some(Item::Blank));
+	assert_eq!(parser.next(), Some(Item::Error("Key")));
+}
+
+#[test]
+fn test_last_property() {
+	fn check_last(document: &str, parser: fn(&str) -> Parser) {
+		for section in [None, Some("S"), Some("T"), Some("U")] {
+			for key in ["a", "b", "c"] {
+				// Same as get but keep going to find the last match
+				let mut expected = None;
+				let mut items = parser(document);
+				while let Some(item) = items.next() {
+					match item {
+						Item::Section(_) if section.is_none() => break,
+						Item::Property(k, Some(v)) if items.lookup_section() == Some(section) && k == key => expected = Some(v),
+						_ => (),
+					}
+				}
+				assert_eq!(parser(document).last_property(section, key), expected, "{document:?} {section:?} {key}");
+			}
+		}
+	}
+	let documents = [
+		"",
+		"a=1\nb=2\na=3",
+		"a=1\n[S]\na=2\nb\n[T]\nb=3\n[S]\nb=4\nc=5;x\n[T]\n",
+		"\r\na = 1\r\n\r[S]\r\n;c=2\r\nc=3\n\r[T\r\nc=4\r\n[U]\nc=5\nc\n",
+		"[S]\n[T]\n[S]\na=1\n\n",
+	];
+	for document in documents {
+		check_last(document, |s| Parser::new(s));
+		check_last(document, |s| Parser::new(s).auto_trim(true));
+		check_last(document, |s| Parser::new(s).comment_char(b'#').inline_comments(true));
+	}
+
+	let document = "[S]\na=1\\\nb=2\na=3\\\n[T]";
+	assert_eq!(Parser::new(document).last_property(Some("S"), "b"), Some("2"));
+	assert_eq!(Parser::new(document).line_continuation(true).last_property(Some("S"), "b"), None);
+	assert_eq!(Parser::new(document).line_continuation(true).last_property(Some("S"), "a"), Some("3\\\n[T]"));
+
+	// Properties after a malformed section header belong to no section
+	let document = "[S]\na=1\n[T\nb=2";
+	assert_eq!(Parser::new(document).last_property(Some("S"), "a"), Some("1"));
+	assert_eq!(Parser::new(document).last_property(Some("S"), "b"), None);
+	assert_eq!(Parser::new(document).strict(true).last_property(Some("S"), "a"), Some("1"));
+	assert_eq!(Parser::new(document).line_continuation(true).last_property(Some("S"), "b"), None);
+}
+
+#[test]
+fn test_split_last() {
+	fn items(parser: Parser) -> Vec<Item> { parser.collect() }
+	let document = "a=b=c\n=x=\nk=v\n==\nnone\n[a=b]\n";
+	assert_eq!(items(Parser::new(document).split_last(true)), [
+		Item::Property("a=b", Some("c")),
+		Item::Property("=x", Some("")),
+		Item::Property("k", Some("v")),
+		Item::Property("=", Some("")),
+		Item::Property("none", None),
+		Item::SectionEnd,
+		Item::Section("a=b"),
+		Item::SectionEnd,
+	]);
+	assert_eq!(items(Parser::new(document).split_last(false)), items(Parser::new(document)));
+
+	let mut parser = Parser::new(" a = b = c \r\nk : v : w").split_last(true).auto_trim(true);
+	assert_eq!(parser.next(), Some(Item::Property("a = b", Some("c"))));
+	let mut parser = parser.separator_char(b':');
+	assert_eq!(parser.next(), Some(Item::Property("k : v", Some("w"))));
+
+	// The separator is only searched on the first line of continued values
+	let mut parser = Parser::new("a=b\\\nc=d").split_last(true).line_continuation(true);
+	assert_eq!(parser.next(), Some(Item::Property("a", Some("b\\\nc=d"))));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_stream_parser() {
+	fn stream(document: &str, chunk: usize, options: fn(&str) -> Parser) -> Vec<OwnedItem> {
+		let mut stream = StreamParser::new(options(""));
+		let mut items = Vec::new();
+		for bytes in document.as_bytes().chunks(chunk) {
+			stream.feed(bytes);
+			while let Some(item) = stream.next() {
+				items.push(item.unwrap().to_owned());
+			}
+		}
+		stream.finish();
+		while let Some(item) = stream.next() {
+			items.push(item.unwrap().to_owned());
+		}
+		let mut parser = options(document);
+		parser.by_ref().for_each(drop);
+		assert_eq!(stream.line(), parser.line());
+		items
+	}
+	let documents = [
+		"",
+		"a=1",
+		"a=1\r\n\r\n[S]\r\n;c\r\nb\r\n[T\r\n",
+		"a=1\r\r\n[S]\n\rk=\u{3000}v\\\nw\n[T]\nk = v ; c\\\n",
+		"\n\n[]\n[S]x\n[S]\n[S]\n",
+		"a=1\n b\r\n\tc\n[S]\n d\nk=v\n \n w\n",
+	];
+	let options: [fn(&str) -> Parser; 4] = [
+		|s| Parser::new(s),
+		|s| Parser::new(s).auto_trim(true).inline_comments(true),
+		|s| Parser::new(s).line_continuation(true),
+		|s| Parser::new(s).folding(true),
+	];
+	for document in documents {
+		for options in options {
+			let expected: Vec<_> = options(document).map(OwnedItem::from).collect();
+			for chunk in 1..=document.len().max(1) {
+				assert_eq!(stream(document, chunk, options), expected, "{document:?} {chunk}");
+			}
+		}
+	}
+
+	// Invalid utf8 is reported per line
+	let mut stream = StreamParser::default();
+	stream.feed(b"a=\xff\nb=");
+	stream.feed(b"\xc3");
+	assert!(matches!(stream.next(), Some(Err(_))));
+	assert_eq!(stream.next(), None);
+	stream.feed(b"\xa9\n");
+	assert_eq!(stream.next(), Some(Ok(Item::Property("b", Some("\u{e9}")))));
+
+	// Strict mode stops at the first error
+	let mut stream = StreamParser::new(Parser::new("").strict(true));
+	stream.feed(b"a\nb=1\n");
+	assert_eq!(stream.next(), Some(Ok(Item::Error("a"))));
+	assert_eq!(stream.next(), None);
+	assert_eq!(stream.error().map(|err| err.line()), Some(1));
+	stream.finish();
+	assert_eq!(stream.next(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_io_parser() {
+	let documents = [
+		"",
+		"a=1",
+		"a=1\r\n\r\n[S]\r\n;c\r\nb\r\n[T\r\n",
+		"a=1\r\r\n[S]\n\rk=\u{3000}v\\\nw\n[T]\nk = v ; c\\\n",
+		"\n\n[]\n[S]x\n[S]\n[S]\n",
+		"a=1\n b\r\n\tc\n[S]\n d\nk=v\n \n w\n",
+	];
+	let options: [fn(&str) -> Parser; 4] = [
+		|s| Parser::new(s),
+		|s| Parser::new(s).auto_trim(true).inline_comments(true),
+		|s| Parser::new(s).line_continuation(true),
+		|s| Parser::new(s).folding(true),
+	];
+	for document in documents {
+		for options in options {
+			let expected: Vec<_> = options(document).map(OwnedItem::from).collect();
+			// Tiny buffer to split lines over multiple reads
+			let reader = std::io::BufReader::with_capacity(3, document.as_bytes());
+			let items: Vec<_> = IoParser::new(reader, options("")).collect::<std::io::Result<_>>().unwrap();
+			assert_eq!(items, expected, "{document:?}");
+		}
+	}
+
+	let mut parser = IoParser::new(&b"a=\xff\nb=1"[..], Parser::new(""));
+	assert_eq!(parser.next().unwrap().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+	assert_eq!(parser.next().unwrap().unwrap(), Item::Property("b", Some("1")));
+	assert_eq!(parser.next().unwrap().unwrap(), Item::SectionEnd);
+	assert!(parser.next().is_none());
+	assert_eq!(parser.line(), 1);
+}
+
+#[test]
+fn test_visitor() {
+	use core::ops::ControlFlow;
+
+	// Records every callback, stops after the given number of items
+	struct Recorder<'a> {
+		items: Vec<Item<'a>>,
+		limit: usize,
+	}
+	impl<'a> Recorder<'a> {
+		fn push(&mut self, item: Item<'a>) -> ControlFlow<()> {
+			self.items.push(item);
+			if self.items.len().is_multiple_of(self.limit) { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+		}
+	}
+	impl<'a> Visitor<'a> for Recorder<'a> {
+		fn error(&mut self, error: &'a str) -> ControlFlow<()> { self.push(Item::Error(error)) }
+		fn section(&mut self, name: &'a str) -> ControlFlow<()> { self.push(Item::Section(name)) }
+		fn section_end(&mut self) -> ControlFlow<()> { self.push(Item::SectionEnd) }
+		fn property(&mut self, key: &'a str, value: Option<&'a str>) -> ControlFlow<()> { self.push(Item::Property(key, value)) }
+		fn comment(&mut self, comment: &'a str) -> ControlFlow<()> { self.push(Item::Comment(comment)) }
+		fn blank(&mut self) -> ControlFlow<()> { self.push(Item::Blank) }
+	}
+
+	let document = "a=1\n;c\n\n[S\nk\n[T]\nb=2";
+	let expected: Vec<_> = Parser::new(document).collect();
+
+	let mut visitor = Recorder { items: Vec::new(), limit: usize::MAX };
+	assert_eq!(parse_with(document, &mut visitor), ControlFlow::Continue(()));
+	assert_eq!(visitor.items, expected);
+
+	// Resuming after breaking continues with the next item
+	for limit in 1..expected.len() + 1 {
+		let mut parser = Parser::new(document);
+		let mut visitor = Recorder { items: Vec::new(), limit };
+		let mut breaks = 0;
+		while parser.visit(&mut visitor).is_break() {
+			breaks += 1;
+		}
+		assert_eq!(visitor.items, expected);
+		assert_eq!(breaks, expected.len() / limit);
+	}
+
+	// Default callbacks continue
+	struct Nothing;
+	impl<'a> Visitor<'a> for Nothing {}
+	assert_eq!(parse_with(document, &mut Nothing), ControlFlow::Continue(()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_includes() {
+	let dir = std::env::temp_dir().join(format!("ini_core_test_includes_{}", std::process::id()));
+	std::fs::create_dir_all(dir.join("sub")).unwrap();
+	let write = |name: &str, contents: &str| std::fs::write(dir.join(name), contents).unwrap();
+	write("main.ini", "a=1\n@include sub/inc.ini\n[Main]\nb=2\n@include  empty.ini \nc=3\n");
+	write("sub/inc.ini", "x=1\n[Inc]\ny=2\n@include nested.ini\n");
+	write("sub/nested.ini", "z=3");
+	write("empty.ini", "");
+	write("keys.ini", "include = sub/nested.ini\n@include sub/nested.ini\n");
+	write("cycle.ini", "@include sub/cycle.ini\n");
+	write("sub/cycle.ini", "@include ../cycle.ini\n");
+
+	let items = parse_with_includes(dir.join("main.ini")).unwrap();
+	let expected = "a=1\nx=1\n[Inc]\ny=2\nz=3\n[Main]\nb=2\nc=3\n";
+	assert_eq!(items, Parser::new(expected).map(OwnedItem::from).collect::<Vec<_>>());
+
+	let items = Includes::new(Directive::Key("include")).parse(dir.join("keys.ini")).unwrap();
+	assert_eq!(items, [Item::Property("z", Some("3")), Item::Property("@include sub/nested.ini", None), Item::SectionEnd]);
+
+	let items = Includes::default().options(Parser::new("").auto_trim(true)).parse(dir.join("sub/inc.ini")).unwrap();
+	assert_eq!(items.len(), 6);
+
+	let err = parse_with_includes(dir.join("cycle.ini")).unwrap_err();
+	assert!(matches!(err, IncludeError::Cycle(_)), "{err}");
+	assert!(err.path().ends_with("cycle.ini"));
+
+	let err = Includes::default().max_depth(1).parse(dir.join("main.ini")).unwrap_err();
+	assert!(matches!(err, IncludeError::TooDeep(_)), "{err}");
+	assert!(err.path().ends_with("nested.ini"));
+
+	let err = parse_with_includes(dir.join("missing.ini")).unwrap_err();
+	assert!(matches!(err, IncludeError::Io(_, _)), "{err}");
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_expand_vars() {
+	let lookup = |name: &str| match name {
+		"A" => Some(String::from("1")),
+		"B_2" => Some(String::from("$A")),
+		"EMPTY" => Some(String::new()),
+		_ => None,
+	};
+	let expand = |s, missing| expand_vars(s, lookup, missing);
+	assert!(matches!(expand("no vars", MissingVar::Error), Ok(Cow::Borrowed("no vars"))));
+	assert_eq!(expand("$A${A}x${A}x$B_2-", MissingVar::Error).unwrap(), "11x1x$A-");
+	assert_eq!(expand("${EMPTY}|$EMPTY", MissingVar::Error).unwrap(), "|");
+	assert_eq!(expand("$$A $$$A $$", MissingVar::Error).unwrap(), "$A $1 $");
+	// Not placeholders
+	assert_eq!(expand("$ $1 ${} ${A $-", MissingVar::Error).unwrap(), "$ $1 ${} ${A $-");
+	assert_eq!(expand("$", MissingVar::Error).unwrap(), "$");
+	// Undefined variables
+	assert_eq!(expand("<$X${Y}>", MissingVar::Keep).unwrap(), "<$X${Y}>");
+	assert_eq!(expand("<$X${Y}>", MissingVar::Empty).unwrap(), "<>");
+	assert_eq!(expand("<$A${Y}>", MissingVar::Error).unwrap_err().name(), "Y");
+	assert_eq!(expand("$X", MissingVar::Error).unwrap_err().to_string(), "undefined variable: X");
+	assert_eq!(expand("\u{e9}${A}\u{e9}", MissingVar::Error).unwrap(), "\u{e9}1\u{e9}");
+}
+
+#[test]
+fn test_count_items() {
+	let documents = [
+		"",
+		"\n",
+		"\r\n\r\n\r",
+		"a=1",
+		"[S]",
+		"[S",
+		"[S]x\n[T]\n",
+		"a=1\r\n[S]\r\n;c\r\n\r\n b\n[S]\n[T]\n#[U]\n  [V]\n\\\nk=v\\\n\\\n",
+		"\n\r\n\r[\n[]\n[[]]]\n",
+	];
+	let options: [fn(&str) -> Parser; 5] = [
+		|s| Parser::new(s),
+		|s| Parser::new(s).comment_char(b'['),
+		|s| Parser::new(s).auto_trim(true).inline_comments(true).skip_bom(true),
+		|s| Parser::new(s).line_continuation(true),
+		|s| Parser::new(s).strict(true),
+	];
+	for document in documents {
+		for options in options {
+			let mut parser = options(document);
+			loop {
+				assert_eq!(parser.count_items(), parser.clone().count(), "{document:?}");
+				if parser.next().is_none() {
+					break;
+				}
+			}
+		}
+	}
+}
+
+#[test]
+fn test_input_bytes() {
+	let document = "\u{feff}[S]\nk=v\n";
+	let mut parser = Parser::new(document).skip_bom(true);
+	assert_eq!(parser.as_str(), document);
+	assert_eq!(parser.input_bytes(), document.as_bytes());
+	loop {
+		assert_eq!(parser.remainder_bytes(), parser.remainder().as_bytes());
+		assert_eq!(&parser.input_bytes()[parser.offset()..], parser.remainder_bytes());
+		if parser.next().is_none() {
+			break;
+		}
+	}
+	assert_eq!(parser.remainder_bytes(), b"");
+	assert_eq!(BytesParser::new(b"a\xff").input(), b"a\xff");
+}
+
+#[test]
+fn test_significant() {
+	let document = ";c\n\n[S]\n;d\nk=v\n \n[T\nflag\n";
+	let parser = Parser::new(document);
+	assert_eq!(parser.clone().significant().collect::<Vec<_>>(), [
+		Item::SectionEnd,
+		Item::Section("S"),
+		Item::Property("k", Some("v")),
+		Item::Property(" ", None),
+		Item::SectionEnd,
+		Item::Error("[T"),
+		Item::Property("flag", None),
+		Item::SectionEnd,
+	]);
+	assert_eq!(parser.clone().significant().section_ends(false).count(), 5);
+	assert_eq!(parser.clone().auto_trim(true).significant().section_ends(false).count(), 4);
+	assert_eq!(parser.clone().properties().collect::<Vec<_>>(), [("k", Some("v")), (" ", None), ("flag", None)]);
+	assert_eq!(Parser::new(";only\n\n").significant().collect::<Vec<_>>(), [Item::SectionEnd]);
+	assert_eq!(Parser::new(";only\n\n").properties().next(), None);
+}
+
+#[test]
+fn test_empty_section() {
+	fn items(parser: Parser) -> Vec<Item> { parser.collect() }
+	let document = "a=1\n[S]\nb=2\n[]\nc=3\n[ ]\nd=4\n[T]\n";
+	let new = || Parser::new(document);
+	assert_eq!(items(new().empty_section(EmptySectionPolicy::AllowEmpty)), items(new()));
+
+	assert_eq!(items(new().empty_section(EmptySectionPolicy::Error)), [
+		Item::Property("a", Some("1")), Item::SectionEnd,
+		Item::Section("S"), Item::Property("b", Some("2")), Item::SectionEnd,
+		Item::Error("[]"), Item::Property("c", Some("3")), Item::SectionEnd,
+		Item::Section(" "), Item::Property("d", Some("4")), Item::SectionEnd,
+		Item::Section("T"), Item::SectionEnd,
+	]);
+	assert_eq!(Item::Error("[]").error_kind(), Some(ErrorKind::EmptySection));
+	let mut parser = new().empty_section(EmptySectionPolicy::Error).auto_trim(true).strict(true);
+	assert_eq!(parser.nth(5), Some(Item::Error("[]")));
+	assert_eq!(parser.next(), None);
+	assert_eq!(parser.error().map(|err| (err.line(), err.kind())), Some((4, ErrorKind::EmptySection)));
+
+	let mut parser = new().empty_section(EmptySectionPolicy::Global).auto_trim(true);
+	let mut global = Vec::new();
+	while let Some(item) = parser.next() {
+		global.push((item, parser.in_global_section(), parser.current_section()));
+	}
+	assert_eq!(global, [
+		(Item::Property("a", Some("1")), true, None), (Item::SectionEnd, true, None),
+		(Item::Section("S"), false, Some("S")), (Item::Property("b", Some("2")), false, Some("S")), (Item::SectionEnd, true, None),
+		(Item::Property("c", Some("3")), true, None), (Item::SectionEnd, true, None),
+		(Item::Property("d", Some("4")), true, None), (Item::SectionEnd, true, None),
+		(Item::Section("T"), false, Some("T")), (Item::SectionEnd, false, Some("T")),
+	]);
+
+	let parser = new().empty_section(EmptySectionPolicy::Global);
+	assert_eq!(parser.count_items(), parser.clone().count());
+	assert_eq!(parser.clone().get(Some("S"), "c"), None);
+	assert_eq!(parser.clone().last_property(Some("S"), "c"), None);
+	assert_eq!(parser.clone().last_property(Some("S"), "b"), Some("2"));
+	assert_eq!(parser.clone().get(Some(" "), "d"), Some("4"));
+	assert_eq!(Parser::new(document).get(Some(""), "c"), Some("3"));
+}
+
+#[test]
+fn test_detect_newline() {
+	assert_eq!(Newline::detect(""), None);
+	assert_eq!(Newline::detect("a=1"), None);
+	assert_eq!(Newline::detect("\n"), Some(Newline::Lf));
+	assert_eq!(Newline::detect("\r"), Some(Newline::Cr));
+	assert_eq!(Newline::detect("\r\n"), Some(Newline::CrLf));
+	assert_eq!(Newline::detect("[S]\nk=v\r\n"), Some(Newline::Lf));
+	assert_eq!(Newline::detect("[S]\r\rk=v\r\n"), Some(Newline::Cr));
+	assert_eq!(Newline::detect(&("x".repeat(100) + "\r\n")), Some(Newline::CrLf));
+
+	// Detects over the whole input regardless of the position
+	let mut parser = Parser::new("a=1\r\nb=2\nc=3");
+	parser.nth(1);
+	assert_eq!(parser.detect_newline(), Some(Newline::CrLf));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_merge() {
+	// Replaces values in place, keeping comments and unrelated keys
+	let base = ";comment\r\n[S]\r\na = 1\r\nb=2\r\n\r\n[T]\r\nc=3";
+	assert_eq!(merge(base, "[S]\na=10\n;dropped\n[T]\nc=30"), ";comment\r\n[S]\r\na = 10\r\nb=2\r\n\r\n[T]\r\nc=30");
+
+	// New keys go after the last property of the section, new sections at the end
+	assert_eq!(merge(base, "[S]\nd=4\n[U]\ne\n[T]\nf=6"), ";comment\r\n[S]\r\na = 1\r\nb=2\r\nd=4\r\n\r\n[T]\r\nc=3\r\nf=6\r\n[U]\r\ne\r\n");
+
+	// Global properties
+	assert_eq!(merge("x=1\n[S]\ny=2\n", "x=10\nz=3\n[S]\ny=20"), "x=10\nz=3\n[S]\ny=20\n");
+	assert_eq!(merge(";c\n[S]\n", "z=3"), "z=3\n;c\n[S]\n");
+	assert_eq!(merge("", "z=3\n[S]\nk=v"), "z=3\n[S]\nk=v\n");
+	assert_eq!(merge("a=1\n", ""), "a=1\n");
+
+	// Every duplicate in the base is replaced, the last duplicate in the overlay wins
+	assert_eq!(merge("[S]\nk=1\nk=2\n[S]\nk=3\n", "[S]\nk=a\nk=b"), "[S]\nk=b\nk=b\n[S]\nk=b\n");
+
+	// Properties without a value
+	assert_eq!(merge("[S]\nk\nj=1\n", "[S]\nk=1\nj"), "[S]\nk=1\nj\n");
+	assert_eq!(merge("[S]\nk =  \n", "[S]\nk=v"), "[S]\nk =  v\n");
+
+	// Same key in another section is left alone, sections are case sensitive
+	assert_eq!(merge("k=0\n[S]\nk=1\n[s]\nk=2", "[S]\nk=9"), "k=0\n[S]\nk=9\n[s]\nk=2");
+
+	// Properties after a malformed section header belong to no section
+	assert_eq!(merge("[S]\na=1\n[T\nb=2\n", "[S]\nb=3"), "[S]\na=1\nb=3\n[T\nb=2\n");
+	assert_eq!(merge("[S]\na=1\n", "[S]\n[T\na=3"), "[S]\na=1\n");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_diff() {
+	assert_eq!(diff("", ""), []);
+	assert_eq!(diff("a=1\n[S]\nb = 2\n", "a = 1\n\n[S]\n;c\nb=2"), []);
+
+	// Global properties
+	assert_eq!(diff("a=1\nb=2\nc", "a=1\nb\nc=3\nd=4"), [
+		Change::PropertyModified { section: None, key: "b", old: Some("2"), new: None },
+		Change::PropertyModified { section: None, key: "c", old: None, new: Some("3") },
+		Change::PropertyAdded { section: None, key: "d", value: Some("4") },
+	]);
+
+	// Sections in document order, duplicate sections are merged and the last key wins
+	assert_eq!(diff("[S]\nk=1\n[T]\nk=2\n[S]\nk=3", "[U]\nk=4\n[S]\nk=3\n[V]"), [
+		Change::SectionRemoved("T"),
+		Change::PropertyRemoved { section: Some("T"), key: "k", value: Some("2") },
+		Change::SectionAdded("U"),
+		Change::PropertyAdded { section: Some("U"), key: "k", value: Some("4") },
+		Change::SectionAdded("V"),
+	]);
+
+	// Properties after a malformed section header belong to no section
+	assert_eq!(diff("[S]\na=1\n[T\nb=2", "[S]\na=1\n[U\nb=3"), []);
+	assert_eq!(diff("[S]\na=1\n[T\nb=2", "[S]\na=1\nb=2"), [
+		Change::PropertyAdded { section: Some("S"), key: "b", value: Some("2") },
+	]);
+
+	// Case sensitive
+	assert_eq!(diff("[S]\nk=1", "[S]\nK=1"), [
+		Change::PropertyRemoved { section: Some("S"), key: "k", value: Some("1") },
+		Change::PropertyAdded { section: Some("S"), key: "K", value: Some("1") },
+	]);
+
+	// Comments are matched up per section
+	let differ = Diff::new().comments(true);
+	assert_eq!(differ.diff(";a\n;a\n[S]\n;b", ";a\n[S]\n;b\n;c\n[T]\n;d"), [
+		Change::CommentRemoved { section: None, comment: "a" },
+		Change::CommentAdded { section: Some("S"), comment: "c" },
+		Change::SectionAdded("T"),
+		Change::CommentAdded { section: Some("T"), comment: "d" },
+	]);
+}
+
+#[test]
+fn test_parse_values() {
+	for value in ["true", "TRUE", "True", "yes", "Yes", "on", "ON", "1", " 1\t"] {
+		assert_eq!(parse_bool(value), Some(true), "{:?}", value);
+	}
+	for value in ["false", "FALSE", "no", "No", "off", "Off", "0", "\t0 "] {
+		assert_eq!(parse_bool(value), Some(false), "{:?}", value);
+	}
+	for value in ["", " ", "y", "n", "t", "f", "2", "01", "truee", "enabled"] {
+		assert_eq!(parse_bool(value), None, "{:?}", value);
+	}
+
+	assert_eq!(parse_int("0"), Some(0));
+	assert_eq!(parse_int("+7"), Some(7));
+	assert_eq!(parse_int(" -9223372036854775808 "), Some(i64::MIN));
+	assert_eq!(parse_int("9223372036854775808"), None);
+	assert_eq!(parse_int("1.0"), None);
+	assert_eq!(parse_int(""), None);
+
+	assert_eq!(parse_float("2"), Some(2.0));
+	assert_eq!(parse_float(" -0.25e2 "), Some(-25.0));
+	assert_eq!(parse_float("inf"), Some(f64::INFINITY));
+	assert_eq!(parse_float("abc"), None);
+}
+
+#[test]
+fn test_case_insensitive() {
+	assert!(key_eq("", ""));
+	assert!(key_eq("Key_1", "kEY_1"));
+	assert!(!key_eq("Key", "Keys"));
+	assert!(!key_eq("\u{c9}", "\u{e9}"));
+
+	let document = "a=1\n[Sect]\nKey=2\nkey=3\n[sect]\nKEY=4\n";
+	let parser = Parser::new(document);
+	assert_eq!(parser.clone().get(Some("SECT"), "key"), None);
+	assert_eq!(parser.clone().get(Some("Sect"), "key"), Some("3"));
+	assert_eq!(parser.clone().last_property(Some("sect"), "Key"), None);
+
+	let parser = parser.case_insensitive(true);
+	assert_eq!(parser.clone().get(None, "A"), Some("1"));
+	assert_eq!(parser.clone().get(Some("SECT"), "key"), Some("2"));
+	assert_eq!(parser.clone().get(Some("SECT"), "a"), None);
+	assert_eq!(parser.clone().last_property(Some("SECT"), "key"), Some("4"));
+	assert_eq!(parser.clone().last_property(None, "A"), Some("1"));
+	assert_eq!(parser.clone().line_continuation(true).last_property(Some("sEcT"), "kEy"), Some("4"));
+
+	// The items are not affected
+	assert!(parser.clone().eq(Parser::new(document)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_case_insensitive_map() {
+	let document = "[Sect]\nKey=2\nkey=3\n[sect]\nKEY=4\nOther\n";
+	let map = Parser::new(document).case_insensitive(true).into_map_with(DuplicateKeys::KeepFirst);
+	assert_eq!(map.len(), 1);
+	assert_eq!(map[&Some("sect".to_string())], [("key".to_string(), Some("2".to_string())), ("other".to_string(), None)]);
+
+	let map = Parser::new(document).into_map_with(DuplicateKeys::KeepFirst);
+	assert_eq!(map.len(), 2);
+	assert_eq!(map[&Some("Sect".to_string())].len(), 2);
+}
+
+#[test]
+fn test_section_trailing() {
+	let document = "[A] ; comment\n[B]x\n[C]\n[D] \r\n[a][b] c\n[E\nk=v";
+	let mut parser = Parser::new(document);
+	assert_eq!(parser.nth(1), Some(Item::Error("[A] ; comment")));
+	assert_eq!(parser.trailing(), None);
+
+	let mut parser = Parser::new(document).section_trailing(true);
+	assert_eq!(parser.nth(1), Some(Item::Section("A")));
+	assert_eq!(parser.trailing(), Some(" ; comment"));
+	assert_eq!(parser.nth(1), Some(Item::Section("B")));
+	assert_eq!(parser.trailing(), Some("x"));
+	assert_eq!(parser.nth(1), Some(Item::Section("C")));
+	assert_eq!(parser.trailing(), None);
+	assert_eq!(parser.nth(1), Some(Item::Section("D")));
+	assert_eq!(parser.trailing(), Some(" "));
+	assert_eq!(parser.nth(1), Some(Item::Section("a][b")));
+	assert_eq!(parser.trailing(), Some(" c"));
+	assert_eq!(parser.nth(1), Some(Item::Error("[E")));
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("v"))));
+
+	// Trimming applies to the name only
+	let mut parser = Parser::new("[ A ] # x").section_trailing(true).auto_trim(true);
+	assert_eq!(parser.nth(1), Some(Item::Section("A")));
+	assert_eq!(parser.trailing(), Some(" # x"));
+
+	// Strict mode accepts the header
+	let mut parser = Parser::new("[A] x\nk=v").section_trailing(true).strict(true);
+	assert_eq!(parser.nth(1), Some(Item::Section("A")));
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("v"))));
+	assert_eq!(parser.error(), None);
+
+	// The trailing text is saved with the position
+	let mut parser = Parser::new("[A]x\n[B]").section_trailing(true);
+	parser.nth(1);
+	let pos = parser.position();
+	parser.nth(1);
+	assert_eq!(parser.trailing(), None);
+	parser.seek(pos);
+	assert_eq!(parser.trailing(), Some("x"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_value_pipeline() {
+	use alloc::borrow::Cow;
+
+	let pipeline = ValuePipeline::new().trim(true).join_continuations(true).unescape(true);
+	let document = "a = plain value \nb= a\\\n  b\nc=\\x41 \nd=";
+	let mut values = Vec::new();
+	for item in Parser::new(document).line_continuation(true) {
+		if let Item::Property(_, Some(value)) = item {
+			values.push(pipeline.apply(value));
+		}
+	}
+	assert!(matches!(values[0], Cow::Borrowed("plain value")));
+	assert!(matches!(&values[1], Cow::Owned(s) if s == "a  b"));
+	assert!(matches!(&values[2], Cow::Owned(s) if s == "A"));
+	assert!(matches!(values[3], Cow::Borrowed("")));
+
+	// Disabled steps leave the value alone
+	assert!(matches!(ValuePipeline::new().apply(" \\t "), Cow::Borrowed(" \\t ")));
+
+	// Steps which change nothing after an allocating step keep the owned string
+	fn keep(s: &str) -> Cow<'_, str> {
+		expand_vars(s, |_| None, MissingVar::Keep).unwrap()
+	}
+	fn expand(s: &str) -> Cow<'_, str> {
+		expand_vars(s, |_| Some(String::from("v")), MissingVar::Keep).unwrap()
+	}
+	assert!(matches!(pipeline.apply_with(" x ", keep), Cow::Borrowed("x")));
+	assert!(matches!(pipeline.apply_with(r"\;x", keep), Cow::Owned(s) if s == ";x"));
+	assert_eq!(pipeline.apply_with(" $X\\t ", expand), "v\t");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_write_items() {
+	let document = "g=1\n;c\n[A]\nk= v \n\n[B\nflag\n[]\n[C]\n=empty\n";
+	let items: Vec<_> = Parser::new(document).collect();
+	let written = write_items(items.iter().cloned()).unwrap();
+	assert_eq!(written, document);
+	assert_eq!(Parser::new(&written).collect::<Vec<_>>(), items);
+
+	// Newlines are normalized, the items are the same
+	let items: Vec<_> = Parser::new("a=1\r\n[S]\rb").collect();
+	let written = write_items(items.iter().cloned()).unwrap();
+	assert_eq!(written, "a=1\n[S]\nb\n");
+	assert_eq!(Parser::new(&written).collect::<Vec<_>>(), items);
+
+	// Without section ends
+	assert_eq!(write_items([Item::Section("S"), Item::Property("k", Some("v"))]).unwrap(), "[S]\nk=v\n");
+	assert_eq!(write_items([]).unwrap(), "");
+
+	assert_eq!(write_items([Item::Property("k", Some("a\nb"))]), Err(WriteError::Newline));
+	assert_eq!(write_items([Item::Section("a]b")]), Err(WriteError::SectionBracket));
+
+	// Error text is written unchecked and parses back as the line it is
+	assert_eq!(write_items([Item::Error("k=v"), Item::Error("[S]")]).unwrap(), "k=v\n[S]\n");
+}
+
+// Reproduces the document from the displayed items and the newlines which ended their lines
+fn reserialize(document: &str) -> String {
+	let mut parser = Parser::new(document);
+	let mut output = String::new();
+	while let Some((item, newline)) = parser.next_with_newline() {
+		output += &item.display_line(newline).to_string();
+	}
+	return output;
+}
+
+#[test]
+fn test_round_trip() {
+	let documents = [
+		"",
+		"\n",
+		"\r",
+		"\r\n",
+		"\n\r",
+		"[S]",
+		"[S]\r\nk=v\r\n",
+		"a=1\rb=2\nc=3\r\n",
+		" ; not a comment \n;comment\r\n\t\n[ S ]\r\n k = v \n[Err\r\n=\n[]\r\n",
+		"a=b=c\n[x][y]\n  \r\n\r\n\r\nlast",
+	];
+	for document in documents {
+		assert_eq!(reserialize(document), document);
+	}
+
+	// Random documents built from tricky lines and newlines
+	const LINES: [&str; 14] = ["", " ", "\t", "[S]", "[S", "[]", "k=v", " k = v ", "=", "k", "=v", ";c", "#c", "a=b;c"];
+	const NEWLINES: [&str; 3] = ["\n", "\r\n", "\r"];
+	let mut seed = 0x2545f491u32;
+	let mut random = |n: usize| {
+		seed ^= seed << 13;
+		seed ^= seed >> 17;
+		seed ^= seed << 5;
+		seed as usize % n
+	};
+	for _ in 0..1000 {
+		let mut document = String::new();
+		for _ in 0..random(12) {
+			document += LINES[random(LINES.len())];
+			document += NEWLINES[random(NEWLINES.len())];
+		}
+		if random(2) == 0 {
+			document += LINES[random(LINES.len())];
+		}
+		assert_eq!(reserialize(&document), document);
+	}
+}
+
+#[test]
+fn test_next_with_newline() {
+	let document = "[S]\r\n\r\n\n\rk=v\r;c\n[T";
+	let mut parser = Parser::new(document);
+	assert_eq!(parser.next_with_newline(), Some((Item::SectionEnd, None)));
+	assert_eq!(parser.next_with_newline(), Some((Item::Section("S"), Some(Newline::CrLf))));
+	assert_eq!(parser.next_with_newline(), Some((Item::Blank, Some(Newline::CrLf))));
+	assert_eq!(parser.next_with_newline(), Some((Item::Blank, Some(Newline::Lf))));
+	assert_eq!(parser.next_with_newline(), Some((Item::Blank, Some(Newline::Cr))));
+	assert_eq!(parser.next_with_newline(), Some((Item::Property("k", Some("v")), Some(Newline::Cr))));
+	assert_eq!(parser.next_with_newline(), Some((Item::Comment("c"), Some(Newline::Lf))));
+	assert_eq!(parser.next_with_newline(), Some((Item::SectionEnd, None)));
+	assert_eq!(parser.next_with_newline(), Some((Item::Error("[T"), None)));
+	assert_eq!(parser.next_with_newline(), Some((Item::SectionEnd, None)));
+	assert_eq!(parser.next_with_newline(), None);
+
+	// Continued lines keep their inner newlines in the value
+	let mut parser = Parser::new("a=1\\\r\n2\r\nb=3").line_continuation(true);
+	assert_eq!(parser.next_with_newline(), Some((Item::Property("a", Some("1\\\r\n2")), Some(Newline::CrLf))));
+
+	// Writing the items reproduces the mixed newlines
+	let mut writer = Writer::new(String::new()).newline(Newline::CrLf);
+	let mut parser = Parser::new(document);
+	while let Some((item, newline)) = parser.next_with_newline() {
+		writer.item_with_newline(item, newline).unwrap();
+	}
+	writer.blank().unwrap();
+	assert_eq!(writer.finish(), String::from(document) + "\r\n");
+}
+
+#[test]
+fn test_reject_control_chars() {
+	let document = "a=\x01\n[S\0]\nb=\x7f\n\x1b;c\nd=\te\n[T]\r\nk=1\\\n\0\nk=2";
+	let value: Vec<_> = Parser::new(document).reject_control_chars(true).line_continuation(true).collect();
+	assert_eq!(value, [
+		Item::Error("a=\x01"),
+		Item::SectionEnd,
+		Item::Error("[S\0]"),
+		Item::Error("b=\x7f"),
+		Item::Error("\x1b;c"),
+		Item::Property("d", Some("\te")),
+		Item::SectionEnd,
+		Item::Section("T"),
+		Item::Error("k=1\\\n\0"),
+		Item::Property("k", Some("2")),
+		Item::SectionEnd,
+	]);
+	assert_eq!(Parser::new(document).reject_control_chars(true).count_items(), Parser::new(document).reject_control_chars(true).count());
+
+	let errors: Vec<_> = Parser::new(document).reject_control_chars(true).line_continuation(true).results()
+		.filter_map(Result::err).map(|error| (error.line(), error.kind())).collect();
+	assert_eq!(errors, [(1, ErrorKind::ControlChar), (2, ErrorKind::ControlChar), (3, ErrorKind::ControlChar), (4, ErrorKind::ControlChar), (7, ErrorKind::ControlChar)]);
+
+	let mut parser = Parser::new("[S]\nk=1\\\nv\0").reject_control_chars(true).line_continuation(true).strict(true);
+	parser.by_ref().for_each(drop);
+	assert_eq!(parser.error().map(|error| (error.line(), error.kind())), Some((2, ErrorKind::ControlChar)));
+
+	// Other errors keep their kind
+	let mut parser = Parser::new("[S").reject_control_chars(true).strict(true);
+	parser.by_ref().for_each(drop);
+	assert_eq!(parser.error().map(|error| error.kind()), Some(ErrorKind::UnterminatedSection));
+
+	assert!(Parser::new(document).eq(Parser::new(document).reject_control_chars(false)));
+}
+
+#[test]
+fn test_quoted_values() {
+	let document = r#"a="x"
+b = " padded " 
+c="a\\"
+d="a\"
+e="x" y
+f=x "y"
+g=""
+h="a;b" ;c
+i="a"#;
+	let value: Vec<_> = Parser::new(document).quoted_values(true).auto_trim(true).collect();
+	assert_eq!(value, [
+		Item::Property("a", Some("x")),
+		Item::Property("b", Some(" padded ")),
+		Item::Property("c", Some(r"a\\")),
+		Item::Error(r#"d="a\""#),
+		Item::Property("e", Some(r#""x" y"#)),
+		Item::Property("f", Some(r#"x "y""#)),
+		Item::Property("g", Some("")),
+		Item::Property("h", Some(r#""a;b" ;c"#)),
+		Item::Error(r#"i="a"#),
+		Item::SectionEnd,
+	]);
+	let quoted: Vec<_> = {
+		let mut parser = Parser::new(document).quoted_values(true);
+		core::iter::from_fn(|| parser.next().map(|_| parser.quoted())).collect()
+	};
+	assert_eq!(quoted, [true, true, true, false, false, false, true, false, false, false]);
+
+	let errors: Vec<_> = Parser::new(document).quoted_values(true).results().filter_map(Result::err).map(|error| (error.line(), error.kind())).collect();
+	assert_eq!(errors, [(4, ErrorKind::UnterminatedQuote), (9, ErrorKind::UnterminatedQuote)]);
+	assert!(Parser::new(document).eq(Parser::new(document).quoted_values(false)));
+
+	// Inline comments after the closing quote
+	let mut parser = Parser::new("h=\"a;b\" ;c\nk=\"v\"\n").quoted_values(true).inline_comments(true);
+	assert_eq!(parser.next(), Some(Item::Property("h", Some("a;b"))));
+	assert_eq!(parser.inline_comment(), Some("c"));
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("v"))));
+	assert_eq!(parser.inline_comment(), None);
+
+	// Separators within the quotes
+	let mut parser = Parser::new("a=b=\"c=d\"").quoted_values(true).split_last(true);
+	assert_eq!(parser.next(), Some(Item::Property("a=b", Some("c=d"))));
+	let mut parser = Parser::new("key  \"a b\"  ").quoted_values(true).separator(Separator::Whitespace);
+	assert_eq!(parser.next(), Some(Item::Property("key", Some("a b"))));
+
+	// Continued lines within the quotes
+	let mut parser = Parser::new("k=\"a\\\nb\"\nl=1").quoted_values(true).line_continuation(true);
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("a\\\nb"))));
+	assert_eq!(parser.next(), Some(Item::Property("l", Some("1"))));
+
+	assert_eq!(Parser::new("[S]\nk = \" v \"").quoted_values(true).get(Some("S"), "k "), Some(" v "));
+}
+
+#[test]
+fn test_properties_of() {
+	let document = "k=0\n[ A ]\nk=1\n;c\n[B\nflag\n[A]\nk=2\n[]\nk=3";
+	let mut parser = Parser::new(document).auto_trim(true);
+	assert!(parser.properties_of("A").eq([("k", Some("1"))]));
+	assert_eq!(parser.next(), Some(Item::Error("[B")));
+
+	// Duplicate sections are found one after another
+	assert!(parser.properties_of("A").eq([("k", Some("2"))]));
+	assert!(parser.properties_of("A").eq([]));
+	assert_eq!(parser.next(), None);
+
+	// Untrimmed names don't match
+	assert!(Parser::new(document).properties_of("A").eq([("k", Some("2"))]));
+	assert!(Parser::new(document).properties_of("").eq([("k", Some("3"))]));
+	assert!(Parser::new(document).empty_section(EmptySectionPolicy::Global).properties_of("").eq([]));
+
+	// Dropping the iterator early leaves the parser in the section
+	let mut parser = Parser::new(document).auto_trim(true);
+	assert_eq!(parser.properties_of("A").next(), Some(("k", Some("1"))));
+	assert_eq!(parser.next(), Some(Item::Comment("c")));
+}
+
+#[test]
+fn test_nth_line() {
+	let document = "a=1\r\n[S]\r\r\n ;c\n[T\nk\r[U]x\n\n[]\nlast";
+	for parser in [Parser::new(document), Parser::new(document).auto_trim(true).strict(true), Parser::new(document).no_comments().section_trailing(true)] {
+		// Every line parses to the item it produces in context
+		let mut items = parser.clone();
+		while let Some(item) = items.next() {
+			if item != Item::SectionEnd {
+				assert_eq!(parser.nth_line(items.line_number()), Some(item));
+			}
+		}
+		assert_eq!(parser.nth_line(0), None);
+		assert_eq!(parser.nth_line(11), None);
+	}
+
+	// The skipped byte order mark isn't part of the first line
+	let parser = Parser::new("\u{FEFF}[S]\nk=v").skip_bom(true);
+	assert_eq!(parser.nth_line(1), Some(Item::Section("S")));
+	assert_eq!(parser.nth_line(2), Some(Item::Property("k", Some("v"))));
+	assert_eq!(Parser::new("\u{FEFF}[S]").nth_line(1), Some(Item::Property("\u{FEFF}[S]", None)));
+
+	// Independent of the state of the parser
+	let mut parser = Parser::new(document).strict(true);
+	parser.by_ref().for_each(drop);
+	assert_eq!(parser.nth_line(6), Some(Item::Error("k")));
+	assert_eq!(parser.nth_line(9), Some(Item::Section("")));
+	assert_eq!(parser.nth_line(10), Some(Item::Error("last")));
+	assert!(parser.error().is_some());
+
+	assert_eq!(Parser::new("a\n").nth_line(2), None);
+	assert_eq!(Parser::new("k=a\\\nb").line_continuation(true).nth_line(1), Some(Item::Property("k", Some("a\\\nb"))));
+	assert_eq!(Parser::new("k=a\\\nb").line_continuation(true).nth_line(2), Some(Item::Property("b", None)));
+}
+
+#[test]
+fn test_results() {
+	fn errors(parser: Parser<'_>) -> Vec<(u32, ErrorKind, &str)> {
+		parser.results().filter_map(Result::err).map(|error| (error.line(), error.kind(), error.text())).collect()
+	}
+
+	let document = "flag\r\n[A\r\n\r\n[B]x\r\n[]\r\nk=v";
+	assert!(Parser::new(document).results().map(|result| result.unwrap_or_else(|error| Item::Error(error.text()))).eq(Parser::new(document)));
+	assert_eq!(errors(Parser::new(document)), [(2, ErrorKind::UnterminatedSection, "[A"), (4, ErrorKind::TrailingAfterSection, "[B]x")]);
+	assert_eq!(errors(Parser::new(document).empty_section(EmptySectionPolicy::Error)), [
+		(2, ErrorKind::UnterminatedSection, "[A"),
+		(4, ErrorKind::TrailingAfterSection, "[B]x"),
+		(5, ErrorKind::EmptySection, "[]"),
+	]);
+	assert_eq!(errors(Parser::new(document).strict(true)), [(1, ErrorKind::MissingSeparator, "flag")]);
+	assert_eq!(errors(Parser::new("a=1\nabcdef").max_line_len(4)), [(2, ErrorKind::LineTooLong, "abcd")]);
+
+	// The kinds depending on the parser options are only recovered from the parser
+	let kinds = |parser: Parser<'static>| {
+		let error = parser.results().find_map(Result::err).unwrap();
+		(error.kind(), Item::Error(error.text()).error_kind().unwrap())
+	};
+	assert_eq!(kinds(Parser::new("[abcdef]").max_line_len(4)), (ErrorKind::LineTooLong, ErrorKind::UnterminatedSection));
+	assert_eq!(kinds(Parser::new("k=\x01").reject_control_chars(true)), (ErrorKind::ControlChar, ErrorKind::MissingSeparator));
+	assert_eq!(kinds(Parser::new("k=\"v").quoted_values(true)), (ErrorKind::UnterminatedQuote, ErrorKind::MissingSeparator));
+
+	let error = Parser::new("[S]\n[T").results().find_map(Result::err).unwrap();
+	assert_eq!(error.to_string(), "line 2: unterminated section header: \"[T\"");
+	assert_eq!(ParseError::from(error), error.error());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_validate() {
+	fn errors(validator: Validator<'_>) -> Vec<(u32, ErrorKind)> {
+		validator.map(|error| (error.line(), error.kind())).collect()
+	}
+
+	assert_eq!(validate(""), []);
+	assert_eq!(validate("a=1\n  \n;c\n[S]\nk=v\nk=w\n"), []);
+
+	// Keeps going after the first error
+	let document = "flag\r\n[A\r\n\r\n[B]x\r\n[]\r\nk=v";
+	assert_eq!(errors(Parser::new(document).validate()), [
+		(1, ErrorKind::MissingSeparator),
+		(2, ErrorKind::UnterminatedSection),
+		(4, ErrorKind::TrailingAfterSection),
+	]);
+	assert_eq!(errors(Parser::new(document).empty_section(EmptySectionPolicy::Error).validate()), [
+		(1, ErrorKind::MissingSeparator),
+		(2, ErrorKind::UnterminatedSection),
+		(4, ErrorKind::TrailingAfterSection),
+		(5, ErrorKind::EmptySection),
+	]);
+
+	// Strict mode stops at the first of the same errors
+	let mut parser = Parser::new(document).strict(true);
+	for _ in parser.by_ref() {}
+	assert_eq!(parser.error().map(|error| (error.line(), error.kind())), Some((1, ErrorKind::MissingSeparator)));
+
+	// Duplicate keys
+	let document = "k=1\nk=2\n[S]\nk=3\nK=4\n[T]\nk=5\n[S]\nk=6\n";
+	assert_eq!(errors(Parser::new(document).validate()), []);
+	assert_eq!(errors(Parser::new(document).validate().duplicate_keys(true)), [
+		(2, ErrorKind::DuplicateKey),
+		(9, ErrorKind::DuplicateKey),
+	]);
+	assert_eq!(errors(Parser::new(document).case_insensitive(true).validate().duplicate_keys(true)), [
+		(2, ErrorKind::DuplicateKey),
+		(5, ErrorKind::DuplicateKey),
+		(9, ErrorKind::DuplicateKey),
+	]);
+	assert_eq!(ErrorKind::DuplicateKey.to_string(), "duplicate key");
+}
+
+#[test]
+fn test_default_comments() {
+	let document = ";a\n#b\n!c\n[S]\nk=v ;x";
+	assert!(Parser::new(document).default_comments().eq(Parser::new(document).comment_chars(b";#")));
+	assert!(Parser::new(document).no_comments().eq(Parser::new(document).comment_chars(b"")));
+
+	let mut parser = Parser::new(document).no_comments();
+	assert_eq!(parser.next(), Some(Item::Property(";a", None)));
+	assert_eq!(parser.next(), Some(Item::Property("#b", None)));
+
+	// Sections are still parsed and inline comments have nothing to split on
+	let mut parser = Parser::new(document).no_comments().inline_comments(true);
+	assert_eq!(parser.nth(4), Some(Item::Section("S")));
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("v ;x"))));
+
+	let mut parser = BytesParser::new(b"#b").default_comments();
+	assert_eq!(parser.next(), Some(ByteItem::Comment(b"b")));
+}
+
+#[test]
+fn test_current_line() {
+	let document = "a=1\r\n\r\n;c\n[S]\rk = v \\\n w\n[T";
+	let mut parser = Parser::new(document).line_continuation(true);
+	assert_eq!(parser.current_line(), "");
+	let mut lines = Vec::new();
+	while let Some((item, span)) = parser.next_spanned() {
+		assert_eq!(parser.current_line(), &document[span], "{:?}", item);
+		lines.push(parser.current_line());
+	}
+	assert_eq!(lines, ["a=1", "", ";c", "", "[S]", "k = v \\\n w", "", "[T", ""]);
+
+	// Strict mode stops at the malformed line
+	let mut parser = Parser::new("a=1\nflag\nb=2").strict(true);
+	assert_eq!(parser.nth(1), Some(Item::Error("flag")));
+	assert_eq!(parser.current_line(), "flag");
+	assert_eq!(parser.next(), None);
+	assert_eq!(parser.current_line(), "flag");
+
+	// Saved with the position
+	let mut parser = Parser::new("a=1\nb=2");
+	parser.next();
+	let pos = parser.position();
+	parser.next();
+	assert_eq!(parser.current_line(), "b=2");
+	parser.seek(pos);
+	assert_eq!(parser.current_line(), "a=1");
+}
+
+#[test]
+fn test_max_line_len() {
+	let document = "[S]\nabc=1\r\nabcdef\n";
+	assert!(Parser::new(document).max_line_len(6).eq(Parser::new(document)));
+	assert_eq!(Parser::new(document).max_line_len(6).count_items(), Parser::new(document).count_items());
+
+	let mut parser = Parser::new(document).max_line_len(5);
+	assert_eq!(parser.nth(1), Some(Item::Section("S")));
+	assert_eq!(parser.next(), Some(Item::Property("abc", Some("1"))));
+	assert_eq!(parser.next(), Some(Item::Error("abcde")));
+	assert_eq!(parser.current_line(), "abcdef");
+	assert_eq!(parser.next(), None);
+	let error = parser.error().unwrap();
+	assert_eq!((error.line(), error.kind()), (3, ErrorKind::LineTooLong));
+	assert_eq!(error.to_string(), "line 3: line too long");
+
+	// The span and newline cover the overlong line only
+	let mut parser = Parser::new("a=1\nbbbbbbbbbb\r\nc=3").max_line_len(4);
+	parser.next();
+	assert_eq!(parser.clone().next_spanned(), Some((Item::Error("bbbb"), 4..14)));
+	assert_eq!(parser.next_with_newline(), Some((Item::Error("bbbb"), Some(Newline::CrLf))));
+	let mut parser = Parser::new("k=1\\\nvvvvvvvvvv\nc=3").line_continuation(true).max_line_len(4);
+	assert_eq!(parser.next_spanned(), Some((Item::Error("vvvv"), 0..15)));
+
+	// The error doesn't split a character
+	let mut parser = Parser::new("k=\u{e9}\u{e9}").max_line_len(4);
+	assert_eq!(parser.next(), Some(Item::Error("k=\u{e9}")));
+	let mut parser = Parser::new("k=\u{e9}\u{e9}").max_line_len(0);
+	assert_eq!(parser.next(), Some(Item::Error("")));
+
+	// Lookups scan forward and stop at the overlong line
+	let parser = Parser::new("[S]\nk=1\nkkkkkkkkkk\nk=2").max_line_len(8);
+	assert_eq!(parser.clone().last_property(Some("S"), "k"), Some("1"));
+	assert_eq!(parser.clone().count_items(), 4);
+
+	// Each continued line is limited separately
+	let mut parser = Parser::new("k=123\\\n45678\\\n9").line_continuation(true).max_line_len(6);
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("123\\\n45678\\\n9"))));
+	let mut parser = Parser::new("a=1\\\n123456789\nb=2").line_continuation(true).max_line_len(4);
+	assert_eq!(parser.next(), Some(Item::Error("1234")));
+	assert_eq!(parser.next(), None);
+	assert_eq!(parser.error().map(|error| (error.line(), error.kind())), Some((2, ErrorKind::LineTooLong)));
+
+	// Each folded line is limited separately
+	let mut parser = Parser::new("k=123\n 4567\n\t89").folding(true).max_line_len(6);
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("123\n 4567\n\t89"))));
+	let mut parser = Parser::new("a=1\n b\n 23456789\nb=2").folding(true).max_line_len(4);
+	assert_eq!(parser.next(), Some(Item::Error(" 234")));
+	assert_eq!(parser.next(), None);
+	assert_eq!(parser.error().map(|error| (error.line(), error.kind())), Some((3, ErrorKind::LineTooLong)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_max_line_len_stream() {
+	let mut stream = StreamParser::new(Parser::new("").max_line_len(4));
+	stream.feed(b"a=1\nb=2");
+	assert_eq!(stream.next(), Some(Ok(Item::Property("a", Some("1")))));
+	assert_eq!(stream.next(), None);
+
+	// A partial line over the limit is an error without waiting for its newline
+	stream.feed(b"34567");
+	assert_eq!(stream.next(), Some(Ok(Item::Error("b=23"))));
+	assert_eq!(stream.next(), None);
+	assert_eq!(stream.error().map(|error| error.kind()), Some(ErrorKind::LineTooLong));
+
+	// Only the returned start of the overlong line is validated
+	let mut stream = StreamParser::new(Parser::new("").max_line_len(2));
+	stream.feed(b"ab\xc3");
+	assert_eq!(stream.next(), Some(Ok(Item::Error("ab"))));
+	assert_eq!(stream.next(), None);
+
+	let errors: Vec<_> = Parser::new("a\nbbbbbbbb\nc").max_line_len(4).validate().map(|error| (error.line(), error.kind())).collect();
+	assert_eq!(errors, [(1, ErrorKind::MissingSeparator), (2, ErrorKind::LineTooLong)]);
+}
+
+#[test]
+fn test_multibyte_utf8() {
+	let document = "[Sécti\u{f6}n]\nκλειδί = τιμή ; σχόλιο\n;ñ\u{3000}\nké\u{3000}=\u{3000}v\u{e4}l\\\n😀\n[ü]ß\n\u{20ac}";
+	let mut parser = Parser::new(document).auto_trim(true).inline_comments(true).line_continuation(true).section_trailing(true);
+	assert_eq!(parser.nth(1), Some(Item::Section("Séctiön")));
+	assert_eq!(parser.next(), Some(Item::Property("κλειδί", Some("τιμή"))));
+	assert_eq!(parser.inline_comment(), Some("σχόλιο"));
+	assert_eq!(parser.next(), Some(Item::Comment("ñ\u{3000}")));
+	assert_eq!(parser.next(), Some(Item::Property("ké\u{3000}", Some("\u{3000}väl\\\n😀"))));
+	assert_eq!(parser.nth(1), Some(Item::Section("ü")));
+	assert_eq!(parser.trailing(), Some("ß"));
+	assert_eq!(parser.next(), Some(Item::Property("\u{20ac}", None)));
+
+	// Trimming with a unicode predicate
+	let mut parser = Parser::new("\u{3000}k\u{3000}=\u{3000}ü\u{3000}").auto_trim(true).trim_with(char::is_whitespace);
+	assert_eq!(parser.next(), Some(Item::Property("k", Some("ü"))));
+
+	// The parser slices only at ascii characters whatever the options, every slice is checked in debug builds
+	for chr in 0..0x100 {
+		let chr = chr as u8;
+		let parser = Parser::new(document).comment_char(chr).separator_char(chr);
+		parser.clone().auto_trim(true).inline_comments(true).line_continuation(true).section_trailing(true).for_each(drop);
+		parser.clone().strict(true).for_each(drop);
+		for max_line_len in 0..document.len() {
+			parser.clone().max_line_len(max_line_len).for_each(drop);
+		}
+	}
+
+	// Checked conversion of raw bytes
+	let mut parser = BytesParser::new(b"[\xc3\xbc]\nk=\xc3");
+	assert_eq!(parser.nth(1).map(Item::try_from), Some(Ok(Item::Section("ü"))));
+	assert!(matches!(parser.next().map(Item::try_from), Some(Err(_))));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_lossy() {
+	use std::borrow::Cow;
+
+	let document = b";\xff\n[Se\xc3\xa7\xe7]\nk\xc3\xab=v\xc3\xa4\xc3\nbare\n\x80\x80";
+	let items: Vec<_> = BytesParser::new(document).lossy().collect();
+	assert_eq!(items, [
+		LossyItem::Comment("\u{fffd}".into()),
+		LossyItem::SectionEnd,
+		LossyItem::Section("Se\u{e7}\u{fffd}".into()),
+		LossyItem::Property("k\u{eb}".into(), Some("v\u{e4}\u{fffd}".into())),
+		LossyItem::Property("bare".into(), None),
+		LossyItem::Property("\u{fffd}\u{fffd}".into(), None),
+		LossyItem::SectionEnd,
+	]);
+
+	// Borrowed unless a replacement was made
+	assert!(matches!(&items[3], LossyItem::Property(Cow::Borrowed(_), Some(Cow::Owned(_)))));
+	assert!(matches!(&items[4], LossyItem::Property(Cow::Borrowed(_), None)));
+
+	// Valid documents decode the same as the string parser
+	let document = "[S]\n;c\nk=v\n\nerror]";
+	assert!(BytesParser::new(document.as_bytes()).lossy().map(|item| item.to_string()).eq(Parser::new(document).map(|item| item.to_string())));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_documented() {
+	let document = ";a\n;b\nk=1\n;c\n\n;d\nl\n[S]\n;e\n[T]\n;f\n[error\nm=2\n;g\n;h";
+	let properties: Vec<_> = Parser::new(document).documented().collect();
+	assert_eq!(properties, [
+		(vec!["a", "b"], Item::Property("k", Some("1"))),
+		(vec!["d"], Item::Property("l", None)),
+		(vec![], Item::Property("m", Some("2"))),
+	]);
+
+	// An empty header reopening the global section also ends the block
+	let properties: Vec<_> = Parser::new("[S]\n;a\n[]\nk=1").empty_section(EmptySectionPolicy::Global).documented().collect();
+	assert_eq!(properties, [(vec![], Item::Property("k", Some("1")))]);
+}
+
+#[test]
+fn test_strip_array_suffix() {
+	assert_eq!(strip_array_suffix("a[]"), "a");
+	assert_eq!(strip_array_suffix("a[][]"), "a[]");
+	assert_eq!(strip_array_suffix("[]"), "");
+	assert_eq!(strip_array_suffix("a[] "), "a[] ");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_multi_map() {
+	fn values(values: &[Option<&str>]) -> Vec<Option<String>> {
+		values.iter().map(|value| value.map(String::from)).collect()
+	}
+	let document = "g=1\n[S]\nk[] = a\nk = b\nflag\nflag\n;c\n[T]\nk=c\n[]\ng=2\n[S]\nk[]=d";
+	let map = Parser::new(document).auto_trim(true).empty_section(EmptySectionPolicy::Global).multi_map();
+	let entries: Vec<_> = map.iter().map(|((section, key), values)| (section.as_deref(), key.as_str(), values.len())).collect();
+	assert_eq!(entries, [(None, "g", 2), (Some("S"), "flag", 2), (Some("S"), "k", 3), (Some("T"), "k", 1)]);
+	assert_eq!(map[&(Some("S".into()), "k".into())], values(&[Some("a"), Some("b"), Some("d")]));
+	assert_eq!(map[&(Some("S".into()), "flag".into())], values(&[None, None]));
+
+	let map = Parser::new("[S]\nKey[]=1\nkey=2").case_insensitive(true).multi_map();
+	assert_eq!(map[&(Some("s".into()), "key".into())], values(&[Some("1"), Some("2")]));
+
+	// Properties after a malformed section header belong to no section
+	let map = Parser::new("[S]\na=1\n[T\na=2").multi_map();
+	assert_eq!(map.len(), 1);
+	assert_eq!(map[&(Some("S".into()), "a".into())], values(&[Some("1")]));
+}
+
+#[test]
+fn test_next_const() {
+	fn check(parser: Parser) {
+		let mut expected = parser.clone();
+		let mut parser = parser;
+		loop {
+			let (item, next) = parser.next_const();
+			parser = next;
+			assert_eq!(item, expected.next());
+			assert_eq!((parser.line(), parser.line_number(), parser.offset()), (expected.line(), expected.line_number(), expected.offset()));
+			assert_eq!((parser.current_section(), parser.in_global_section(), parser.current_line()), (expected.current_section(), expected.in_global_section(), expected.current_line()));
+			if item.is_none() {
+				break;
+			}
+		}
+	}
+
+	// Random documents built from tricky lines and newlines, parsed with every supported option
+	const LINES: [&str; 20] = ["", " ", "\t", "[S]", "[ S ]", "[S", "[]", "[ ]", "[S]x", "k=v", " k = v ", "=", "k", "=v", ";c", "#c", "a=b;c", "a:b=c:d", "k v", " k \t v "];
+	const NEWLINES: [&str; 3] = ["\n", "\r\n", "\r"];
+	let mut seed = 0x2545f491u32;
+	let mut random = |n: usize| {
+		seed ^= seed << 13;
+		seed ^= seed >> 17;
+		seed ^= seed << 5;
+		seed as usize % n
+	};
+	for _ in 0..1000 {
+		let mut document = String::new();
+		for _ in 0..random(12) {
+			document += LINES[random(LINES.len())];
+			document += NEWLINES[random(NEWLINES.len())];
+		}
+		if random(2) == 0 {
+			document += LINES[random(LINES.len())];
+		}
+		let policy = [EmptySectionPolicy::AllowEmpty, EmptySectionPolicy::Error, EmptySectionPolicy::Global][random(3)];
+		let parser = Parser::new(&document)
+			.comment_chars([&b";"[..], b";#", b""][random(3)])
+			.separator([Separator::Char(b'='), Separator::Char(b':'), Separator::Whitespace][random(3)])
+			.auto_trim(random(2) == 0)
+			.auto_trim_start(random(2) == 0)
+			.blank_on_whitespace(random(2) == 0)
+			.split_last(random(2) == 0)
+			.empty_section(policy);
+		check(parser);
+	}
+
+	// Evaluated at compile time
+	const ITEMS: (Option<Item>, Option<Item>) = {
+		let (first, parser) = Parser::new("[S]\nk = v").auto_trim(true).next_const();
+		let (_, parser) = parser.next_const();
+		(first, parser.next_const().0)
+	};
+	assert_eq!(ITEMS, (Some(Item::SectionEnd), Some(Item::Property("k", Some("v")))));
+}
+
+#[test]
+#[should_panic(expected = "option not supported in const contexts")]
+fn test_next_const_unsupported() {
+	let _ = Parser::new("k=v").inline_comments(true).next_const();
+}
+
+#[test]
+fn test_item_ord() {
+	// Every variant pair in declaration order
+	let variants = [Item::Error("z"), Item::Section("z"), Item::SectionEnd, Item::Property("z", Some("z")), Item::Comment("z"), Item::Blank];
+	for (i, a) in variants.iter().enumerate() {
+		for (j, b) in variants.iter().enumerate() {
+			assert_eq!(a.cmp(b), i.cmp(&j), "{:?} {:?}", a, b);
+		}
+	}
+
+	// Then by the strings
+	assert!(Item::Error("a") < Item::Error("b"));
+	assert!(Item::Section("A") < Item::Section("a"));
+	assert!(Item::Section("a") < Item::Section("ab"));
+	assert!(Item::Property("a", Some("z")) < Item::Property("b", None));
+	assert!(Item::Property("a", None) < Item::Property("a", Some("")));
+	assert!(Item::Property("a", Some("1")) < Item::Property("a", Some("2")));
+	assert!(Item::Comment(" b") < Item::Comment("a"));
+
+	// The byte items order the same
+	let mut items: Vec<_> = Parser::new("k=2\n;c\n[S]\nk\nk=1\n[S\n\n").collect();
+	items.sort();
+	let mut bytes: Vec<_> = BytesParser::new(b"k=2\n;c\n[S]\nk\nk=1\n[S\n\n").collect();
+	bytes.sort();
+	assert!(bytes.into_iter().map(|item| Item::try_from(item).unwrap()).eq(items));
+}
+
+#[test]
+fn test_canonical() {
+	use std::collections::HashSet;
+	use std::hash::{BuildHasher, RandomState};
+
+	let state = RandomState::new();
+	let equal = [
+		(Item::Property("k ", Some(" v")), Item::Property("k", Some("v"))),
+		(Item::Property("\tk", None), Item::Property("k", None)),
+		(Item::Section(" S "), Item::Section("S")),
+		(Item::Comment(" c"), Item::Comment("c")),
+		(Item::Error("[x "), Item::Error("[x")),
+		(Item::Blank, Item::Blank),
+	];
+	for (a, b) in equal {
+		assert_eq!(a.canonical(), b.canonical());
+		assert_eq!(state.hash_one(a.canonical()), state.hash_one(b.canonical()));
+		assert_eq!(a.canonical().trimmed(), b);
+	}
+
+	// Values and variants still differ
+	assert_ne!(Item::Property("k", Some("")).canonical(), Item::Property("k", None).canonical());
+	assert_ne!(Item::Section("k").canonical(), Item::Comment("k").canonical());
+	assert_ne!(Item::Property("k", Some("v w")).canonical(), Item::Property("k", Some("vw")).canonical());
+	assert!(Item::Section("b").canonical() < Item::Property("a", None).canonical());
+	assert!(Item::Property(" a", Some("2")).canonical() < Item::Property("b ", Some("1")).canonical());
+
+	let document = "[S]\nk=v\nk = v\n[ S ]\n;c\n; c \nk=w";
+	let set: HashSet<_> = Parser::new(document).map(Item::canonical).collect();
+	assert_eq!(set.len(), 5);
+	assert_eq!(Item::Section(" S ").canonical().to_string(), "[S]\n");
+}
+
+#[test]
+fn test_skip_to_section() {
+	// Skipping leaves the parser in the same state as parsing the skipped items
+	fn check(parser: Parser, name: &str, ignore_case: bool) {
+		let mut skipped = parser.clone();
+		let mut expected = parser;
+		let found = skipped.skip_to_section(name);
+		let expected_found = loop {
+			match expected.next() {
+				Some(Item::Section(section)) if section == name || ignore_case && key_eq(section, name) => break true,
+				Some(_) => (),
+				None => break false,
+			}
+		};
+		assert_eq!(found, expected_found);
+		assert_eq!((skipped.line(), skipped.offset(), skipped.current_section()), (expected.line(), expected.offset(), expected.current_section()));
+		assert!(skipped.eq(expected));
+	}
+
+	let document = "a=1\r\n[A]\n;[B]\n b=[B]\n\n[ B ]\r[B]x\n[b]\nc=3\\\n[B]\n[B]\n[C]";
+	for name in ["A", "B", "b", "C", "D", "", " B "] {
+		check(Parser::new(document), name, false);
+		check(Parser::new(document).auto_trim(true), name, false);
+		check(Parser::new(document).case_insensitive(true), name, true);
+		check(Parser::new(document).comment_chars(b";["), name, false);
+		check(Parser::new(document).line_continuation(true), name, false);
+		check(Parser::new(document).strict(true), name, false);
+		check(Parser::new(document).empty_section(EmptySectionPolicy::Global), name, false);
+	}
+
+	let mut parser = Parser::new(document).auto_trim(true);
+	assert!(parser.skip_to_section("B"));
+	assert_eq!(parser.line(), 6);
+	assert!(parser.skip_to_section("b"));
+	assert_eq!(parser.next(), Some(Item::Property("c", Some("3\\"))));
+	assert!(parser.skip_to_section("B"));
+	assert_eq!(parser.line(), 10);
+	assert!(!parser.skip_to_section("A"));
+	assert_eq!(parser.next(), None);
+}
+
+#[test]
+fn test_skip_section() {
+	// Skipping leaves the parser in the same state as parsing the skipped items
+	fn check(parser: Parser) {
+		let mut parser = parser;
+		loop {
+			let mut skipped = parser.clone();
+			skipped.skip_section();
+			let mut expected = parser.clone();
+			while let Some(item) = expected.clone().next() {
+				if matches!(item, Item::SectionEnd | Item::Section(_)) || matches!(item, Item::Error(error) if error.starts_with('[')) {
+					break;
+				}
+				expected.next();
+			}
+			assert_eq!((skipped.line(), skipped.offset(), skipped.current_section()), (expected.line(), expected.offset(), expected.current_section()));
+			assert!(skipped.clone().eq(expected));
+			if parser.next().is_none() {
+				break;
+			}
+		}
+	}
+
+	let document = "a=1\r\n;[c]\n b=[B]\n\n[A]\r\nk=v\\\n[B]\n[C]x\nd\n[]\ne=5\n[D\n\n";
+	check(Parser::new(document));
+	check(Parser::new(document).auto_trim(true));
+	check(Parser::new(document).comment_chars(b";["));
+	check(Parser::new(document).line_continuation(true));
+	check(Parser::new(document).strict(true));
+	check(Parser::new(document).max_line_len(4));
+	check(Parser::new(document).empty_section(EmptySectionPolicy::Global));
+
+	let mut parser = Parser::new("[A]\nk=1\n[B]\nk=2");
+	parser.skip_section();
+	assert_eq!(parser.offset(), 0);
+	assert_eq!(parser.nth(1), Some(Item::Section("A")));
+	parser.skip_section();
+	assert_eq!(parser.line(), 2);
+	parser.skip_section();
+	assert_eq!(parser.nth(1), Some(Item::Section("B")));
+	parser.skip_section();
+	assert_eq!(parser.remainder(), "");
+	assert_eq!(parser.next(), Some(Item::SectionEnd));
+	assert_eq!(parser.next(), None);
+}
+
+#[test]
+fn test_is_exhausted() {
+	let documents = ["", "\n", "k=v", "k=v\n", "[S]", "[S]\n", "a=1\n[S]\nb=2\n\n", "[S\n", "k\n[T]x\nv"];
+	let parsers = [
+		|s| Parser::new(s),
+		|s| Parser::new(s).strict(true),
+		|s| Parser::new(s).max_line_len(2),
+		|s| Parser::new(s).empty_section(EmptySectionPolicy::Global),
+	];
+	for document in documents {
+		for parser in parsers {
+			let mut parser: Parser = parser(document);
+			loop {
+				assert_eq!(parser.is_exhausted(), parser.clone().next().is_none());
+				if parser.next().is_none() {
+					break;
+				}
+			}
+			assert!(parser.is_exhausted());
+		}
+	}
+
+	// Byte parser and the last item of the document
+	let mut parser = BytesParser::new(b"[S]\nk=v");
+	assert_eq!(parser.nth(2), Some(ByteItem::Property(b"k", Some(b"v"))));
+	assert!(!parser.is_exhausted());
+	assert_eq!(parser.next(), Some(ByteItem::SectionEnd));
+	assert!(parser.is_exhausted());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_global_section_name() {
+	let document = "a=1\n[S]\nb=2\n[DEFAULT]\nc=3\n[]\nd=4";
+	let parser = Parser::new(document).empty_section(EmptySectionPolicy::Global).global_section_name("DEFAULT");
+	let map = parser.clone().into_map();
+	let names: Vec<_> = map.keys().cloned().collect();
+	assert_eq!(names, [Some("DEFAULT".to_string()), Some("S".to_string())]);
+	let keys: Vec<_> = map[&Some("DEFAULT".to_string())].iter().map(|(key, _)| key.as_str()).collect();
+	assert_eq!(keys, ["a", "c", "d"]);
+
+	let map = parser.clone().multi_map();
+	assert!(map.contains_key(&(Some("DEFAULT".into()), "d".into())));
+	assert!(map.keys().all(|(section, _)| section.is_some()));
+
+	// Folded like the section names
+	let map = parser.case_insensitive(true).into_map();
+	assert_eq!(map[&Some("default".to_string())].len(), 3);
+
+	// The items are not affected
+	assert!(Parser::new(document).global_section_name("DEFAULT").eq(Parser::new(document)));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_to_json_value() {
+	use serde_json::json;
+
+	assert_eq!(to_json_value(""), json!({}));
+	assert_eq!(to_json_value(";c\n\n[S\n"), json!({}));
+
+	// Repeated keys across duplicate sections, empty section names
+	let document = "k=1\nk=2\nk\n[S]\nk=a\n[]\nx=y\n[S]\nk=b\nk=c";
+	assert_eq!(to_json_value(document), json!({
+		"k": ["1", "2", null],
+		"S": { "k": ["a", "b", "c"] },
+		"": { "x": "y" },
+	}));
+
+	// Sections take precedence over global properties
+	let document = "a=1\nb=2\n[a]\n[]\nb=3\na=4";
+	let parser = Parser::new(document).empty_section(EmptySectionPolicy::Global);
+	assert_eq!(parser.clone().into_json_value(), json!({ "a": {}, "b": ["2", "3"] }));
+	assert_eq!(parser.global_section_name("a").into_json_value(), json!({ "a": { "a": ["1", "4"], "b": ["2", "3"] } }));
+
+	let value = Parser::new("[S]\nKey=1\n[s]\nkey=2").case_insensitive(true).into_json_value();
+	assert_eq!(value, json!({ "s": { "key": ["1", "2"] } }));
+
+	// Properties after a malformed section header are dropped
+	assert_eq!(Parser::new("[A]\na=1\n[B\nb=2").into_json_value(), json!({ "A": { "a": "1" } }));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_to_toml_string() {
+	assert_eq!(to_toml_string(""), "");
+
+	// Section replaces the global property, duplicate sections merge, empty sections are kept
+	let document = "S=global\nk=1\n[S]\na=x\n[empty]\n[S]\nb\n[]\nk=2";
+	assert_eq!(to_toml_string(document), "k = \"1\"\n\n[S]\na = \"x\"\nb = \"\"\n\n[empty]\n\n[\"\"]\nk = \"2\"\n");
+
+	// Quoting of keys, section names and values
+	let document = "[my section]\nkey.with.dots=C:\\path\ntab=a\tb\nctl=\x01\nok_key-1=\"\"";
+	assert_eq!(to_toml_string(document), "[\"my section\"]\n\"key.with.dots\" = \"C:\\\\path\"\ntab = \"a\\tb\"\nctl = \"\\u0001\"\nok_key-1 = \"\\\"\\\"\"\n");
+	assert_eq!(to_toml_string("k=1\n=v\nk=2"), "k = [\"1\", \"2\"]\n\"\" = \"v\"\n");
+
+	// Properties after a malformed section header are dropped
+	assert_eq!(to_toml_string("[A]\na=1\n[B\nb=2"), "[A]\na = \"1\"\n");
+
+	let toml = Toml::new().infer_types(true);
+	assert_eq!(toml.to_toml_string("a=-12\nb=2.0\nc=1e3\nd=off\ne=0\nf=inf\ng=nan\nh\ni=1\ni=x"),
+		"a = -12\nb = 2.0\nc = 1000.0\nd = false\ne = 0\nf = \"inf\"\ng = \"nan\"\nh = true\ni = [1, \"x\"]\n");
+}
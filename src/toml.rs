@@ -0,0 +1,196 @@
+use core::fmt::Write;
+use alloc::{string::String, vec::Vec};
+use crate::{parse_bool, parse_float, parse_int, Item, Parser};
+
+/// Converts a document to TOML.
+///
+/// Shorthand for [`Toml::to_toml_string`] with the default options.
+///
+/// ```
+/// let document = "; Comment\nname = app\n\n[window]\nwidth = 640\n[a.b]\nk = \"quoted\"\nk = two";
+/// assert_eq!(ini_core::to_toml_string(document), "\
+/// name = \"app\"
+///
+/// [window]
+/// width = \"640\"
+///
+/// [\"a.b\"]
+/// k = [\"\\\"quoted\\\"\", \"two\"]
+/// ");
+/// ```
+#[inline]
+pub fn to_toml_string(s: &str) -> String {
+	Toml::new().to_toml_string(s)
+}
+
+/// Options for converting documents to TOML.
+///
+/// See [`to_toml_string`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Toml {
+	infer_types: bool,
+}
+
+impl Toml {
+	/// Constructs a new `Toml` instance.
+	#[inline]
+	pub const fn new() -> Toml {
+		Toml { infer_types: false }
+	}
+
+	/// Sets converting values to integers, floats and booleans.
+	///
+	/// Values are tried with [`parse_int`], [`parse_float`] and [`parse_bool`] in that order, finite floats only.
+	/// Keys without a value become `true`.
+	/// This loses how the values were written, eg. `yes` becomes `true` and `+1` becomes `1`.
+	///
+	/// The default is `false`, all values are strings and keys without a value are empty strings.
+	///
+	/// ```
+	/// let toml = ini_core::Toml::new().infer_types(true).to_toml_string("a=1\nb=1.5\nc=yes\nd=text\ne");
+	/// assert_eq!(toml, "a = 1\nb = 1.5\nc = true\nd = \"text\"\ne = true\n");
+	/// ```
+	#[must_use]
+	#[inline]
+	pub const fn infer_types(self, infer_types: bool) -> Toml {
+		Toml { infer_types }
+	}
+
+	/// Converts a document to TOML.
+	///
+	/// The document is parsed with [`auto_trim`](Parser::auto_trim) enabled, the global properties are written first followed by a table for every section:
+	///
+	/// * Section names are written as a single key, `[a.b]` becomes `["a.b"]` and not a nested table.
+	/// * Keys and section names are quoted unless they are bare keys.
+	/// * Repeated keys within a section become an array of their values in document order.
+	/// * Duplicate sections are merged, sections without properties are written as empty tables.
+	/// * A section replaces a global property with the same name.
+	/// * Comments, blank lines and errors are dropped, as are the properties after a malformed section header.
+	///
+	/// Sections and keys are written in the order they first appear.
+	pub fn to_toml_string(&self, s: &str) -> String {
+		let tables = group(s);
+		let mut out = String::new();
+		for (name, props) in &tables {
+			if let Some(name) = name {
+				if !out.is_empty() {
+					out.push('\n');
+				}
+				out.push('[');
+				write_key(&mut out, name);
+				out.push_str("]\n");
+			}
+			for (key, values) in props {
+				// Global properties replaced by a section
+				if name.is_none() && tables.iter().any(|table| table.0 == Some(key)) {
+					continue;
+				}
+				write_key(&mut out, key);
+				out.push_str(" = ");
+				if let [value] = values[..] {
+					self.write_value(&mut out, value);
+				}
+				else {
+					out.push('[');
+					for (i, &value) in values.iter().enumerate() {
+						if i > 0 {
+							out.push_str(", ");
+						}
+						self.write_value(&mut out, value);
+					}
+					out.push(']');
+				}
+				out.push('\n');
+			}
+		}
+		return out;
+	}
+
+	fn write_value(&self, out: &mut String, value: Option<&str>) {
+		if self.infer_types {
+			let Some(value) = value else {
+				out.push_str("true");
+				return;
+			};
+			if let Some(int) = parse_int(value) {
+				let _ = write!(out, "{}", int);
+				return;
+			}
+			if let Some(float) = parse_float(value).filter(|float| float.is_finite()) {
+				// Debug formatting keeps the fraction of whole numbers
+				let _ = write!(out, "{:?}", float);
+				return;
+			}
+			if let Some(bool) = parse_bool(value) {
+				let _ = write!(out, "{}", bool);
+				return;
+			}
+		}
+		write_string(out, value.unwrap_or(""));
+	}
+}
+
+// Sections with their properties in document order, the global section is first
+type Tables<'a> = Vec<(Option<&'a str>, Vec<(&'a str, Vec<Option<&'a str>>)>)>;
+
+fn group(s: &str) -> Tables<'_> {
+	let mut tables: Tables = Vec::new();
+	tables.push((None, Vec::new()));
+	let mut current = 0;
+	let mut parser = Parser::new(s).auto_trim(true);
+	while let Some(item) = parser.next() {
+		match item {
+			Item::Section(name) => {
+				current = match tables.iter().position(|table| table.0 == Some(name)) {
+					Some(index) => index,
+					None => {
+						tables.push((Some(name), Vec::new()));
+						tables.len() - 1
+					},
+				};
+			},
+			Item::SectionEnd if parser.in_global_section() => current = 0,
+			// Properties after a malformed section header belong to no section
+			Item::Property(..) if parser.lookup_section().is_none() => (),
+			Item::Property(key, value) => {
+				let props = &mut tables[current].1;
+				match props.iter_mut().find(|prop| prop.0 == key) {
+					Some(prop) => prop.1.push(value),
+					None => props.push((key, alloc::vec![value])),
+				}
+			},
+			_ => (),
+		}
+	}
+	return tables;
+}
+
+fn write_key(out: &mut String, key: &str) {
+	if key.len() > 0 && key.bytes().all(|chr| chr.is_ascii_alphanumeric() || chr == b'_' || chr == b'-') {
+		out.push_str(key);
+	}
+	else {
+		write_string(out, key);
+	}
+}
+
+// Writes a basic string
+fn write_string(out: &mut String, s: &str) {
+	out.push('"');
+	for chr in s.chars() {
+		match chr {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\u{8}' => out.push_str("\\b"),
+			'\t' => out.push_str("\\t"),
+			'\n' => out.push_str("\\n"),
+			'\u{c}' => out.push_str("\\f"),
+			'\r' => out.push_str("\\r"),
+			'\0'..='\u{1f}' | '\u{7f}' => {
+				let _ = write!(out, "\\u{:04X}", chr as u32);
+			},
+			_ => out.push(chr),
+		}
+	}
+	out.push('"');
+}
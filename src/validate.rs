@@ -0,0 +1,86 @@
+use alloc::vec::Vec;
+use crate::{trim, Duplicates, ErrorKind, Item, ParseError, Parser};
+
+/// Iterator over every malformed line of a document.
+///
+/// See [`Parser::validate`].
+#[derive(Clone, Debug)]
+pub struct Validator<'a> {
+	duplicates: Duplicates<'a>,
+	duplicate_keys: bool,
+}
+
+impl<'a> Validator<'a> {
+	/// Sets reporting repeated keys within a section as [`ErrorKind::DuplicateKey`].
+	///
+	/// Keys are compared like [`Parser::duplicate_keys`], ignoring ascii case if the parser is [`case_insensitive`](Parser::case_insensitive).
+	///
+	/// The default is `false`.
+	#[must_use]
+	#[inline]
+	pub fn duplicate_keys(self, duplicate_keys: bool) -> Validator<'a> {
+		Validator { duplicate_keys, ..self }
+	}
+}
+
+impl<'a> Iterator for Validator<'a> {
+	type Item = ParseError;
+
+	fn next(&mut self) -> Option<ParseError> {
+		loop {
+			let item = self.duplicates.parser().next()?;
+			let kind = match item {
+				// Overlong lines stop parsing
+				Item::Error(_) if self.duplicates.parser().error().is_some() => return self.duplicates.parser().error(),
+				Item::Error(error) => self.duplicates.parser().bytes.error_kind(error.as_bytes()),
+				// Lines with only whitespace are not errors
+				Item::Property(key, None) if !trim(key).is_empty() => ErrorKind::MissingSeparator,
+				_ if self.duplicate_keys && self.duplicates.is_duplicate(item) => ErrorKind::DuplicateKey,
+				_ => continue,
+			};
+			let line = self.duplicates.parser().line_number();
+			return Some(ParseError { line, kind });
+		}
+	}
+}
+
+impl<'a> core::iter::FusedIterator for Validator<'a> {}
+
+impl<'a> Parser<'a> {
+	/// Reports every malformed line of the document.
+	///
+	/// Reports the same errors as [`strict`](Parser::strict) mode with the line number and kind, but keeps going after the first error.
+	/// Enable [`duplicate_keys`](Validator::duplicate_keys) to also report repeated keys.
+	///
+	/// ```
+	/// use ini_core::ErrorKind;
+	///
+	/// let document = "[A\nk=1\nflag\n[B]x\n[C]\nk=2\nk=3";
+	/// let errors: Vec<_> = ini_core::Parser::new(document).validate().duplicate_keys(true).collect();
+	/// let errors: Vec<_> = errors.iter().map(|error| (error.line(), error.kind())).collect();
+	/// assert_eq!(errors, [
+	/// 	(1, ErrorKind::UnterminatedSection),
+	/// 	(3, ErrorKind::MissingSeparator),
+	/// 	(4, ErrorKind::TrailingAfterSection),
+	/// 	(7, ErrorKind::DuplicateKey),
+	/// ]);
+	/// ```
+	#[inline]
+	pub fn validate(self) -> Validator<'a> {
+		let ignore_case = self.bytes.is_case_insensitive();
+		Validator { duplicates: self.duplicate_keys().ignore_case(ignore_case), duplicate_keys: false }
+	}
+}
+
+/// Reports every malformed line of the document.
+///
+/// Shorthand for [`Parser::validate`] with the default options, duplicate keys are not reported.
+///
+/// ```
+/// let errors = ini_core::validate("[Section\nKey=Value\nflag");
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[1].to_string(), "line 3: missing separator");
+/// ```
+pub fn validate(s: &str) -> Vec<ParseError> {
+	Parser::new(s).validate().collect()
+}
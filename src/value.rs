@@ -0,0 +1,107 @@
+use alloc::borrow::Cow;
+use crate::{join_continuations, trim, unescape};
+
+/// Processes property values without allocating when nothing changes.
+///
+/// Applies in order: [`trim`], [`join_continuations`], [`unescape`] and an optional custom step, eg. [`expand_vars`](crate::expand_vars).
+/// Every step borrows its input when it has nothing to do, the result is [`Cow::Borrowed`] unless a step changed the value.
+///
+/// All steps are disabled by default.
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// let pipeline = ini_core::ValuePipeline::new().trim(true).unescape(true);
+/// assert!(matches!(pipeline.apply(" plain "), Cow::Borrowed("plain")));
+/// assert!(matches!(pipeline.apply(r" a\tb "), Cow::Owned(s) if s == "a\tb"));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ValuePipeline {
+	trim: bool,
+	join_continuations: bool,
+	unescape: bool,
+}
+
+impl ValuePipeline {
+	/// Constructs a new `ValuePipeline` instance.
+	#[inline]
+	pub const fn new() -> ValuePipeline {
+		ValuePipeline { trim: false, join_continuations: false, unescape: false }
+	}
+
+	/// Sets trimming ascii whitespace, see [`trim`].
+	#[must_use]
+	#[inline]
+	pub const fn trim(self, trim: bool) -> ValuePipeline {
+		ValuePipeline { trim, ..self }
+	}
+
+	/// Sets joining continued lines, see [`join_continuations`].
+	#[must_use]
+	#[inline]
+	pub const fn join_continuations(self, join_continuations: bool) -> ValuePipeline {
+		ValuePipeline { join_continuations, ..self }
+	}
+
+	/// Sets decoding escape sequences, see [`unescape`].
+	#[must_use]
+	#[inline]
+	pub const fn unescape(self, unescape: bool) -> ValuePipeline {
+		ValuePipeline { unescape, ..self }
+	}
+
+	/// Processes the value.
+	pub fn apply<'a>(&self, value: &'a str) -> Cow<'a, str> {
+		let mut value = Cow::Borrowed(value);
+		if self.trim {
+			value = then(value, trim_cow);
+		}
+		if self.join_continuations {
+			value = then(value, join_continuations);
+		}
+		if self.unescape {
+			value = then(value, unescape);
+		}
+		return value;
+	}
+
+	/// Processes the value followed by a custom step.
+	///
+	/// The step should return [`Cow::Borrowed`] if it leaves the value unchanged.
+	/// Pass a function rather than a closure stored in a variable, closures don't infer the lifetime of the returned `Cow`.
+	///
+	/// ```
+	/// use std::borrow::Cow;
+	/// use ini_core::{expand_vars, MissingVar, ValuePipeline};
+	///
+	/// fn expand(s: &str) -> Cow<'_, str> {
+	/// 	let lookup = |name: &str| (name == "HOME").then(|| String::from("/home/user"));
+	/// 	expand_vars(s, lookup, MissingVar::Keep).unwrap()
+	/// }
+	///
+	/// let pipeline = ValuePipeline::new().trim(true);
+	/// assert!(matches!(pipeline.apply_with(" /tmp ", expand), Cow::Borrowed("/tmp")));
+	/// assert_eq!(pipeline.apply_with(" $HOME/data ", expand), "/home/user/data");
+	/// ```
+	pub fn apply_with<'a, F>(&self, value: &'a str, f: F) -> Cow<'a, str> where F: for<'b> FnOnce(&'b str) -> Cow<'b, str> {
+		then(self.apply(value), f)
+	}
+}
+
+fn trim_cow(s: &str) -> Cow<'_, str> {
+	Cow::Borrowed(trim(s))
+}
+
+// Applies the step, reusing the owned string if the step leaves it unchanged
+fn then<'a, F>(value: Cow<'a, str>, f: F) -> Cow<'a, str> where F: for<'b> FnOnce(&'b str) -> Cow<'b, str> {
+	match value {
+		Cow::Borrowed(s) => f(s),
+		Cow::Owned(s) => {
+			let result = match f(&s) {
+				Cow::Borrowed(t) if t.len() == s.len() => None,
+				t => Some(t.into_owned()),
+			};
+			Cow::Owned(result.unwrap_or(s))
+		},
+	}
+}
@@ -0,0 +1,102 @@
+use core::fmt;
+use alloc::{borrow::Cow, string::String};
+
+/// What [`expand_vars`] does with undefined variables.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum MissingVar {
+	/// Keeps the placeholder as is.
+	#[default]
+	Keep,
+	/// Replaces the placeholder with an empty string.
+	Empty,
+	/// Returns an [`UndefinedVar`] error.
+	Error,
+}
+
+/// Error returned by [`expand_vars`] for an undefined variable.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct UndefinedVar<'a> {
+	name: &'a str,
+}
+
+impl<'a> UndefinedVar<'a> {
+	/// Returns the name of the undefined variable.
+	#[inline]
+	pub const fn name(&self) -> &'a str {
+		self.name
+	}
+}
+
+impl<'a> fmt::Display for UndefinedVar<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "undefined variable: {}", self.name)
+	}
+}
+
+impl<'a> core::error::Error for UndefinedVar<'a> {}
+
+// Length of the variable name at the start of the string
+fn name_len(s: &str) -> usize {
+	let bytes = s.as_bytes();
+	if bytes.first().is_none_or(u8::is_ascii_digit) {
+		return 0;
+	}
+	bytes.iter().take_while(|&&chr| chr.is_ascii_alphanumeric() || chr == b'_').count()
+}
+
+/// Expands `${NAME}` and `$NAME` placeholders in the string.
+///
+/// Variables are resolved with the `lookup` function, eg. `|name| std::env::var(name).ok()`.
+/// Undefined variables are handled according to `missing`.
+/// `$$` is a literal `$`, a `$` not followed by a name or `{` is kept as is.
+/// Returns the string unchanged if it contains no `$`.
+///
+/// ```
+/// use ini_core::{expand_vars, MissingVar};
+///
+/// let lookup = |name: &str| (name == "HOME").then(|| String::from("/home/user"));
+/// assert_eq!(expand_vars("${HOME}/data", lookup, MissingVar::Keep).unwrap(), "/home/user/data");
+/// assert_eq!(expand_vars("$HOME/$$5/$USER", lookup, MissingVar::Keep).unwrap(), "/home/user/$5/$USER");
+/// assert_eq!(expand_vars("$HOME/$USER", lookup, MissingVar::Empty).unwrap(), "/home/user/");
+/// assert_eq!(expand_vars("$HOME/$USER", lookup, MissingVar::Error).unwrap_err().name(), "USER");
+/// ```
+pub fn expand_vars<'a, F: Fn(&str) -> Option<String>>(s: &'a str, lookup: F, missing: MissingVar) -> Result<Cow<'a, str>, UndefinedVar<'a>> {
+	if !s.contains('$') {
+		return Ok(Cow::Borrowed(s));
+	}
+	let mut result = String::with_capacity(s.len());
+	let mut rest = s;
+	while let Some(i) = rest.find('$') {
+		result.push_str(&rest[..i]);
+		let after = &rest[i + 1..];
+		if let Some(after) = after.strip_prefix('$') {
+			result.push('$');
+			rest = after;
+			continue;
+		}
+		// Find the name and the length of the placeholder
+		let placeholder = match after.strip_prefix('{') {
+			Some(braced) => braced.find('}').map(|end| (&braced[..end], end + 3)),
+			None => Some((&after[..name_len(after)], name_len(after) + 1)),
+		};
+		let (name, len) = match placeholder {
+			Some((name, len)) if !name.is_empty() => (name, len),
+			_ => {
+				result.push('$');
+				rest = after;
+				continue;
+			},
+		};
+		match lookup(name) {
+			Some(value) => result.push_str(&value),
+			None => match missing {
+				MissingVar::Keep => result.push_str(&rest[i..i + len]),
+				MissingVar::Empty => (),
+				MissingVar::Error => return Err(UndefinedVar { name }),
+			},
+		}
+		rest = &rest[i + len..];
+	}
+	result.push_str(rest);
+	Ok(Cow::Owned(result))
+}
@@ -0,0 +1,74 @@
+use core::ops::ControlFlow;
+use crate::Parser;
+
+/// Callbacks for the items of a document.
+///
+/// Every callback defaults to continuing, implement the ones of interest.
+/// Return [`ControlFlow::Break`] to stop parsing early.
+///
+/// See [`parse_with`] and [`Parser::visit`].
+pub trait Visitor<'a> {
+	/// Called for [`Item::Error`](crate::Item::Error).
+	#[inline]
+	fn error(&mut self, error: &'a str) -> ControlFlow<()> {
+		let _ = error;
+		ControlFlow::Continue(())
+	}
+
+	/// Called for [`Item::Section`](crate::Item::Section).
+	#[inline]
+	fn section(&mut self, name: &'a str) -> ControlFlow<()> {
+		let _ = name;
+		ControlFlow::Continue(())
+	}
+
+	/// Called for [`Item::SectionEnd`](crate::Item::SectionEnd).
+	#[inline]
+	fn section_end(&mut self) -> ControlFlow<()> {
+		ControlFlow::Continue(())
+	}
+
+	/// Called for [`Item::Property`](crate::Item::Property).
+	#[inline]
+	fn property(&mut self, key: &'a str, value: Option<&'a str>) -> ControlFlow<()> {
+		let _ = (key, value);
+		ControlFlow::Continue(())
+	}
+
+	/// Called for [`Item::Comment`](crate::Item::Comment).
+	#[inline]
+	fn comment(&mut self, comment: &'a str) -> ControlFlow<()> {
+		let _ = comment;
+		ControlFlow::Continue(())
+	}
+
+	/// Called for [`Item::Blank`](crate::Item::Blank).
+	#[inline]
+	fn blank(&mut self) -> ControlFlow<()> {
+		ControlFlow::Continue(())
+	}
+}
+
+/// Parses the document calling the visitor for every item.
+///
+/// Shorthand for [`Parser::visit`] with the default options.
+///
+/// ```
+/// use core::ops::ControlFlow;
+///
+/// struct Keys(Vec<String>);
+/// impl<'a> ini_core::Visitor<'a> for Keys {
+/// 	fn property(&mut self, key: &'a str, _value: Option<&'a str>) -> ControlFlow<()> {
+/// 		self.0.push(key.to_string());
+/// 		ControlFlow::Continue(())
+/// 	}
+/// }
+///
+/// let mut keys = Keys(Vec::new());
+/// let _ = ini_core::parse_with("a=1\n[Section]\nb=2", &mut keys);
+/// assert_eq!(keys.0, ["a", "b"]);
+/// ```
+#[inline]
+pub fn parse_with<'a, V: Visitor<'a>>(s: &'a str, visitor: &mut V) -> ControlFlow<()> {
+	Parser::new(s).visit(visitor)
+}
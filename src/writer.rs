@@ -0,0 +1,225 @@
+use core::fmt;
+use crate::{escape, Item, Newline, WriteError};
+
+/// Ini writer.
+///
+/// Writes well-formed ini documents which parse back to the same items.
+/// Every line is terminated with the configured [`Newline`].
+///
+/// Strings are validated and rejected with a [`WriteError`] if they would corrupt the output.
+/// Enable [`escape`](Writer::escape) to escape newlines and other special characters instead.
+///
+/// ```
+/// let mut writer = ini_core::Writer::new(String::new()).comment_char(b'#');
+/// writer.comment("Settings").unwrap();
+/// writer.section("Section").unwrap();
+/// writer.property("Key", Some("Value")).unwrap();
+/// writer.blank().unwrap();
+/// assert_eq!(writer.finish(), "#Settings\n[Section]\nKey=Value\n\n");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Writer<W> {
+	inner: W,
+	comment_char: u8,
+	newline: Option<Newline>,
+	escape: bool,
+	strict: bool,
+	in_section: bool,
+}
+
+impl<W: fmt::Write> Writer<W> {
+	/// Constructs a new `Writer` instance.
+	#[inline]
+	pub const fn new(inner: W) -> Writer<W> {
+		Writer { inner, comment_char: b';', newline: Some(Newline::Lf), escape: false, strict: false, in_section: false }
+	}
+
+	/// Sets the comment character, eg. `b'#'`.
+	///
+	/// The default is `b';'`.
+	#[must_use]
+	#[inline]
+	pub fn comment_char(self, chr: u8) -> Writer<W> {
+		// Mask off high bit to ensure we don't corrupt utf8 strings
+		let comment_char = chr & 0x7f;
+		Writer { comment_char, ..self }
+	}
+
+	/// Sets the newline style.
+	///
+	/// The default is [`Newline::Lf`].
+	#[must_use]
+	#[inline]
+	pub fn newline(self, newline: Newline) -> Writer<W> {
+		Writer { newline: Some(newline), ..self }
+	}
+
+	/// Sets escaping special characters the same way as [`Item::display_escaped`].
+	///
+	/// The default is `false`.
+	#[must_use]
+	#[inline]
+	pub fn escape(self, escape: bool) -> Writer<W> {
+		Writer { escape, ..self }
+	}
+
+	/// Sets rejecting properties written before any section.
+	///
+	/// The default is `false`.
+	#[must_use]
+	#[inline]
+	pub fn strict(self, strict: bool) -> Writer<W> {
+		Writer { strict, ..self }
+	}
+
+	/// Writes a section header.
+	pub fn section(&mut self, name: &str) -> Result<(), WriteError> {
+		self.validate(&Item::Section(name))?;
+		self.inner.write_char('[')?;
+		self.write_str(name)?;
+		self.inner.write_char(']')?;
+		self.in_section = true;
+		self.end_line()
+	}
+
+	/// Writes a property.
+	pub fn property(&mut self, key: &str, value: Option<&str>) -> Result<(), WriteError> {
+		if self.strict && !self.in_section {
+			return Err(WriteError::NoSection);
+		}
+		if is_invalid_key(key, value, self.comment_char) {
+			return Err(WriteError::InvalidKey);
+		}
+		self.validate(&Item::Property(key, value))?;
+		self.write_str(key)?;
+		if let Some(value) = value {
+			self.inner.write_char('=')?;
+			self.write_str(value)?;
+		}
+		self.end_line()
+	}
+
+	/// Writes a comment.
+	pub fn comment(&mut self, comment: &str) -> Result<(), WriteError> {
+		self.validate(&Item::Comment(comment))?;
+		self.inner.write_char(self.comment_char as char)?;
+		self.write_str(comment)?;
+		self.end_line()
+	}
+
+	/// Writes a blank line.
+	pub fn blank(&mut self) -> Result<(), WriteError> {
+		self.end_line()
+	}
+
+	/// Writes an item.
+	///
+	/// [`Item::Error`] writes its text, escaped if [`escape`](Writer::escape) is enabled, and [`Item::SectionEnd`] writes nothing.
+	/// The text of an error is not checked to parse back as an error, eg. `Item::Error("k=v")` parses back as a property.
+	pub fn item(&mut self, item: Item) -> Result<(), WriteError> {
+		match item {
+			Item::Error(error) => {
+				self.validate(&item)?;
+				self.write_str(error)?;
+				self.end_line()
+			},
+			Item::Section(name) => self.section(name),
+			Item::SectionEnd => Ok(()),
+			Item::Property(key, value) => self.property(key, value),
+			Item::Comment(comment) => self.comment(comment),
+			Item::Blank => self.blank(),
+		}
+	}
+
+	/// Writes an item ending its line with the given newline instead of the configured one.
+	///
+	/// Nothing is written after the item if the newline is `None`.
+	/// Use with [`Parser::next_with_newline`](crate::Parser::next_with_newline) to reproduce the newlines of a document.
+	///
+	/// ```
+	/// let document = "[Section]\r\nKey=Value\nFlag";
+	/// let mut parser = ini_core::Parser::new(document);
+	/// let mut writer = ini_core::Writer::new(String::new());
+	/// while let Some((item, newline)) = parser.next_with_newline() {
+	/// 	writer.item_with_newline(item, newline).unwrap();
+	/// }
+	/// assert_eq!(writer.finish(), document);
+	/// ```
+	pub fn item_with_newline(&mut self, item: Item, newline: Option<Newline>) -> Result<(), WriteError> {
+		let configured = self.newline;
+		self.newline = newline;
+		let result = self.item(item);
+		self.newline = configured;
+		result
+	}
+
+	/// Returns a reference to the underlying writer.
+	#[inline]
+	pub fn get_ref(&self) -> &W {
+		&self.inner
+	}
+
+	/// Finishes writing and returns the underlying writer.
+	#[inline]
+	pub fn finish(self) -> W {
+		self.inner
+	}
+
+	fn validate(&self, item: &Item) -> Result<(), WriteError> {
+		if !self.escape {
+			return item.validate();
+		}
+		// The parser splits on the first `=` even if escaped, only newlines are taken care of
+		match item {
+			&Item::Section(section) if section.contains(']') => Err(WriteError::SectionBracket),
+			&Item::Property(key, _) if key.contains('=') => Err(WriteError::KeySeparator),
+			_ => Ok(()),
+		}
+	}
+
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		if self.escape {
+			escape::write_escaped(&mut self.inner, s)
+		}
+		else {
+			self.inner.write_str(s)
+		}
+	}
+
+	fn end_line(&mut self) -> Result<(), WriteError> {
+		if let Some(newline) = self.newline {
+			self.inner.write_str(newline.as_str())?;
+		}
+		Ok(())
+	}
+}
+
+// Keys which parse back as another item: an empty key without a value is a blank line, a key starting with `[` or the comment character is a section or comment
+pub(crate) fn is_invalid_key(key: &str, value: Option<&str>, comment_char: u8) -> bool {
+	match key.as_bytes().first() {
+		None => value.is_none(),
+		Some(&chr) => chr == b'[' || chr == comment_char,
+	}
+}
+
+/// Writes the items to a string.
+///
+/// Every item is written with [`Writer::item`], the result parses back to the same items given the same comment character and parser options.
+/// [`Item::Error`] is the exception: its text is written unchecked and parses back as whatever line it is, an error returned by the parser parses back as the same error.
+/// The [`Item::SectionEnd`] pseudo items are written as nothing and are reinserted by the parser.
+///
+/// Returns an error if an item cannot be written without changing how it parses, see [`Item::write_checked`].
+///
+/// ```
+/// let document = ";comment\n[Section]\nKey=Value\n\nFlag\n";
+/// let items: Vec<_> = ini_core::Parser::new(document).collect();
+/// assert_eq!(ini_core::write_items(items).unwrap(), document);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn write_items<'a, I: IntoIterator<Item = Item<'a>>>(items: I) -> Result<alloc::string::String, WriteError> {
+	let mut writer = Writer::new(alloc::string::String::new());
+	for item in items {
+		writer.item(item)?;
+	}
+	Ok(writer.finish())
+}